@@ -0,0 +1,258 @@
+use anyhow::Context;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+
+use crate::details::Penalty;
+use crate::events::{self, TimelineEvent, TimelineKind};
+use crate::{permissions, GameResult, GameTime};
+
+#[derive(Serialize)]
+struct MatchArchive<'a> {
+    my_team: &'a str,
+    opponent_team: &'a str,
+    final_score: (u64, u64),
+    period_scores: &'a [(u64, u64)],
+    /// Penalty minutes of my team and of the opponent
+    penalty_minutes: (u64, u64),
+    penalties: &'a [Penalty],
+    timeline: Vec<TimelineEvent>,
+    samples: &'a [GameResult],
+}
+
+/// Final result of an archived match.
+#[derive(Debug, Deserialize)]
+pub struct ArchivedMatch {
+    pub my_team: String,
+    pub opponent_team: String,
+    pub final_score: (u64, u64),
+}
+
+/// Collects all samples of a running match and writes them as one archive file once it is over.
+pub struct Archiver {
+    directory: PathBuf,
+    samples: Vec<GameResult>,
+}
+
+impl Archiver {
+    pub fn new(directory: PathBuf) -> Self {
+        Self {
+            directory,
+            samples: Vec::new(),
+        }
+    }
+
+    /// Record a sample; returns path of the archive if the sample finished the match.
+    pub fn record(&mut self, result: &GameResult) -> anyhow::Result<Option<PathBuf>> {
+        if self
+            .samples
+            .first()
+            .is_some_and(|first| first.opponent_team != result.opponent_team)
+        {
+            self.samples.clear();
+        }
+
+        match result.game_time {
+            GameTime::Playing(_) | GameTime::BreakAfter(_) => {
+                self.samples.push(result.clone());
+                Ok(None)
+            }
+            GameTime::Played if !self.samples.is_empty() => {
+                self.samples.push(result.clone());
+                let path = self.write()?;
+                self.samples.clear();
+                Ok(Some(path))
+            }
+            GameTime::Played | GameTime::WillBePlayed(_) => {
+                self.samples.clear();
+                Ok(None)
+            }
+        }
+    }
+
+    fn write(&self) -> anyhow::Result<PathBuf> {
+        let path = archive_path(&self.directory, &self.samples)?;
+        write_archive(&path, &self.samples, timeline(&self.samples))?;
+        Ok(path)
+    }
+}
+
+/// Archive a match known only by its final result, e.g. backfilled from the results page;
+/// `None` when the match is archived already.
+pub fn import(directory: &Path, result: &GameResult) -> anyhow::Result<Option<PathBuf>> {
+    let samples = std::slice::from_ref(result);
+    let path = archive_path(directory, samples)?;
+    if path.exists() {
+        return Ok(None);
+    }
+    // the course of the match is unknown
+    let timeline = vec![events::event(TimelineKind::Finished, result)];
+    write_archive(&path, samples, timeline)?;
+    Ok(Some(path))
+}
+
+/// All matches of the archive, oldest first.
+pub fn history(directory: &Path) -> anyhow::Result<Vec<(NaiveDate, ArchivedMatch)>> {
+    let mut files: Vec<_> = fs::read_dir(directory)
+        .with_context(|| format!("cannot read match archive {}", directory.display()))?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<_, _>>()?;
+    files.retain(|file| {
+        file.extension()
+            .is_some_and(|extension| extension == "json")
+    });
+    files.sort();
+
+    let mut matches = Vec::new();
+    for file in files {
+        let Some(day) = file_day(&file) else {
+            continue;
+        };
+        let archived: ArchivedMatch = serde_json::from_reader(File::open(&file)?)
+            .with_context(|| format!("{} is not a match archive", file.display()))?;
+        matches.push((day, archived));
+    }
+    Ok(matches)
+}
+
+/// Day of the match the archive file is named after.
+fn file_day(path: &Path) -> Option<NaiveDate> {
+    let name = path.file_name()?.to_str()?;
+    NaiveDate::parse_from_str(name.get(..10)?, "%Y-%m-%d").ok()
+}
+
+/// Archive file named after the day of the match and the opponent.
+fn archive_path(directory: &Path, samples: &[GameResult]) -> anyhow::Result<PathBuf> {
+    let first = samples.first().context("no samples to archive")?;
+    let last = samples.last().context("no samples to archive")?;
+
+    let opponent: String = last
+        .opponent_team
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '-' })
+        .collect();
+    permissions::create_dir_all(directory)?;
+    Ok(directory.join(format!(
+        "{}-{opponent}.json",
+        first.generated.format("%Y-%m-%d")
+    )))
+}
+
+fn write_archive(
+    path: &Path,
+    samples: &[GameResult],
+    timeline: Vec<TimelineEvent>,
+) -> anyhow::Result<()> {
+    let last = samples.last().context("no samples to archive")?;
+    let archive = MatchArchive {
+        my_team: &last.my_team,
+        opponent_team: &last.opponent_team,
+        final_score: (last.my_team_score, last.opponent_team_score),
+        period_scores: &last.period_scores,
+        penalty_minutes: last
+            .penalties
+            .iter()
+            .fold((0, 0), |(my, opponent), penalty| {
+                if penalty.my_team {
+                    (my + penalty.duration, opponent)
+                } else {
+                    (my, opponent + penalty.duration)
+                }
+            }),
+        penalties: &last.penalties,
+        timeline,
+        samples,
+    };
+    serde_json::to_writer_pretty(permissions::create(path)?, &archive)?;
+    Ok(())
+}
+
+fn timeline(samples: &[GameResult]) -> Vec<TimelineEvent> {
+    let mut events = Vec::new();
+    if let Some(first) = samples.first() {
+        events.push(events::event(TimelineKind::Started, first));
+    }
+    for pair in samples.windows(2) {
+        events.extend(events::between(&pair[0], &pair[1]));
+    }
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Periods;
+    use chrono::Local;
+
+    fn sample(my_team_score: u64, opponent_team_score: u64, game_time: GameTime) -> GameResult {
+        GameResult {
+            my_team: "Sparta Praha".to_string(),
+            my_team_score,
+            opponent_team: "Kometa Brno".to_string(),
+            opponent_team_score,
+            period_scores: Vec::new(),
+            goals: Vec::new(),
+            penalties: Vec::new(),
+            stats: Vec::new(),
+            broadcast: Vec::new(),
+            opponent_form: None,
+            series_score: None,
+            series_best_of: None,
+            timeline: Vec::new(),
+            estimated_time_remaining: None,
+            estimated_end_at: None,
+            started_at: None,
+            elapsed_minutes: None,
+            last_goal_at: None,
+            minutes_since_last_goal: None,
+            outcome: None,
+            goal_difference: None,
+            is_leading: None,
+            clock: None,
+            quality: None,
+            stale_since: None,
+            served_by: None,
+            game_time,
+            periods: Periods::HOCKEY,
+            generated: Local::now(),
+        }
+    }
+
+    #[test]
+    fn test_file_day() {
+        assert_eq!(
+            file_day(Path::new("archive/2025-03-01-Kometa-Brno.json")),
+            NaiveDate::from_ymd_opt(2025, 3, 1)
+        );
+        assert_eq!(file_day(Path::new("archive/notes.json")), None);
+    }
+
+    #[test]
+    fn test_timeline() {
+        let samples = [
+            sample(0, 0, GameTime::Playing(3)),
+            sample(2, 1, GameTime::Playing(15)),
+            sample(2, 1, GameTime::BreakAfter(20)),
+            sample(2, 1, GameTime::Playing(21)),
+            sample(3, 1, GameTime::Played),
+        ];
+        let kinds: Vec<_> = timeline(&samples)
+            .into_iter()
+            .map(|event| format!("{:?}", event.kind))
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                "Started",
+                "GoalFor",
+                "GoalFor",
+                "GoalAgainst",
+                "Break",
+                "Resumed",
+                "GoalFor",
+                "Finished"
+            ]
+        );
+    }
+}
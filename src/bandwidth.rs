@@ -0,0 +1,146 @@
+//! Approximate bytes transferred by the page loads this month, and the monthly cap after which
+//! the crawler refreshes less often.
+
+use chrono::Local;
+use fantoccini::Client;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tracing::{debug, warn};
+
+/// Bytes of the loaded page and its resources as far as the browser tells them; resources of
+/// other origins without `Timing-Allow-Origin` count as nothing.
+const TRANSFER_SIZE_SCRIPT: &str = r#"
+    return performance.getEntries()
+        .filter(entry => entry.transferSize !== undefined)
+        .reduce((total, entry) => total + entry.transferSize, 0);
+"#;
+
+#[derive(Default, Deserialize, Serialize)]
+struct Usage {
+    /// `YYYY-MM` of the counted bytes
+    month: String,
+    bytes: u64,
+}
+
+struct Accounting {
+    usage: Usage,
+    cap: Option<u64>,
+    capped_refresh: Duration,
+    path: Option<PathBuf>,
+}
+
+impl Accounting {
+    fn add(&mut self, bytes: u64, month: String) {
+        if self.usage.month != month {
+            self.usage = Usage { month, bytes: 0 };
+        }
+        let was_capped = self.capped();
+        self.usage.bytes += bytes;
+        if !was_capped && self.capped() {
+            warn!(
+                "monthly bandwidth cap reached with {} MB, refreshing every {:?}",
+                self.usage.bytes / 1_000_000,
+                self.capped_refresh
+            );
+        }
+    }
+
+    fn capped(&self) -> bool {
+        self.cap.is_some_and(|cap| self.usage.bytes >= cap)
+    }
+}
+
+static ACCOUNTING: Mutex<Option<Accounting>> = Mutex::new(None);
+
+fn this_month() -> String {
+    Local::now().format("%Y-%m").to_string()
+}
+
+/// Count the transferred bytes, kept across restarts in the file if any; once the monthly
+/// cap is reached, refresh at most every `capped_refresh`.
+pub fn init(cap: Option<u64>, capped_refresh: Duration, path: Option<&Path>) {
+    let usage = path
+        .and_then(|path| fs::read(path).ok())
+        .and_then(|content| serde_json::from_slice::<Usage>(&content).ok())
+        .filter(|usage| usage.month == this_month())
+        .unwrap_or_default();
+    *ACCOUNTING.lock().unwrap() = Some(Accounting {
+        usage,
+        cap,
+        capped_refresh,
+        path: path.map(Path::to_path_buf),
+    });
+}
+
+/// Count bytes transferred outside of the browser.
+pub fn record(bytes: u64) {
+    if let Some(accounting) = ACCOUNTING.lock().unwrap().as_mut() {
+        accounting.add(bytes, this_month());
+    }
+}
+
+/// Count the bytes of the page just loaded by the browser.
+pub async fn measure(client: &mut Client) {
+    if ACCOUNTING.lock().unwrap().is_none() {
+        return;
+    }
+    match client.execute(TRANSFER_SIZE_SCRIPT, Vec::new()).await {
+        Ok(bytes) => record(bytes.as_f64().unwrap_or_default() as u64),
+        Err(error) => debug!("cannot measure the transferred bytes: {error}"),
+    }
+}
+
+/// Bytes transferred this month, when counted.
+pub fn used() -> Option<u64> {
+    ACCOUNTING
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|accounting| accounting.usage.bytes)
+}
+
+/// Prolong the refresh interval once the monthly cap is reached.
+pub fn throttle(refresh: Duration) -> Duration {
+    match ACCOUNTING.lock().unwrap().as_ref() {
+        Some(accounting) if accounting.capped() => refresh.max(accounting.capped_refresh),
+        _ => refresh,
+    }
+}
+
+/// Keep the usage of this month for the next start.
+pub fn save() -> anyhow::Result<()> {
+    if let Some(Accounting {
+        usage,
+        path: Some(path),
+        ..
+    }) = ACCOUNTING.lock().unwrap().as_ref()
+    {
+        serde_json::to_writer(File::create(path)?, usage)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add() {
+        let mut accounting = Accounting {
+            usage: Usage::default(),
+            cap: Some(1000),
+            capped_refresh: Duration::from_secs(600),
+            path: None,
+        };
+        accounting.add(600, "2024-09".to_string());
+        assert!(!accounting.capped());
+        accounting.add(600, "2024-09".to_string());
+        assert!(accounting.capped());
+        accounting.add(600, "2024-10".to_string());
+        assert_eq!(accounting.usage.bytes, 600);
+        assert!(!accounting.capped());
+    }
+}
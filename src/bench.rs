@@ -0,0 +1,73 @@
+//! Latencies of repeated scrapes by phase, to compare drivers and backends and to tune the
+//! timeouts on the hardware at hand.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::time::Duration;
+
+/// Durations measured so far, by phase.
+#[derive(Default)]
+pub struct Timings {
+    phases: BTreeMap<&'static str, Vec<Duration>>,
+}
+
+impl Timings {
+    pub fn record(&mut self, phase: &'static str, duration: Duration) {
+        self.phases.entry(phase).or_default().push(duration);
+    }
+
+    /// Table of the percentiles of each phase, in milliseconds.
+    pub fn report(&self) -> String {
+        let mut report = format!(
+            "{:<8} {:>5} {:>8} {:>8} {:>8} {:>8}\n",
+            "phase", "runs", "p50", "p90", "p99", "max"
+        );
+        for (phase, durations) in &self.phases {
+            let mut sorted = durations.clone();
+            sorted.sort();
+            let millis = |percent| percentile(&sorted, percent).as_millis();
+            writeln!(
+                report,
+                "{:<8} {:>5} {:>8} {:>8} {:>8} {:>8}",
+                phase,
+                sorted.len(),
+                millis(50),
+                millis(90),
+                millis(99),
+                millis(100)
+            )
+            .unwrap();
+        }
+        report
+    }
+}
+
+/// Nearest-rank percentile of sorted durations.
+fn percentile(sorted: &[Duration], percent: usize) -> Duration {
+    if sorted.is_empty() {
+        return Duration::ZERO;
+    }
+    let rank = (percent * sorted.len()).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentile() {
+        let sorted: Vec<_> = (1..=10).map(Duration::from_secs).collect();
+        assert_eq!(percentile(&sorted, 50), Duration::from_secs(5));
+        assert_eq!(percentile(&sorted, 90), Duration::from_secs(9));
+        assert_eq!(percentile(&sorted, 99), Duration::from_secs(10));
+        assert_eq!(percentile(&sorted, 0), Duration::from_secs(1));
+        assert_eq!(percentile(&[], 50), Duration::ZERO);
+
+        let mut timings = Timings::default();
+        timings.record("load", Duration::from_millis(120));
+        let report = timings.report();
+        assert!(report.lines().nth(1).unwrap().starts_with("load"));
+        assert!(report.contains("120"));
+    }
+}
@@ -0,0 +1,102 @@
+use fantoccini::{Client, Locator};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::time::Duration;
+use tokio::time::sleep;
+use url::Url;
+
+use crate::{budget, element_text, parse_score};
+
+/// Playoff series between two teams, `wins` counted from the first team's perspective.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Series {
+    pub teams: (String, String),
+    pub wins: (u64, u64),
+}
+
+impl Series {
+    /// Wins of the team and of its opponent if the team plays in this series.
+    pub fn score_for(&self, team_name: &str) -> Option<(u64, u64)> {
+        if self.teams.0.starts_with(team_name) {
+            Some(self.wins)
+        } else if self.teams.1.starts_with(team_name) {
+            Some((self.wins.1, self.wins.0))
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Round {
+    pub name: String,
+    pub series: Vec<Series>,
+}
+
+/// The bracket page is gone or redirected elsewhere, e.g. at the change of the season.
+#[derive(Debug)]
+pub struct MissingBracket(pub Url);
+
+impl fmt::Display for MissingBracket {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} contains no playoff bracket", self.0)
+    }
+}
+
+impl std::error::Error for MissingBracket {}
+
+/// Read all rounds of the playoff bracket page (e.g. `.../extraliga/pavouk/`).
+pub async fn get_bracket(client: &mut Client, url: &Url) -> anyhow::Result<Vec<Round>> {
+    budget::goto(client, url.as_str()).await?;
+    sleep(Duration::from_millis(500)).await;
+    if client.current_url().await?.path() != url.path() {
+        return Err(MissingBracket(url.clone()).into());
+    }
+
+    let mut rounds = Vec::new();
+    for (index, round) in client
+        .find_all(Locator::Css(".draw__round"))
+        .await?
+        .into_iter()
+        .enumerate()
+    {
+        let name = element_text(&round, ".draw__header")
+            .await
+            .unwrap_or_else(|_| format!("Round {}", index + 1));
+
+        let mut series = Vec::new();
+        for bracket in round.find_all(Locator::Css(".bracket")).await? {
+            let rows = bracket
+                .find_all(Locator::Css(".bracket__participantRow"))
+                .await?;
+            let [first, second] = rows.as_slice() else {
+                continue;
+            };
+            series.push(Series {
+                teams: (
+                    element_text(first, ".bracket__name").await?,
+                    element_text(second, ".bracket__name").await?,
+                ),
+                // the result is missing until the series starts
+                wins: (
+                    parse_score(
+                        &element_text(first, ".bracket__result")
+                            .await
+                            .unwrap_or_default(),
+                    ),
+                    parse_score(
+                        &element_text(second, ".bracket__result")
+                            .await
+                            .unwrap_or_default(),
+                    ),
+                ),
+            });
+        }
+        rounds.push(Round { name, series });
+    }
+
+    if rounds.is_empty() {
+        return Err(MissingBracket(url.clone()).into());
+    }
+    Ok(rounds)
+}
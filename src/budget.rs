@@ -0,0 +1,113 @@
+use fantoccini::Client;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::warn;
+use url::Url;
+
+use crate::bandwidth;
+
+const WINDOW: Duration = Duration::from_secs(3600);
+
+/// Maximum number of page loads per domain within a sliding hour.
+struct RequestBudget {
+    per_hour: usize,
+    loads: HashMap<String, VecDeque<Instant>>,
+}
+
+impl RequestBudget {
+    /// Time to wait before another page of the domain can be loaded; zero records the load.
+    fn acquire(&mut self, domain: &str, now: Instant) -> Duration {
+        let loads = self.loads.entry(domain.to_string()).or_default();
+        while loads
+            .front()
+            .is_some_and(|load| now.duration_since(*load) >= WINDOW)
+        {
+            loads.pop_front();
+        }
+
+        if loads.len() < self.per_hour {
+            loads.push_back(now);
+            Duration::ZERO
+        } else {
+            WINDOW - now.duration_since(loads[0])
+        }
+    }
+}
+
+static BUDGET: Mutex<Option<RequestBudget>> = Mutex::new(None);
+
+/// Enable the budget for all the page loads done by the process.
+pub fn init(per_hour: usize) {
+    *BUDGET.lock().unwrap() = Some(RequestBudget {
+        per_hour,
+        loads: HashMap::new(),
+    });
+}
+
+/// Load the page, waiting until the domain budget allows it.
+pub async fn goto(client: &mut Client, url: &str) -> anyhow::Result<()> {
+    reserve(url).await;
+    client.goto(url).await?;
+    bandwidth::measure(client).await;
+    Ok(())
+}
+
+/// Wait until the domain budget allows loading the page and count the load.
+pub async fn reserve(url: &str) {
+    if let Some(host) = Url::parse(url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_string))
+    {
+        loop {
+            let wait = match BUDGET.lock().unwrap().as_mut() {
+                Some(budget) => budget.acquire(&host, Instant::now()),
+                None => Duration::ZERO,
+            };
+            if wait.is_zero() {
+                break;
+            }
+            warn!("request budget for {host} exhausted, waiting {wait:?}");
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+/// Prolong the refresh interval so that a cycle of page loads fits into the budget.
+pub fn throttle(refresh: Duration, loads_per_cycle: u32) -> Duration {
+    match BUDGET.lock().unwrap().as_ref() {
+        Some(budget) => refresh.max(WINDOW * loads_per_cycle / budget.per_hour.max(1) as u32),
+        None => refresh,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_acquire() {
+        let mut budget = RequestBudget {
+            per_hour: 2,
+            loads: HashMap::new(),
+        };
+        let now = Instant::now();
+        assert_eq!(budget.acquire("www.livesport.cz", now), Duration::ZERO);
+        assert_eq!(
+            budget.acquire("www.livesport.cz", now + Duration::from_secs(600)),
+            Duration::ZERO
+        );
+        assert_eq!(
+            budget.acquire("www.livesport.cz", now + Duration::from_secs(1200)),
+            Duration::from_secs(2400)
+        );
+        assert_eq!(
+            budget.acquire("www.flashscore.com", now + Duration::from_secs(1200)),
+            Duration::ZERO
+        );
+        assert_eq!(
+            budget.acquire("www.livesport.cz", now + WINDOW),
+            Duration::ZERO
+        );
+    }
+}
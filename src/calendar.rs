@@ -0,0 +1,67 @@
+use chrono::{DateTime, Local};
+use std::time::Duration;
+
+use crate::{GameResult, GameTime};
+
+/// The browser is started this long before a known kickoff ...
+const LEAD: chrono::Duration = chrono::Duration::minutes(30);
+/// ... and kept until this long after it.
+const TAIL: chrono::Duration = chrono::Duration::hours(3);
+/// Look for new fixtures this often when none is known.
+const RECHECK: Duration = Duration::from_secs(6 * 3600);
+
+/// Kickoff implied by a scraped result, the previous one is kept while the match goes on.
+pub fn kickoff(result: &GameResult, previous: Option<DateTime<Local>>) -> Option<DateTime<Local>> {
+    match result.game_time {
+        GameTime::WillBePlayed(Some((hours, minutes))) => Some(
+            result.generated
+                + chrono::Duration::hours(hours as i64)
+                + chrono::Duration::minutes(minutes as i64),
+        ),
+        GameTime::WillBePlayed(None) => None,
+        GameTime::Playing(_) | GameTime::BreakAfter(_) => previous.or(Some(result.generated)),
+        GameTime::Played => previous,
+    }
+}
+
+/// How long nothing has to run: `None` inside the activation window of any kickoff,
+/// otherwise until the next window opens or the recheck for unknown fixtures is due.
+pub fn idle_for(kickoffs: &[Option<DateTime<Local>>], now: DateTime<Local>) -> Option<Duration> {
+    let mut idle = RECHECK;
+    for kickoff in kickoffs.iter().flatten() {
+        let start = *kickoff - LEAD;
+        if start <= now && now <= *kickoff + TAIL {
+            return None;
+        }
+        if start > now {
+            idle = idle.min((start - now).to_std().unwrap_or_default());
+        }
+    }
+    Some(idle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_idle_for() {
+        let now = Local::now();
+        assert_eq!(idle_for(&[None], now), Some(RECHECK));
+
+        let kickoff = now + chrono::Duration::hours(2);
+        assert_eq!(
+            idle_for(&[None, Some(kickoff)], now),
+            Some(Duration::from_secs(90 * 60))
+        );
+
+        let kickoff = now + chrono::Duration::minutes(10);
+        assert_eq!(idle_for(&[Some(kickoff)], now), None);
+
+        let kickoff = now - chrono::Duration::hours(2);
+        assert_eq!(idle_for(&[Some(kickoff)], now), None);
+
+        let kickoff = now - chrono::Duration::hours(4);
+        assert_eq!(idle_for(&[Some(kickoff)], now), Some(RECHECK));
+    }
+}
@@ -0,0 +1,111 @@
+//! The feed the page downloads itself, read from the Chrome DevTools network events.
+//!
+//! chromedriver records the events into the performance log when asked by the
+//! `goog:loggingPrefs` capability; response bodies are then fetched with a DevTools command.
+
+use fantoccini::wd::WebDriverCompatibleCommand;
+use fantoccini::Client;
+use serde_json::{json, Value};
+use tracing::debug;
+use url::{ParseError, Url};
+
+/// Marker of a match record in the feed format.
+const FEED_MARKER: &str = "AA÷";
+
+/// Entries of the performance log recorded since the previous read.
+#[derive(Debug)]
+struct PerformanceLog;
+
+impl WebDriverCompatibleCommand for PerformanceLog {
+    fn endpoint(&self, base_url: &Url, session_id: Option<&str>) -> Result<Url, ParseError> {
+        base_url.join(&format!(
+            "session/{}/se/log",
+            session_id.unwrap_or_default()
+        ))
+    }
+
+    fn method_and_body(&self, _request_url: &Url) -> (::http::Method, Option<String>) {
+        (
+            ::http::Method::POST,
+            Some(json!({"type": "performance"}).to_string()),
+        )
+    }
+}
+
+/// Raw Chrome DevTools Protocol command.
+#[derive(Debug)]
+struct DevToolsCommand {
+    method: &'static str,
+    params: Value,
+}
+
+impl WebDriverCompatibleCommand for DevToolsCommand {
+    fn endpoint(&self, base_url: &Url, session_id: Option<&str>) -> Result<Url, ParseError> {
+        base_url.join(&format!(
+            "session/{}/goog/cdp/execute",
+            session_id.unwrap_or_default()
+        ))
+    }
+
+    fn method_and_body(&self, _request_url: &Url) -> (::http::Method, Option<String>) {
+        (
+            ::http::Method::POST,
+            Some(json!({"cmd": self.method, "params": self.params}).to_string()),
+        )
+    }
+}
+
+/// Capability enabling the performance log with the network events.
+pub fn logging_preferences() -> Value {
+    json!({"performance": "ALL"})
+}
+
+/// Forget the network events recorded so far, e.g. those of previously visited pages.
+pub async fn clear(client: &Client) -> anyhow::Result<()> {
+    client.issue_cmd(PerformanceLog).await?;
+    Ok(())
+}
+
+/// Feed payloads received by XHR, fetch or WebSocket since the previous read of the log.
+pub async fn feeds(client: &Client) -> anyhow::Result<Vec<String>> {
+    let entries = client.issue_cmd(PerformanceLog).await?;
+    let mut feeds = Vec::new();
+    for entry in entries.as_array().into_iter().flatten() {
+        let Some(message) = entry["message"]
+            .as_str()
+            .and_then(|message| serde_json::from_str::<Value>(message).ok())
+        else {
+            continue;
+        };
+        let event = &message["message"];
+        let params = &event["params"];
+        let payload = match event["method"].as_str() {
+            Some("Network.responseReceived")
+                if matches!(params["type"].as_str(), Some("XHR" | "Fetch")) =>
+            {
+                let body = client
+                    .issue_cmd(DevToolsCommand {
+                        method: "Network.getResponseBody",
+                        params: json!({"requestId": params["requestId"]}),
+                    })
+                    .await;
+                match body {
+                    Ok(body) => body["body"].as_str().map(str::to_string),
+                    Err(error) => {
+                        debug!(
+                            "could not read body of {}: {error}",
+                            params["response"]["url"]
+                        );
+                        None
+                    }
+                }
+            }
+            Some("Network.webSocketFrameReceived") => params["response"]["payloadData"]
+                .as_str()
+                .map(str::to_string),
+            _ => None,
+        };
+        feeds.extend(payload.filter(|payload| payload.contains(FEED_MARKER)));
+    }
+    Ok(feeds)
+}
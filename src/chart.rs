@@ -0,0 +1,62 @@
+//! Tiny terminal charts of the score progression.
+
+use crate::{GameResult, GameTime};
+
+/// Levels of the sparkline, the middle one is a tie.
+const LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+const TIE: i64 = 3;
+
+/// Goal difference of my team over the regulation time (or longer), one character per
+/// `width`-th of it; the part of the match not played yet is left out.
+pub fn sparkline(result: &GameResult, width: usize) -> String {
+    let (now, span) = match result.game_time {
+        GameTime::Playing(minute) | GameTime::BreakAfter(minute) => (minute, minute),
+        GameTime::Played => (u64::MAX, 0),
+        GameTime::WillBePlayed(_) => return String::new(),
+    };
+    let last = result.timeline.last().map_or(0, |&(minute, _, _)| minute);
+    let span = result.periods.regulation().max(last).max(span);
+    let width = width.max(1) as u64;
+
+    (1..=width)
+        // the column covering the current minute is drawn before it is complete
+        .take_while(|column| span * (column - 1) / width < now)
+        .map(|column| span * column / width)
+        .map(|minute| {
+            let difference = result
+                .timeline
+                .iter()
+                .take_while(|&&(goal_minute, _, _)| goal_minute <= minute)
+                .last()
+                .map_or(0, |&(_, my, opponent)| my as i64 - opponent as i64);
+            LEVELS[(difference + TIE).clamp(0, LEVELS.len() as i64 - 1) as usize]
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::sample;
+
+    #[test]
+    fn test_sparkline() {
+        let result = GameResult {
+            timeline: vec![(0, 0, 0), (10, 1, 0), (25, 1, 1), (31, 1, 2)],
+            ..sample(GameTime::Playing(40), 0, 0)
+        };
+        assert_eq!(sparkline(&result, 6), "▅▅▄▃");
+
+        let result = GameResult {
+            game_time: GameTime::Played,
+            ..result
+        };
+        assert_eq!(sparkline(&result, 6), "▅▅▄▃▃▃");
+
+        let result = GameResult {
+            game_time: GameTime::WillBePlayed(None),
+            ..result
+        };
+        assert_eq!(sparkline(&result, 6), "");
+    }
+}
@@ -0,0 +1,970 @@
+use anyhow::Context;
+use chrono_tz::Tz;
+use clap::parser::ValueSource;
+use clap::ArgMatches;
+use serde::Deserialize;
+#[cfg(feature = "chat")]
+use std::collections::BTreeMap;
+use std::collections::HashSet;
+use std::fs::{self, OpenOptions};
+#[cfg(feature = "server")]
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+#[cfg(feature = "server")]
+use toml_edit::{ArrayOfTables, DocumentMut, Item, Table};
+use url::Url;
+
+#[cfg(any(
+    feature = "chat",
+    feature = "sentry",
+    feature = "server",
+    feature = "smtp"
+))]
+use crate::secrets;
+#[cfg(feature = "chat")]
+use crate::slack;
+use crate::{details, domains, metrics, schedule, teams, template, Backend, Cli, OutputFormat};
+
+const SUMMARY_PLACEHOLDERS: &[&str] = &[
+    "my_team",
+    "opponent_team",
+    "my_team_score",
+    "opponent_team_score",
+    "period_scores",
+    "scorers",
+    "broadcast",
+    "date",
+    "status",
+    "period",
+    "clock",
+];
+
+/// TOML configuration file; its keys are named after the command line options.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    url: Option<Url>,
+    team_name: Option<String>,
+    output: Option<PathBuf>,
+    #[serde(default)]
+    teams: Vec<teams::Team>,
+    #[serde(default)]
+    clubs: Vec<teams::Club>,
+    #[serde(default)]
+    only: Vec<String>,
+    #[serde(default)]
+    mirrors: Vec<String>,
+    layout_snapshot: Option<PathBuf>,
+    output_format: Option<OutputFormat>,
+    archive_dir: Option<PathBuf>,
+    summary_output: Option<PathBuf>,
+    openligadb_output: Option<PathBuf>,
+    state_dir: Option<PathBuf>,
+    #[cfg(feature = "redis")]
+    dedup_redis: Option<Url>,
+    #[cfg(feature = "mqtt")]
+    mqtt_broker: Option<Url>,
+    #[cfg(feature = "mqtt")]
+    mqtt_topic: Option<String>,
+    summary_template: Option<PathBuf>,
+    player_output: Option<PathBuf>,
+    #[cfg(feature = "chat")]
+    telegram_token: Option<String>,
+    #[cfg(feature = "chat")]
+    telegram_chat: Option<String>,
+    #[cfg(feature = "chat")]
+    slack_webhook: Option<String>,
+    #[cfg(feature = "chat")]
+    #[serde(default)]
+    slack_template: BTreeMap<String, String>,
+    #[cfg(feature = "smtp")]
+    smtp: Option<String>,
+    #[cfg(feature = "smtp")]
+    email_from: Option<String>,
+    #[cfg(feature = "smtp")]
+    #[serde(default)]
+    email_to: Vec<String>,
+    #[cfg(feature = "chat")]
+    matrix_homeserver: Option<Url>,
+    #[cfg(feature = "chat")]
+    matrix_token: Option<String>,
+    #[cfg(feature = "chat")]
+    matrix_room: Option<String>,
+    #[cfg(feature = "chat")]
+    ntfy: Option<Url>,
+    #[cfg(feature = "chat")]
+    ntfy_token: Option<String>,
+    #[cfg(feature = "chat")]
+    gotify: Option<Url>,
+    #[cfg(feature = "chat")]
+    gotify_token: Option<String>,
+    #[serde(default)]
+    webhook: Vec<Url>,
+    webhook_retries: Option<u32>,
+    webhook_timeout: Option<u64>,
+    drop_glitches: Option<bool>,
+    #[serde(default)]
+    sink_exec: Vec<String>,
+    #[serde(default)]
+    on_event: Vec<String>,
+    #[cfg(feature = "desktop")]
+    notify_desktop: Option<bool>,
+    #[cfg(feature = "wasm")]
+    #[serde(default)]
+    sink_wasm: Vec<PathBuf>,
+    details: Option<bool>,
+    context: Option<bool>,
+    best_of: Option<u64>,
+    bracket: Option<Url>,
+    #[serde(default)]
+    players: Vec<String>,
+    opponent_form: Option<bool>,
+    stealth: Option<bool>,
+    cookie_file: Option<PathBuf>,
+    max_page_loads: Option<usize>,
+    bandwidth_cap: Option<u64>,
+    capped_refresh: Option<u64>,
+    fixture_check_hours: Option<u64>,
+    timezone: Option<Tz>,
+    network_feed: Option<bool>,
+    backend: Option<Backend>,
+    calendar: Option<bool>,
+    ephemeral_browser: Option<bool>,
+    browsers: Option<u16>,
+    trigger_file: Option<PathBuf>,
+    #[serde(default)]
+    schedule: Vec<schedule::Expression>,
+    measure_latency: Option<bool>,
+    profile_interval: Option<u64>,
+    max_outage: Option<u64>,
+    ticker: Option<bool>,
+    #[cfg(feature = "server")]
+    serve: Option<SocketAddr>,
+    #[cfg(feature = "server")]
+    admin_token: Option<String>,
+    output_mode: Option<u32>,
+    dir_mode: Option<u32>,
+    output_owner: Option<String>,
+    user: Option<String>,
+    group: Option<String>,
+    umask: Option<u32>,
+    heartbeat: Option<Url>,
+    standby: Option<Url>,
+    standby_timeout: Option<u64>,
+    refresh: Option<u64>,
+    sentry_dsn: Option<String>,
+    metrics: Option<Url>,
+    metrics_prefix: Option<String>,
+}
+
+impl Config {
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("cannot read config {}", path.display()))?;
+        toml::from_str(&content).with_context(|| format!("invalid config {}", path.display()))
+    }
+
+    /// Use the configured values for all options not given on the command line.
+    pub fn apply(self, cli: &mut Cli, matches: &ArgMatches) {
+        let unset = |id: &str| matches.value_source(id) != Some(ValueSource::CommandLine);
+
+        if unset("url") && self.url.is_some() {
+            cli.url = self.url;
+        }
+        if unset("team_name") && self.team_name.is_some() {
+            cli.team_name = self.team_name;
+        }
+        if unset("output") && self.output.is_some() {
+            cli.output = self.output;
+        }
+        if unset("teams") && !(self.teams.is_empty() && self.clubs.is_empty()) {
+            cli.teams = self
+                .teams
+                .into_iter()
+                .chain(self.clubs.into_iter().flat_map(teams::Club::into_teams))
+                .collect();
+        }
+        if unset("only") && !self.only.is_empty() {
+            cli.only = self.only;
+        }
+        if unset("mirrors") && !self.mirrors.is_empty() {
+            cli.mirrors = self.mirrors;
+        }
+        if unset("layout_snapshot") && self.layout_snapshot.is_some() {
+            cli.layout_snapshot = self.layout_snapshot;
+        }
+        if let Some(output_format) = self.output_format.filter(|_| unset("output_format")) {
+            cli.sinks.output_format = output_format;
+        }
+        if unset("archive_dir") && self.archive_dir.is_some() {
+            cli.sinks.archive_dir = self.archive_dir;
+        }
+        if unset("summary_output") && self.summary_output.is_some() {
+            cli.sinks.summary_output = self.summary_output;
+        }
+        if unset("openligadb_output") && self.openligadb_output.is_some() {
+            cli.sinks.openligadb_output = self.openligadb_output;
+        }
+        if unset("state_dir") && self.state_dir.is_some() {
+            cli.sinks.state_dir = self.state_dir;
+        }
+        #[cfg(feature = "redis")]
+        if unset("dedup_redis") && self.dedup_redis.is_some() {
+            cli.sinks.dedup_redis = self.dedup_redis;
+        }
+        #[cfg(feature = "mqtt")]
+        if unset("mqtt_broker") && self.mqtt_broker.is_some() {
+            cli.sinks.mqtt_broker = self.mqtt_broker;
+        }
+        #[cfg(feature = "mqtt")]
+        if let Some(mqtt_topic) = self.mqtt_topic.filter(|_| unset("mqtt_topic")) {
+            cli.sinks.mqtt_topic = mqtt_topic;
+        }
+        if unset("summary_template") && self.summary_template.is_some() {
+            cli.sinks.summary_template = self.summary_template;
+        }
+        if unset("player_output") && self.player_output.is_some() {
+            cli.sinks.player_output = self.player_output;
+        }
+        #[cfg(feature = "chat")]
+        if unset("telegram_token") && self.telegram_token.is_some() {
+            cli.sinks.telegram_token = self.telegram_token;
+        }
+        #[cfg(feature = "chat")]
+        if unset("telegram_chat") && self.telegram_chat.is_some() {
+            cli.sinks.telegram_chat = self.telegram_chat;
+        }
+        #[cfg(feature = "chat")]
+        if unset("slack_webhook") && self.slack_webhook.is_some() {
+            cli.sinks.slack_webhook = self.slack_webhook;
+        }
+        #[cfg(feature = "chat")]
+        if unset("slack_template") && !self.slack_template.is_empty() {
+            cli.sinks.slack_template = self.slack_template.into_iter().collect();
+        }
+        #[cfg(feature = "smtp")]
+        if unset("smtp") && self.smtp.is_some() {
+            cli.sinks.smtp = self.smtp;
+        }
+        #[cfg(feature = "smtp")]
+        if unset("email_from") && self.email_from.is_some() {
+            cli.sinks.email_from = self.email_from;
+        }
+        #[cfg(feature = "smtp")]
+        if unset("email_to") && !self.email_to.is_empty() {
+            cli.sinks.email_to = self.email_to;
+        }
+        #[cfg(feature = "chat")]
+        if unset("matrix_homeserver") && self.matrix_homeserver.is_some() {
+            cli.sinks.matrix_homeserver = self.matrix_homeserver;
+        }
+        #[cfg(feature = "chat")]
+        if unset("matrix_token") && self.matrix_token.is_some() {
+            cli.sinks.matrix_token = self.matrix_token;
+        }
+        #[cfg(feature = "chat")]
+        if unset("matrix_room") && self.matrix_room.is_some() {
+            cli.sinks.matrix_room = self.matrix_room;
+        }
+        #[cfg(feature = "chat")]
+        if unset("ntfy") && self.ntfy.is_some() {
+            cli.sinks.ntfy = self.ntfy;
+        }
+        #[cfg(feature = "chat")]
+        if unset("ntfy_token") && self.ntfy_token.is_some() {
+            cli.sinks.ntfy_token = self.ntfy_token;
+        }
+        #[cfg(feature = "chat")]
+        if unset("gotify") && self.gotify.is_some() {
+            cli.sinks.gotify = self.gotify;
+        }
+        #[cfg(feature = "chat")]
+        if unset("gotify_token") && self.gotify_token.is_some() {
+            cli.sinks.gotify_token = self.gotify_token;
+        }
+        if unset("webhook") && !self.webhook.is_empty() {
+            cli.sinks.webhook = self.webhook;
+        }
+        if let Some(retries) = self.webhook_retries.filter(|_| unset("webhook_retries")) {
+            cli.sinks.webhook_retries = retries;
+        }
+        if let Some(timeout) = self.webhook_timeout.filter(|_| unset("webhook_timeout")) {
+            cli.sinks.webhook_timeout = timeout;
+        }
+        if let Some(drop_glitches) = self.drop_glitches.filter(|_| unset("drop_glitches")) {
+            cli.sinks.drop_glitches = drop_glitches;
+        }
+        if unset("sink_exec") && !self.sink_exec.is_empty() {
+            cli.sinks.sink_exec = self.sink_exec;
+        }
+        if unset("on_event") && !self.on_event.is_empty() {
+            cli.sinks.on_event = self.on_event;
+        }
+        #[cfg(feature = "desktop")]
+        if let Some(notify_desktop) = self.notify_desktop.filter(|_| unset("notify_desktop")) {
+            cli.sinks.notify_desktop = notify_desktop;
+        }
+        #[cfg(feature = "wasm")]
+        if unset("sink_wasm") && !self.sink_wasm.is_empty() {
+            cli.sinks.sink_wasm = self.sink_wasm;
+        }
+        if let Some(details) = self.details.filter(|_| unset("details")) {
+            cli.details = details;
+        }
+        if let Some(context) = self.context.filter(|_| unset("context")) {
+            cli.context = context;
+        }
+        if unset("best_of") && self.best_of.is_some() {
+            cli.best_of = self.best_of;
+        }
+        if unset("bracket") && self.bracket.is_some() {
+            cli.bracket = self.bracket;
+        }
+        if unset("players") && !self.players.is_empty() {
+            cli.players = self.players;
+        }
+        if let Some(opponent_form) = self.opponent_form.filter(|_| unset("opponent_form")) {
+            cli.opponent_form = opponent_form;
+        }
+        if let Some(stealth) = self.stealth.filter(|_| unset("stealth")) {
+            cli.stealth = stealth;
+        }
+        if unset("cookie_file") && self.cookie_file.is_some() {
+            cli.cookie_file = self.cookie_file;
+        }
+        if unset("max_page_loads") && self.max_page_loads.is_some() {
+            cli.max_page_loads = self.max_page_loads;
+        }
+        if unset("bandwidth_cap") && self.bandwidth_cap.is_some() {
+            cli.bandwidth_cap = self.bandwidth_cap;
+        }
+        if let Some(refresh) = self.capped_refresh.filter(|_| unset("capped_refresh")) {
+            cli.capped_refresh = refresh;
+        }
+        if let Some(hours) = self
+            .fixture_check_hours
+            .filter(|_| unset("fixture_check_hours"))
+        {
+            cli.fixture_check_hours = hours;
+        }
+        if unset("timezone") && self.timezone.is_some() {
+            cli.timezone = self.timezone;
+        }
+        if let Some(network_feed) = self.network_feed.filter(|_| unset("network_feed")) {
+            cli.network_feed = network_feed;
+        }
+        if let Some(backend) = self.backend.filter(|_| unset("backend")) {
+            cli.backend = backend;
+        }
+        if let Some(calendar) = self.calendar.filter(|_| unset("calendar")) {
+            cli.calendar = calendar;
+        }
+        if let Some(ephemeral_browser) = self
+            .ephemeral_browser
+            .filter(|_| unset("ephemeral_browser"))
+        {
+            cli.ephemeral_browser = ephemeral_browser;
+        }
+        if let Some(browsers) = self.browsers.filter(|_| unset("browsers")) {
+            cli.browsers = browsers;
+        }
+        if unset("trigger_file") && self.trigger_file.is_some() {
+            cli.trigger_file = self.trigger_file;
+        }
+        if let Some(ticker) = self.ticker.filter(|_| unset("ticker")) {
+            cli.ticker = ticker;
+        }
+        #[cfg(feature = "server")]
+        if unset("serve") && self.serve.is_some() {
+            cli.serve = self.serve;
+        }
+        #[cfg(feature = "server")]
+        if unset("admin_token") && self.admin_token.is_some() {
+            cli.admin_token = self.admin_token;
+        }
+        if unset("output_mode") && self.output_mode.is_some() {
+            cli.output_mode = self.output_mode;
+        }
+        if unset("dir_mode") && self.dir_mode.is_some() {
+            cli.dir_mode = self.dir_mode;
+        }
+        if unset("output_owner") && self.output_owner.is_some() {
+            cli.output_owner = self.output_owner;
+        }
+        if unset("user") && self.user.is_some() {
+            cli.user = self.user;
+        }
+        if unset("group") && self.group.is_some() {
+            cli.group = self.group;
+        }
+        if unset("umask") && self.umask.is_some() {
+            cli.umask = self.umask;
+        }
+        if unset("heartbeat") && self.heartbeat.is_some() {
+            cli.heartbeat = self.heartbeat;
+        }
+        if unset("standby") && self.standby.is_some() {
+            cli.standby = self.standby;
+        }
+        if let Some(timeout) = self.standby_timeout.filter(|_| unset("standby_timeout")) {
+            cli.standby_timeout = timeout;
+        }
+        if unset("schedule") && !self.schedule.is_empty() {
+            cli.schedule = self.schedule;
+        }
+        if let Some(measure_latency) = self.measure_latency.filter(|_| unset("measure_latency")) {
+            cli.measure_latency = measure_latency;
+        }
+        if unset("profile_interval") && self.profile_interval.is_some() {
+            cli.profile_interval = self.profile_interval;
+        }
+        if let Some(max_outage) = self.max_outage.filter(|_| unset("max_outage")) {
+            cli.max_outage = max_outage;
+        }
+        if let Some(refresh) = self.refresh.filter(|_| unset("refresh")) {
+            cli.refresh = refresh;
+        }
+        if unset("sentry_dsn") && self.sentry_dsn.is_some() {
+            cli.sentry_dsn = self.sentry_dsn;
+        }
+        if unset("metrics") && self.metrics.is_some() {
+            cli.metrics = self.metrics;
+        }
+        if let Some(metrics_prefix) = self.metrics_prefix.filter(|_| unset("metrics_prefix")) {
+            cli.metrics_prefix = metrics_prefix;
+        }
+    }
+}
+
+/// Problem found in the config file, with the line of the offending key when known.
+pub struct ValidationError {
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+/// Replace a URL that moved in the configuration file, keeping the rest of it as written.
+pub fn replace_url(path: &Path, old: &Url, new: &Url) -> anyhow::Result<()> {
+    let text = fs::read_to_string(path)
+        .with_context(|| format!("cannot read config file {}", path.display()))?;
+    let quoted = |url: &Url| format!("\"{url}\"");
+    anyhow::ensure!(
+        text.contains(&quoted(old)),
+        "{old} is not in {}",
+        path.display()
+    );
+    fs::write(path, text.replace(&quoted(old), &quoted(new)))
+        .with_context(|| format!("cannot update config file {}", path.display()))
+}
+
+/// Append the team to the `teams` of the configuration file, keeping the rest of it as written.
+#[cfg(feature = "server")]
+pub fn add_team(path: &Path, team: &teams::Team) -> anyhow::Result<()> {
+    let table = toml::to_string(team)?.parse::<DocumentMut>()?;
+    edit(path, |document| {
+        document
+            .entry("teams")
+            .or_insert(Item::ArrayOfTables(ArrayOfTables::new()))
+            .as_array_of_tables_mut()
+            .context("teams is not an array of tables")?
+            .push(table.as_table().clone());
+        Ok(())
+    })
+}
+
+/// Remove the team from the configuration file, from the teams of its club when it has one;
+/// `false` when the team is not in the file, e.g. given on the command line.
+#[cfg(feature = "server")]
+pub fn remove_team(path: &Path, team: &teams::Team) -> anyhow::Result<bool> {
+    edit(path, |document| {
+        let Some(teams) = teams_of(document, team) else {
+            return Ok(false);
+        };
+        let before = teams.len();
+        teams.retain(|table| !lists(table, team));
+        Ok(teams.len() < before)
+    })
+}
+
+/// Set the read token of the team in the configuration file; `false` when the team is not in
+/// the file.
+#[cfg(feature = "server")]
+pub fn set_team_token(path: &Path, team: &teams::Team, token: &str) -> anyhow::Result<bool> {
+    edit(path, |document| {
+        let Some(table) = teams_of(document, team)
+            .and_then(|teams| teams.iter_mut().find(|table| lists(table, team)))
+        else {
+            return Ok(false);
+        };
+        table.insert("token", toml_edit::value(token));
+        Ok(true)
+    })
+}
+
+/// The `teams` the team is listed in, those of its club when it has one.
+#[cfg(feature = "server")]
+fn teams_of<'a>(
+    document: &'a mut DocumentMut,
+    team: &teams::Team,
+) -> Option<&'a mut ArrayOfTables> {
+    match &team.club {
+        Some(club) => document
+            .get_mut("clubs")
+            .and_then(Item::as_array_of_tables_mut)
+            .and_then(|clubs| {
+                clubs
+                    .iter_mut()
+                    .find(|table| table.get("name").and_then(Item::as_str) == Some(club.as_str()))
+            })
+            .and_then(|club| club.get_mut("teams")),
+        None => document.get_mut("teams"),
+    }
+    .and_then(Item::as_array_of_tables_mut)
+}
+
+/// Whether the table is the entry of the team, matched by its id or else its name.
+#[cfg(feature = "server")]
+fn lists(table: &Table, team: &teams::Team) -> bool {
+    table
+        .get("id")
+        .or_else(|| table.get("name"))
+        .and_then(Item::as_str)
+        == Some(team.id.as_deref().unwrap_or(&team.name))
+}
+
+/// Change the configuration file in place, comments and formatting included.
+#[cfg(feature = "server")]
+fn edit<T>(
+    path: &Path,
+    change: impl FnOnce(&mut DocumentMut) -> anyhow::Result<T>,
+) -> anyhow::Result<T> {
+    let mut document = fs::read_to_string(path)
+        .with_context(|| format!("cannot read config file {}", path.display()))?
+        .parse::<DocumentMut>()
+        .with_context(|| format!("invalid config {}", path.display()))?;
+    let changed = change(&mut document)?;
+    fs::write(path, document.to_string())
+        .with_context(|| format!("cannot update config file {}", path.display()))?;
+    Ok(changed)
+}
+
+/// Check the config file beyond its syntax: URLs, templates, writable outputs, credentials.
+pub fn validate(path: &Path) -> anyhow::Result<Vec<ValidationError>> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("cannot read config {}", path.display()))?;
+    let config: Config = match toml::from_str(&content) {
+        Ok(config) => config,
+        Err(error) => {
+            let line = error
+                .span()
+                .map(|span| content[..span.start].matches('\n').count() + 1);
+            return Ok(vec![ValidationError {
+                line,
+                message: error.message().to_string(),
+            }]);
+        }
+    };
+
+    let base = path.parent().unwrap_or(Path::new("."));
+    let mut errors = Vec::new();
+    let mut check = |key: &str, result: anyhow::Result<()>| {
+        if let Err(error) = result {
+            errors.push(ValidationError {
+                line: line_of(&content, key),
+                message: format!("{key}: {error:#}"),
+            });
+        }
+    };
+
+    let club_teams = config
+        .clubs
+        .iter()
+        .flat_map(|club| club.teams.iter().map(move |team| (club, team)));
+    let mut keys = HashSet::new();
+    for (club, team) in club_teams.clone() {
+        let id = team.id.as_deref().unwrap_or(&team.name);
+        if !keys.insert((&club.name, id)) {
+            check(
+                "clubs",
+                Err(anyhow::anyhow!("{}/{id} is listed twice", club.name)),
+            );
+        }
+    }
+
+    if config.teams.is_empty() && config.clubs.is_empty() {
+        if config.url.is_none() {
+            check("url", Err(anyhow::anyhow!("missing team URL")));
+        }
+        if config.team_name.is_none() {
+            check("team_name", Err(anyhow::anyhow!("missing team name")));
+        }
+        if config.output.is_none() {
+            check("output", Err(anyhow::anyhow!("missing output file")));
+        }
+    }
+    let urls = config
+        .url
+        .iter()
+        .map(|url| ("url", url))
+        .chain(config.teams.iter().map(|team| ("teams", &team.url)))
+        .chain(club_teams.clone().map(|(_, team)| ("clubs", &team.url)));
+    for (key, url) in urls.filter(|(_, url)| !details::is_match_url(url)) {
+        match domains::TeamPage::parse(url) {
+            Ok(page) => {
+                for mirror in &config.mirrors {
+                    check("mirrors", page.url_on(mirror).map(|_| ()));
+                }
+            }
+            Err(error) => check(key, Err(error)),
+        }
+    }
+
+    for (key, file) in [
+        ("output", &config.output),
+        ("layout_snapshot", &config.layout_snapshot),
+        ("summary_output", &config.summary_output),
+        ("openligadb_output", &config.openligadb_output),
+        ("player_output", &config.player_output),
+        ("cookie_file", &config.cookie_file),
+    ] {
+        if let Some(file) = file {
+            check(key, check_writable_file(&base.join(file)));
+        }
+    }
+    for team in &config.teams {
+        check("teams", check_writable_file(&base.join(&team.output)));
+    }
+    for (_, team) in club_teams {
+        check("clubs", check_writable_file(&base.join(&team.output)));
+    }
+    if let Some(archive_dir) = &config.archive_dir {
+        check("archive_dir", check_writable_dir(&base.join(archive_dir)));
+    }
+    if let Some(state_dir) = &config.state_dir {
+        check("state_dir", check_writable_dir(&base.join(state_dir)));
+    }
+    if let Some(template) = &config.summary_template {
+        check("summary_template", check_template(&base.join(template)));
+    }
+    if config.cookie_file.is_some() && config.stealth != Some(true) {
+        check(
+            "cookie_file",
+            Err(anyhow::anyhow!("cookies are kept only with stealth = true")),
+        );
+    }
+    if config.max_page_loads == Some(0) {
+        check(
+            "max_page_loads",
+            Err(anyhow::anyhow!("at least one page load has to be allowed")),
+        );
+    }
+    if config.refresh == Some(0) {
+        check("refresh", Err(anyhow::anyhow!("refresh must be positive")));
+    }
+    let browsers = usize::from(config.browsers.unwrap_or(1));
+    if browsers == 0 {
+        check(
+            "browsers",
+            Err(anyhow::anyhow!("at least one browser has to run")),
+        );
+    }
+    let club_teams = config.clubs.iter().flat_map(|club| &club.teams);
+    for team in config.teams.iter().chain(club_teams) {
+        if let Some(browser) = team.browser.filter(|browser| *browser >= browsers) {
+            check(
+                "teams",
+                Err(anyhow::anyhow!(
+                    "{} is assigned to browser {browser}, only {browsers} of them run",
+                    team.name
+                )),
+            );
+        }
+        #[cfg(feature = "server")]
+        if let Some(token) = &team.token {
+            check("teams", secrets::resolve(token).map(|_| ()));
+        }
+    }
+    for url in &config.webhook {
+        if !matches!(url.scheme(), "http" | "https") {
+            check("webhook", Err(anyhow::anyhow!("{url} is not an HTTP URL")));
+        }
+    }
+    #[cfg(feature = "chat")]
+    if config.telegram_token.is_some() != config.telegram_chat.is_some() {
+        check(
+            "telegram_token",
+            Err(anyhow::anyhow!(
+                "the Telegram bot needs both telegram_token and telegram_chat"
+            )),
+        );
+    }
+    #[cfg(feature = "chat")]
+    if let Some(token) = &config.telegram_token {
+        check("telegram_token", secrets::resolve(token).map(|_| ()));
+    }
+    #[cfg(feature = "server")]
+    if let Some(token) = &config.admin_token {
+        check("admin_token", secrets::resolve(token).map(|_| ()));
+    }
+    for (key, mode) in [
+        ("output_mode", config.output_mode),
+        ("dir_mode", config.dir_mode),
+    ] {
+        if mode.is_some_and(|mode| mode > 0o7777) {
+            check(
+                key,
+                Err(anyhow::anyhow!("{key} is at most 0o7777, written in octal")),
+            );
+        }
+    }
+    if config.umask.is_some_and(|umask| umask > 0o777) {
+        check(
+            "umask",
+            Err(anyhow::anyhow!("umask is at most 0o777, written in octal")),
+        );
+    }
+    #[cfg(feature = "chat")]
+    if let Some(webhook) = &config.slack_webhook {
+        check("slack_webhook", secrets::resolve(webhook).map(|_| ()));
+    }
+    #[cfg(feature = "chat")]
+    for (kind, template) in &config.slack_template {
+        check(
+            "slack_template",
+            slack::parse_template(&format!("{kind}={template}"))
+                .map_err(anyhow::Error::msg)
+                .and_then(|_| check_expressions(template, slack::PLACEHOLDERS))
+                .with_context(|| format!("template of {kind}")),
+        );
+    }
+    #[cfg(feature = "smtp")]
+    if let Some(smtp) = &config.smtp {
+        check("smtp", secrets::resolve(smtp).map(|_| ()));
+        if config.email_from.is_none() || config.email_to.is_empty() {
+            check(
+                "smtp",
+                Err(anyhow::anyhow!("emails need email_from and email_to")),
+            );
+        }
+    }
+    #[cfg(feature = "chat")]
+    if config.matrix_homeserver.is_some()
+        && (config.matrix_token.is_none() || config.matrix_room.is_none())
+    {
+        check(
+            "matrix_homeserver",
+            Err(anyhow::anyhow!(
+                "the Matrix room needs matrix_token and matrix_room"
+            )),
+        );
+    }
+    #[cfg(feature = "chat")]
+    if let Some(token) = &config.matrix_token {
+        check("matrix_token", secrets::resolve(token).map(|_| ()));
+    }
+    #[cfg(feature = "chat")]
+    if let Some(token) = &config.ntfy_token {
+        check("ntfy_token", secrets::resolve(token).map(|_| ()));
+    }
+    #[cfg(feature = "chat")]
+    if config.gotify.is_some() != config.gotify_token.is_some() {
+        check(
+            "gotify",
+            Err(anyhow::anyhow!("Gotify needs both gotify and gotify_token")),
+        );
+    }
+    #[cfg(feature = "chat")]
+    if let Some(token) = &config.gotify_token {
+        check("gotify_token", secrets::resolve(token).map(|_| ()));
+    }
+    if let Some(url) = &config.heartbeat {
+        if !matches!(url.scheme(), "file" | "mqtt") {
+            check(
+                "heartbeat",
+                Err(anyhow::anyhow!(
+                    "{url} is neither a file:// nor an mqtt:// URL"
+                )),
+            );
+        }
+    }
+    if let Some(url) = &config.standby {
+        if !matches!(url.scheme(), "file" | "http" | "https" | "mqtt") {
+            check(
+                "standby",
+                Err(anyhow::anyhow!(
+                    "{url} is not a file://, http:// or mqtt:// URL"
+                )),
+            );
+        }
+    }
+    if config.webhook_timeout == Some(0) {
+        check(
+            "webhook_timeout",
+            Err(anyhow::anyhow!("webhook_timeout must be positive")),
+        );
+    }
+    #[cfg(feature = "sentry")]
+    if let Some(dsn) = &config.sentry_dsn {
+        check(
+            "sentry_dsn",
+            secrets::resolve(dsn).and_then(|dsn| {
+                dsn.parse::<sentry::types::Dsn>()
+                    .map(|_| ())
+                    .context("invalid Sentry DSN")
+            }),
+        );
+    }
+    if let Some(url) = &config.metrics {
+        check("metrics", metrics::Metrics::new(url, "").map(|_| ()));
+    }
+
+    errors.sort_by_key(|error| error.line);
+    Ok(errors)
+}
+
+/// Print the problems of the config file, fails if there is any.
+pub fn report(path: &Path) -> anyhow::Result<()> {
+    let errors = validate(path)?;
+    for error in &errors {
+        match error.line {
+            Some(line) => eprintln!("{}:{line}: {}", path.display(), error.message),
+            None => eprintln!("{}: {}", path.display(), error.message),
+        }
+    }
+    anyhow::ensure!(
+        errors.is_empty(),
+        "{} has {} problem(s)",
+        path.display(),
+        errors.len()
+    );
+    println!("{} is valid", path.display());
+    Ok(())
+}
+
+/// Line number of the `key = ...` assignment.
+fn line_of(content: &str, key: &str) -> Option<usize> {
+    content
+        .lines()
+        .position(|line| {
+            line.trim_start()
+                .strip_prefix(key)
+                .is_some_and(|rest| rest.trim_start().starts_with('='))
+        })
+        .map(|index| index + 1)
+}
+
+fn check_writable_file(path: &Path) -> anyhow::Result<()> {
+    if path.exists() {
+        OpenOptions::new()
+            .append(true)
+            .open(path)
+            .with_context(|| format!("{} is not writable", path.display()))?;
+        Ok(())
+    } else {
+        check_writable_dir(path.parent().unwrap_or(Path::new(".")))
+    }
+}
+
+fn check_writable_dir(path: &Path) -> anyhow::Result<()> {
+    let metadata =
+        fs::metadata(path).with_context(|| format!("{} does not exist", path.display()))?;
+    anyhow::ensure!(metadata.is_dir(), "{} is not a directory", path.display());
+    anyhow::ensure!(
+        !metadata.permissions().readonly(),
+        "{} is not writable",
+        path.display()
+    );
+    Ok(())
+}
+
+fn check_template(path: &Path) -> anyhow::Result<()> {
+    let template = fs::read_to_string(path)
+        .with_context(|| format!("cannot read template {}", path.display()))?;
+    for (index, line) in template.lines().enumerate() {
+        check_expressions(line, SUMMARY_PLACEHOLDERS)
+            .with_context(|| format!("{}:{}", path.display(), index + 1))?;
+    }
+    Ok(())
+}
+
+/// Check all `{...}` expressions of the template.
+fn check_expressions(template: &str, placeholders: &[&str]) -> anyhow::Result<()> {
+    for placeholder in template.split('{').skip(1) {
+        let Some((expression, _)) = placeholder.split_once('}') else {
+            continue;
+        };
+        template::check(expression, placeholders)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_of() {
+        let content =
+            "url = \"https://www.livesport.cz/\"\n\n  output = \"a.json\"\noutput_dir = 1\n";
+        assert_eq!(line_of(content, "url"), Some(1));
+        assert_eq!(line_of(content, "output"), Some(3));
+        assert_eq!(line_of(content, "refresh"), None);
+    }
+
+    #[cfg(feature = "server")]
+    #[test]
+    fn test_add_remove_team() {
+        let dir = std::env::temp_dir().join(format!("livesport-teams-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("config.toml");
+        fs::write(&path, "# the home team\nrefresh = 30\n").unwrap();
+
+        let team: teams::Team = toml::from_str(
+            "url = \"https://www.livesport.cz/tym/sparta-praha/zcG9U7N6/\"\nname = \"Sparta Praha\"\noutput = \"sparta.json\"\n",
+        )
+        .unwrap();
+        add_team(&path, &team).unwrap();
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.starts_with("# the home team\nrefresh = 30\n"));
+        assert!(content.contains("[[teams]]\nurl = "));
+        assert!(set_team_token(&path, &team, "coach").unwrap());
+        assert!(fs::read_to_string(&path)
+            .unwrap()
+            .contains("token = \"coach\""));
+        assert!(remove_team(&path, &team).unwrap());
+        assert!(!remove_team(&path, &team).unwrap());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_validate() {
+        let dir = std::env::temp_dir().join(format!("livesport-config-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("config.toml");
+
+        fs::write(
+            &path,
+            "url = \"https://www.livesport.cz/tym/sparta-praha/zcG9U7N6/\"\nteam_name = \"Sparta Praha\"\noutput = \"sparta.json\"\n",
+        )
+        .unwrap();
+        assert!(validate(&path).unwrap().is_empty());
+
+        fs::write(
+            &path,
+            "url = \"https://www.example.com/team/a/b/\"\nteam_name = \"Sparta Praha\"\n\noutput = \"missing/sparta.json\"\nrefresh = 0\n",
+        )
+        .unwrap();
+        let lines: Vec<_> = validate(&path)
+            .unwrap()
+            .into_iter()
+            .map(|error| error.line)
+            .collect();
+        assert_eq!(lines, [Some(1), Some(4), Some(5)]);
+
+        fs::write(&path, "url = \"https://www.livesport.cz/\"\nrefersh = 10\n").unwrap();
+        let errors = validate(&path).unwrap();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].line, Some(2));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
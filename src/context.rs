@@ -0,0 +1,61 @@
+use fantoccini::{Client, Locator};
+use serde::Serialize;
+use std::time::Duration;
+use tokio::time::sleep;
+use url::Url;
+
+use crate::{budget, GameResult, GameTime, MatchRow, ScrapeOptions};
+
+/// Last result, the current (or next) match and the match after it in one document.
+#[derive(Serialize)]
+pub struct MatchContext {
+    pub previous_match: Option<GameResult>,
+    pub current_or_next_match: GameResult,
+    pub following_match: Option<GameResult>,
+}
+
+/// Read the matches around the current one (the first row) from the team page.
+pub async fn get_context(
+    client: &mut Client,
+    url: &Url,
+    team_name: &str,
+    options: &ScrapeOptions,
+    current: GameResult,
+) -> anyhow::Result<MatchContext> {
+    budget::goto(client, url.as_str()).await?;
+    sleep(Duration::from_millis(500)).await;
+
+    let mut previous_match = None;
+    let mut scheduled = Vec::new();
+    for element in client
+        .find_all(Locator::Css(".event__match"))
+        .await?
+        .iter()
+        .skip(1)
+    {
+        let row = MatchRow::read(element, options.timezone).await?;
+        let is_home = row.is_home(team_name);
+        let result = row.into_result(is_home);
+        match result.game_time {
+            GameTime::Played if previous_match.is_none() => previous_match = Some(result),
+            GameTime::WillBePlayed(_) => scheduled.push(result),
+            _ => {}
+        }
+    }
+
+    if !options.stealth {
+        client.goto("about:blank").await?;
+    }
+    let following_match = scheduled
+        .into_iter()
+        .min_by_key(|result| match result.game_time {
+            GameTime::WillBePlayed(Some(countdown)) => countdown,
+            _ => (u64::MAX, 0),
+        });
+
+    Ok(MatchContext {
+        previous_match,
+        current_or_next_match: current,
+        following_match,
+    })
+}
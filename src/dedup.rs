@@ -0,0 +1,164 @@
+//! Match events claimed in Redis, so that redundant crawler instances announce each of them once.
+
+use anyhow::Context;
+use redis::aio::ConnectionManager;
+use std::time::Duration;
+use tokio::time::timeout;
+use tracing::warn;
+use url::Url;
+
+use crate::events::{TimelineEvent, TimelineKind};
+use crate::{secrets, GameResult};
+
+const TIMEOUT: Duration = Duration::from_secs(2);
+/// Claims outlive any match, the keys of the next one differ by its date
+const CLAIM_SECONDS: u64 = 24 * 3600;
+
+pub struct Dedup {
+    client: redis::Client,
+    /// Reconnects by itself once established.
+    connection: Option<ConnectionManager>,
+}
+
+impl Dedup {
+    pub fn new(url: &Url) -> anyhow::Result<Self> {
+        let mut url = url.clone();
+        if let Some(password) =
+            secrets::url_password(&url).context("cannot read the Redis password")?
+        {
+            url.set_password(Some(&password))
+                .map_err(|_| anyhow::anyhow!("Redis URL cannot have a password"))?;
+        }
+        Ok(Self {
+            client: redis::Client::open(url.as_str())?,
+            connection: None,
+        })
+    }
+
+    /// Whether this instance is the first one to announce the event; when Redis cannot be
+    /// reached, rather announce it twice than not at all.
+    pub async fn claim(&mut self, event: &TimelineEvent, result: &GameResult) -> bool {
+        self.claim_key(key(event, result)).await
+    }
+
+    /// Whether this instance is the first one to publish the state (score and game time) of
+    /// the result.
+    pub async fn claim_change(&mut self, result: &GameResult) -> bool {
+        self.claim_key(change_key(result)).await
+    }
+
+    async fn claim_key(&mut self, key: String) -> bool {
+        match timeout(TIMEOUT, self.try_claim(&key)).await {
+            Ok(Ok(claimed)) => claimed,
+            Ok(Err(error)) => {
+                warn!("could not claim {key} in Redis: {error}");
+                true
+            }
+            Err(_) => {
+                warn!("could not claim {key} in Redis: timed out");
+                true
+            }
+        }
+    }
+
+    async fn try_claim(&mut self, key: &str) -> redis::RedisResult<bool> {
+        if self.connection.is_none() {
+            self.connection = Some(ConnectionManager::new(self.client.clone()).await?);
+        }
+        let connection = self.connection.as_mut().expect("connected above");
+        let reply: Option<String> = redis::cmd("SET")
+            .arg(key)
+            .arg(std::process::id())
+            .arg("NX")
+            .arg("EX")
+            .arg(CLAIM_SECONDS)
+            .query_async(connection)
+            .await?;
+        Ok(reply.is_some())
+    }
+}
+
+/// Key the same on every instance: the instances scrape at different times, so a goal is
+/// told apart by the score only and the minute of the other events only by the period.
+fn key(event: &TimelineEvent, result: &GameResult) -> String {
+    let statistic = event
+        .statistic
+        .as_ref()
+        .map(|statistic| {
+            format!(
+                ":{}={}:{}",
+                statistic.name, statistic.my_team, statistic.opponent_team
+            )
+        })
+        .unwrap_or_default();
+    let scorer = event
+        .goal
+        .as_ref()
+        .map(|goal| format!(":{}", goal.scorer))
+        .unwrap_or_default();
+    format!(
+        "livesport-crawler:{}:{}:{}:{:?}:{}:{}:{}{statistic}{scorer}",
+        result.my_team,
+        result.opponent_team,
+        event.generated.format("%Y-%m-%d"),
+        event.kind,
+        event.my_team_score,
+        event.opponent_team_score,
+        event
+            .minute
+            .filter(|_| !matches!(
+                event.kind,
+                TimelineKind::GoalFor | TimelineKind::GoalAgainst | TimelineKind::PlayerGoal
+            ))
+            .map(|minute| result
+                .periods
+                .completed(minute.saturating_sub(1))
+                .to_string())
+            .unwrap_or_default()
+    )
+}
+
+fn change_key(result: &GameResult) -> String {
+    format!(
+        "livesport-crawler:{}:{}:{}:change:{}:{}:{:?}",
+        result.my_team,
+        result.opponent_team,
+        result.generated.format("%Y-%m-%d"),
+        result.my_team_score,
+        result.opponent_team_score,
+        result.game_time
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events;
+    use crate::simulation::sample;
+    use crate::GameTime;
+
+    #[test]
+    fn test_key() {
+        let previous = sample(GameTime::Playing(19), 1, 0);
+        let latest = GameResult {
+            my_team_score: 2,
+            game_time: GameTime::Playing(20),
+            ..previous.clone()
+        };
+        let events = events::between(&previous, &latest);
+        assert!(matches!(events[0].kind, TimelineKind::GoalFor));
+        let key = key(&events[0], &latest);
+        assert!(key.starts_with("livesport-crawler:Sparta Praha:"));
+        assert!(key.ends_with(":GoalFor:2:0:"));
+
+        // the same goal seen by another instance a minute later, in the next period
+        let later = GameResult {
+            game_time: GameTime::Playing(21),
+            ..latest.clone()
+        };
+        assert_eq!(
+            super::key(&events::between(&previous, &later)[0], &later),
+            key
+        );
+    }
+}
@@ -0,0 +1,88 @@
+//! Native desktop notifications (libnotify, macOS or Windows toasts) of goals and full time.
+
+use notify_rust::Notification;
+use tracing::warn;
+
+use crate::events::{TimelineEvent, TimelineKind};
+use crate::observer::Observer;
+use crate::GameResult;
+
+pub struct DesktopNotifier;
+
+/// Summary and body of the notification, `None` for the events not worth one.
+fn describe(event: &TimelineEvent, latest: &GameResult) -> Option<(String, String)> {
+    let minute = event
+        .minute
+        .map(|minute| format!(" ({minute}')"))
+        .unwrap_or_default();
+    let score = format!(
+        "{} {}:{} {}",
+        latest.my_team, event.my_team_score, event.opponent_team_score, latest.opponent_team
+    );
+    match event.kind {
+        TimelineKind::GoalFor => Some((format!("Goal! {}", latest.my_team), score + &minute)),
+        TimelineKind::GoalAgainst => {
+            Some((format!("Goal of {}", latest.opponent_team), score + &minute))
+        }
+        TimelineKind::Finished => Some(("Full time".to_string(), score)),
+        TimelineKind::PlayerGoal => event
+            .goal
+            .as_ref()
+            .map(|goal| (format!("{} scored", goal.scorer), score + &minute)),
+        _ => None,
+    }
+}
+
+impl Observer for DesktopNotifier {
+    fn on_event(&mut self, event: &TimelineEvent, latest: &GameResult) {
+        let Some((summary, body)) = describe(event, latest) else {
+            return;
+        };
+        // talking to the notification daemon blocks
+        tokio::task::spawn_blocking(move || {
+            if let Err(error) = Notification::new()
+                .appname("livesport-crawler")
+                .summary(&summary)
+                .body(&body)
+                .show()
+            {
+                warn!("could not show the desktop notification: {error}");
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events;
+    use crate::simulation::sample;
+    use crate::GameTime;
+
+    #[test]
+    fn test_describe() {
+        let previous = sample(GameTime::Playing(58), 0, 0);
+        let latest = GameResult {
+            my_team_score: 1,
+            game_time: GameTime::Played,
+            ..previous.clone()
+        };
+        let described: Vec<_> = events::between(&previous, &latest)
+            .iter()
+            .filter_map(|event| describe(event, &latest))
+            .collect();
+        assert_eq!(
+            described,
+            [
+                (
+                    "Goal! Sparta Praha".to_string(),
+                    "Sparta Praha 1:0 Kometa Brno".to_string()
+                ),
+                (
+                    "Full time".to_string(),
+                    "Sparta Praha 1:0 Kometa Brno".to_string()
+                ),
+            ]
+        );
+    }
+}
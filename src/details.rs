@@ -0,0 +1,357 @@
+use anyhow::Context;
+use fantoccini::elements::Element;
+use fantoccini::{Client, Locator};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tokio::time::sleep;
+use url::Url;
+
+use crate::budget;
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Statistic {
+    pub name: String,
+    pub my_team: String,
+    pub opponent_team: String,
+}
+
+/// First path segment of match detail pages on the language mirrors of the site.
+const MATCH_PATHS: [&str; 8] = [
+    "zapas",
+    "match",
+    "spiel",
+    "partido",
+    "mecz",
+    "partita",
+    "wedstrijd",
+    "jogo",
+];
+
+/// First path segment of the basketball section on the language mirrors of the site.
+const BASKETBALL_PATHS: [&str; 5] = [
+    "basketbal",
+    "basketball",
+    "koszykowka",
+    "pallacanestro",
+    "baloncesto",
+];
+
+/// Whether the URL (of the sport link in the match header) is the basketball section.
+pub(crate) fn is_basketball_url(url: &Url) -> bool {
+    url.path_segments()
+        .and_then(|mut segments| segments.next())
+        .is_some_and(|first| BASKETBALL_PATHS.contains(&first))
+}
+
+/// Whether the URL is a match detail page rather than a team page.
+pub fn is_match_url(url: &Url) -> bool {
+    url.path_segments()
+        .and_then(|mut segments| segments.next())
+        .is_some_and(|first| MATCH_PATHS.contains(&first))
+}
+
+/// URL of the match detail page linked from the match row.
+pub async fn match_url(row: &Element) -> anyhow::Result<Url> {
+    let href = row
+        .find(Locator::Css("a.eventRowLink"))
+        .await
+        .context("could not find a.eventRowLink element")?
+        // the property, unlike the attribute, is resolved to an absolute URL
+        .prop("href")
+        .await?
+        .context("match link has no href")?;
+    Ok(Url::parse(&href)?)
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Goal {
+    pub minute: String,
+    pub my_team: bool,
+    pub scorer: String,
+    pub assists: Vec<String>,
+    pub player_of_interest: bool,
+}
+
+/// Penalty of a player of my team (`my_team`) or of the opponent, `duration` in minutes.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Penalty {
+    pub minute: String,
+    pub my_team: bool,
+    pub player: String,
+    pub duration: u64,
+}
+
+pub struct ScrapedPenalty {
+    pub home: bool,
+    pub minute: String,
+    pub player: String,
+    pub duration: u64,
+}
+
+impl ScrapedPenalty {
+    pub fn into_penalty(self, is_home: bool) -> Penalty {
+        Penalty {
+            minute: self.minute,
+            my_team: self.home == is_home,
+            player: self.player,
+            duration: self.duration,
+        }
+    }
+}
+
+/// Minutes of a penalty incident text like `Novák J. (Hákování) 2 min.`.
+fn parse_penalty_minutes(text: &str) -> Option<u64> {
+    let words: Vec<_> = text.split_whitespace().collect();
+    words.windows(2).find_map(|pair| {
+        pair[1]
+            .to_lowercase()
+            .starts_with("min")
+            .then(|| pair[0].parse().ok())
+            .flatten()
+    })
+}
+
+pub struct ScrapedGoal {
+    pub home: bool,
+    pub minute: String,
+    pub scorer: String,
+    pub assists: Vec<String>,
+}
+
+impl ScrapedGoal {
+    /// Convert to a goal seen from my team's perspective, tagging the watched players.
+    pub fn into_goal(self, is_home: bool, players: &[String]) -> Goal {
+        let player_of_interest = players.iter().any(|player| {
+            let player = player.to_lowercase();
+            std::iter::once(&self.scorer)
+                .chain(&self.assists)
+                .any(|name| name.to_lowercase().contains(&player))
+        });
+        Goal {
+            minute: self.minute,
+            my_team: self.home == is_home,
+            scorer: self.scorer,
+            assists: self.assists,
+            player_of_interest,
+        }
+    }
+}
+
+/// Content of the match detail page, home team first.
+pub struct MatchDetails {
+    pub goals: Vec<ScrapedGoal>,
+    pub penalties: Vec<ScrapedPenalty>,
+    pub statistics: Vec<(String, String, String)>,
+    pub broadcast: Vec<String>,
+}
+
+const BROADCAST_SELECTOR: &str = ".br__broadcast, [data-testid='wcl-broadcast']";
+
+/// Open the match page of a scheduled match and read where it is broadcast.
+pub async fn get_broadcast(client: &mut Client, match_url: &Url) -> anyhow::Result<Vec<String>> {
+    budget::goto(client, match_url.as_str()).await?;
+    sleep(Duration::from_millis(500)).await;
+    read_broadcast(client).await
+}
+
+async fn read_broadcast(client: &mut Client) -> anyhow::Result<Vec<String>> {
+    let mut broadcast = Vec::new();
+    for element in client.find_all(Locator::Css(BROADCAST_SELECTOR)).await? {
+        let name = element.text().await?.trim().to_string();
+        if !name.is_empty() && !broadcast.contains(&name) {
+            broadcast.push(name);
+        }
+    }
+    Ok(broadcast)
+}
+
+/// Open the match page, read the goals from its summary and then switch to the statistics tab.
+pub async fn get_details(client: &mut Client, match_url: &Url) -> anyhow::Result<MatchDetails> {
+    budget::goto(client, match_url.as_str()).await?;
+    sleep(Duration::from_millis(500)).await;
+
+    let (goals, penalties) = get_incidents(client).await?;
+    let broadcast = read_broadcast(client).await?;
+
+    client
+        .find(Locator::Css(
+            "a[href*='statistiky-zapasu'], a[href*='match-statistics']",
+        ))
+        .await
+        .context("could not find statistics tab")?
+        .click()
+        .await?;
+    sleep(Duration::from_millis(500)).await;
+
+    let mut statistics = Vec::new();
+    for row in client
+        .find_all(Locator::Css("div[data-testid='wcl-statistics']"))
+        .await?
+    {
+        let name = row
+            .find(Locator::Css("[data-testid='wcl-statistics-category']"))
+            .await?
+            .text()
+            .await?;
+        let values = row
+            .find_all(Locator::Css("[data-testid='wcl-statistics-value']"))
+            .await?;
+        let [home, away] = values.as_slice() else {
+            continue;
+        };
+        statistics.push((name, home.text().await?, away.text().await?));
+    }
+
+    Ok(MatchDetails {
+        goals,
+        penalties,
+        statistics,
+        broadcast,
+    })
+}
+
+async fn get_incidents(
+    client: &mut Client,
+) -> anyhow::Result<(Vec<ScrapedGoal>, Vec<ScrapedPenalty>)> {
+    let mut goals = Vec::new();
+    let mut penalties = Vec::new();
+    for row in client
+        .find_all(Locator::Css(".smv__participantRow"))
+        .await?
+    {
+        let class = row.attr("class").await?.unwrap_or_default();
+        let minute = row
+            .find(Locator::Css(".smv__timeBox"))
+            .await?
+            .text()
+            .await?;
+
+        // only goals show the updated score next to the incident
+        if row
+            .find(Locator::Css(
+                ".smv__incidentHomeScore, .smv__incidentAwayScore",
+            ))
+            .await
+            .is_err()
+        {
+            if let Some(duration) = parse_penalty_minutes(&row.text().await?) {
+                penalties.push(ScrapedPenalty {
+                    home: class.contains("smv__homeParticipant"),
+                    minute,
+                    player: row
+                        .find(Locator::Css(".smv__playerName"))
+                        .await?
+                        .text()
+                        .await?,
+                    duration,
+                });
+            }
+            continue;
+        }
+
+        let scorer = row
+            .find(Locator::Css(".smv__playerName"))
+            .await?
+            .text()
+            .await?;
+        let mut assists = Vec::new();
+        for assist in row.find_all(Locator::Css(".smv__assist")).await? {
+            let text = assist.text().await?;
+            assists.push(text.trim_matches(|c| c == '(' || c == ')').to_string());
+        }
+
+        goals.push(ScrapedGoal {
+            home: class.contains("smv__homeParticipant"),
+            minute,
+            scorer,
+            assists,
+        });
+    }
+
+    Ok((goals, penalties))
+}
+
+/// Statistics whose values differ from the previous sample of the same match.
+pub fn changed_statistics<'a>(
+    previous: &[Statistic],
+    current: &'a [Statistic],
+) -> Vec<&'a Statistic> {
+    current
+        .iter()
+        .filter(|statistic| !previous.contains(statistic))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_match_url() {
+        let url = |url: &str| Url::parse(url).unwrap();
+        assert!(is_match_url(&url(
+            "https://www.livesport.cz/zapas/hokej/sparta-praha-zcG9U7N6/kometa-brno-8bSMBOq4/?mid=x"
+        )));
+        assert!(is_match_url(&url(
+            "https://www.flashscore.com/match/IVi1nt8B/#/match-summary"
+        )));
+        assert!(!is_match_url(&url(
+            "https://www.livesport.cz/tym/sparta-praha/zcG9U7N6/"
+        )));
+    }
+
+    #[test]
+    fn test_is_basketball_url() {
+        let url = |url| Url::parse(url).unwrap();
+        assert!(is_basketball_url(&url(
+            "https://www.livesport.cz/basketbal/"
+        )));
+        assert!(is_basketball_url(&url(
+            "https://www.flashscore.com/basketball/usa/nba/"
+        )));
+        assert!(!is_basketball_url(&url("https://www.livesport.cz/hokej/")));
+        assert!(!is_basketball_url(&url("https://www.livesport.cz/")));
+    }
+
+    #[test]
+    fn test_player_of_interest() {
+        let goal = ScrapedGoal {
+            home: false,
+            minute: "34'".to_string(),
+            scorer: "Novák J.".to_string(),
+            assists: vec!["Dvořák P.".to_string()],
+        };
+        let goal = goal.into_goal(false, &["dvořák".to_string()]);
+        assert!(goal.my_team);
+        assert!(goal.player_of_interest);
+    }
+
+    #[test]
+    fn test_parse_penalty_minutes() {
+        assert_eq!(parse_penalty_minutes("Novák J. (Hákování) 2 min."), Some(2));
+        assert_eq!(parse_penalty_minutes("Smith (Boarding) 5 min"), Some(5));
+        assert_eq!(parse_penalty_minutes("Novák J. (Dvořák P.)"), None);
+    }
+
+    fn statistic(name: &str, my_team: &str, opponent_team: &str) -> Statistic {
+        Statistic {
+            name: name.to_string(),
+            my_team: my_team.to_string(),
+            opponent_team: opponent_team.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_changed_statistics() {
+        let previous = [statistic("Shots", "10", "8"), statistic("Saves", "7", "9")];
+        let current = [
+            statistic("Shots", "12", "8"),
+            statistic("Saves", "7", "9"),
+            statistic("Penalties", "1", "0"),
+        ];
+        assert_eq!(
+            changed_statistics(&previous, &current),
+            [&current[0], &current[2]]
+        );
+    }
+}
@@ -0,0 +1,220 @@
+use anyhow::Context;
+use std::time::{Duration, Instant};
+use url::Url;
+
+use crate::details;
+
+/// Backoff after the first failure of a URL, doubled with every further one ...
+const BACKOFF: Duration = Duration::from_secs(30);
+/// ... up to this limit.
+const MAX_BACKOFF: Duration = Duration::from_secs(30 * 60);
+
+/// Livesport site together with the path segment used for team pages.
+struct Domain {
+    host: &'static str,
+    team_path: &'static str,
+}
+
+const DOMAINS: &[Domain] = &[
+    Domain {
+        host: "livesport.cz",
+        team_path: "tym",
+    },
+    Domain {
+        host: "livesport.sk",
+        team_path: "tim",
+    },
+    Domain {
+        host: "flashscore.com",
+        team_path: "team",
+    },
+    Domain {
+        host: "flashscore.co.uk",
+        team_path: "team",
+    },
+    Domain {
+        host: "flashscore.de",
+        team_path: "team",
+    },
+    Domain {
+        host: "flashscore.pl",
+        team_path: "druzyna",
+    },
+    Domain {
+        host: "flashscore.fr",
+        team_path: "equipe",
+    },
+    Domain {
+        host: "livescore.in",
+        team_path: "team",
+    },
+    Domain {
+        host: "soccer24.com",
+        team_path: "team",
+    },
+    Domain {
+        host: "diretta.it",
+        team_path: "squadra",
+    },
+];
+
+fn find_domain(host: &str) -> anyhow::Result<&'static Domain> {
+    let host = host.strip_prefix("www.").unwrap_or(host);
+    DOMAINS
+        .iter()
+        .find(|domain| domain.host == host)
+        .with_context(|| format!("{host} is not a known Livesport domain"))
+}
+
+/// Domain independent identification of a team page, e.g. `sparta-praha/zcG9U7N6`.
+#[derive(Debug, PartialEq)]
+pub struct TeamPage {
+    slug: String,
+    id: String,
+}
+
+impl TeamPage {
+    pub fn parse(url: &Url) -> anyhow::Result<Self> {
+        let domain = find_domain(url.host_str().context("URL must contain a host")?)?;
+        let segments: Vec<_> = url
+            .path_segments()
+            .context("URL must have a path")?
+            .filter(|segment| !segment.is_empty())
+            .collect();
+        match segments.as_slice() {
+            [team_path, slug, id] if *team_path == domain.team_path => Ok(Self {
+                slug: slug.to_string(),
+                id: id.to_string(),
+            }),
+            _ => anyhow::bail!(
+                "{url} is not a team page, expected https://{}/{}/<team>/<id>/",
+                domain.host,
+                domain.team_path
+            ),
+        }
+    }
+
+    /// URL of the same team page on another domain.
+    pub fn url_on(&self, host: &str) -> anyhow::Result<Url> {
+        let domain = find_domain(host)?;
+        Ok(Url::parse(&format!(
+            "https://www.{}/{}/{}/{}/",
+            domain.host, domain.team_path, self.slug, self.id
+        ))?)
+    }
+}
+
+/// Team page URLs on the primary domain and all the configured mirrors.
+pub struct Mirrors {
+    urls: Vec<Url>,
+    current: usize,
+    failures: Vec<u32>,
+    retry_at: Vec<Option<Instant>>,
+}
+
+impl Mirrors {
+    pub fn new(url: &Url, mirrors: &[String]) -> anyhow::Result<Self> {
+        let mut urls = vec![url.clone()];
+        // the id of a match on the mirrors cannot be derived from its URL
+        if !details::is_match_url(url) {
+            let page = TeamPage::parse(url)?;
+            for mirror in mirrors {
+                urls.push(page.url_on(mirror)?);
+            }
+        }
+        Ok(Self {
+            failures: vec![0; urls.len()],
+            retry_at: vec![None; urls.len()],
+            urls,
+            current: 0,
+        })
+    }
+
+    pub fn current(&self) -> &Url {
+        &self.urls[self.current]
+    }
+
+    /// Whether the current URL is not backing off after its failures.
+    pub fn available(&self, now: Instant) -> bool {
+        self.retry_at[self.current].is_none_or(|retry_at| retry_at <= now)
+    }
+
+    pub fn succeed(&mut self) {
+        self.failures[self.current] = 0;
+        self.retry_at[self.current] = None;
+    }
+
+    /// Back off the current URL exponentially and switch to the mirror available the soonest;
+    /// returns false if there is no other one.
+    pub fn fail(&mut self, now: Instant) -> bool {
+        let failures = &mut self.failures[self.current];
+        *failures += 1;
+        let backoff = BACKOFF
+            .saturating_mul(2u32.saturating_pow(*failures - 1))
+            .min(MAX_BACKOFF);
+        self.retry_at[self.current] = Some(now + backoff);
+
+        let previous = self.current;
+        self.current = (1..=self.urls.len())
+            .map(|offset| (self.current + offset) % self.urls.len())
+            .min_by_key(|&index| self.retry_at[index].filter(|retry_at| *retry_at > now))
+            .unwrap_or(self.current);
+        self.current != previous
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_team_page() {
+        let page = TeamPage::parse(
+            &Url::parse("https://www.livesport.cz/tym/sparta-praha/zcG9U7N6/").unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            page.url_on("flashscore.com").unwrap().as_str(),
+            "https://www.flashscore.com/team/sparta-praha/zcG9U7N6/"
+        );
+        assert!(
+            TeamPage::parse(&Url::parse("https://www.example.com/team/a/b/").unwrap()).is_err()
+        );
+        assert!(
+            TeamPage::parse(&Url::parse("https://www.flashscore.com/match/abc/").unwrap()).is_err()
+        );
+    }
+
+    #[test]
+    fn test_match_url() {
+        let url = Url::parse("https://www.flashscore.com/match/IVi1nt8B/").unwrap();
+        let mut mirrors = Mirrors::new(&url, &["www.livesport.cz".to_string()]).unwrap();
+        assert_eq!(mirrors.current(), &url);
+        assert!(!mirrors.fail(Instant::now()));
+    }
+
+    #[test]
+    fn test_mirrors() {
+        let url = Url::parse("https://www.livesport.cz/tym/sparta-praha/zcG9U7N6/").unwrap();
+        let mut mirrors = Mirrors::new(&url, &["www.flashscore.com".to_string()]).unwrap();
+        let now = Instant::now();
+        assert!(mirrors.fail(now));
+        assert_eq!(mirrors.current().host_str(), Some("www.flashscore.com"));
+        assert!(mirrors.available(now));
+
+        // both back off now, the primary one becomes available the soonest
+        let later = now + Duration::from_secs(10);
+        assert!(mirrors.fail(later));
+        assert_eq!(mirrors.current(), &url);
+        assert!(!mirrors.available(later));
+
+        // the second failure doubles the backoff
+        let later = now + Duration::from_secs(30);
+        assert!(mirrors.available(later));
+        assert!(mirrors.fail(later));
+        assert_eq!(mirrors.current().host_str(), Some("www.flashscore.com"));
+        assert!(mirrors.available(now + Duration::from_secs(40)));
+        mirrors.succeed();
+        assert!(mirrors.available(later));
+    }
+}
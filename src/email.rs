@@ -0,0 +1,137 @@
+//! Emails at the start of the match and with the final score summary, for those not using
+//! chat apps.
+
+use anyhow::Context;
+use lettre::message::Mailbox;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use tracing::{info, warn};
+
+use crate::events::{TimelineEvent, TimelineKind};
+use crate::observer::Observer;
+use crate::{summary, GameResult};
+
+pub struct EmailNotifier {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+    to: Vec<Mailbox>,
+    summary_template: String,
+}
+
+impl EmailNotifier {
+    /// Send through an `smtps://[USER:PASSWORD@]HOST[:PORT]` server, or `smtp://` with
+    /// `?tls=required` for STARTTLS; the final score is written with the summary template.
+    pub fn new(
+        server: &str,
+        from: &str,
+        to: &[String],
+        summary_template: &str,
+    ) -> anyhow::Result<Self> {
+        anyhow::ensure!(!to.is_empty(), "no email recipients");
+        Ok(Self {
+            transport: AsyncSmtpTransport::<Tokio1Executor>::from_url(server)
+                .context("invalid SMTP server URL")?
+                .build(),
+            from: from
+                .parse()
+                .with_context(|| format!("invalid sender {from}"))?,
+            to: to
+                .iter()
+                .map(|to| {
+                    to.parse()
+                        .with_context(|| format!("invalid recipient {to}"))
+                })
+                .collect::<anyhow::Result<_>>()?,
+            summary_template: summary_template.to_string(),
+        })
+    }
+
+    /// Subject and body of the email, `None` for the events not worth one.
+    fn compose(&self, event: &TimelineEvent, latest: &GameResult) -> Option<(String, String)> {
+        match event.kind {
+            TimelineKind::Started => Some((
+                format!("{} – {} has started", latest.my_team, latest.opponent_team),
+                format!(
+                    "The match {} – {} has just started.\n",
+                    latest.my_team, latest.opponent_team
+                ),
+            )),
+            TimelineKind::Finished => Some((
+                format!(
+                    "Final score: {} {}:{} {}",
+                    latest.my_team,
+                    latest.my_team_score,
+                    latest.opponent_team_score,
+                    latest.opponent_team
+                ),
+                summary::render(&self.summary_template, latest),
+            )),
+            _ => None,
+        }
+    }
+}
+
+impl Observer for EmailNotifier {
+    fn announces(&self) -> bool {
+        true
+    }
+
+    fn on_event(&mut self, event: &TimelineEvent, latest: &GameResult) {
+        let Some((subject, body)) = self.compose(event, latest) else {
+            return;
+        };
+        let mut builder = Message::builder().from(self.from.clone()).subject(&subject);
+        for to in &self.to {
+            builder = builder.to(to.clone());
+        }
+        let message = match builder.body(body) {
+            Ok(message) => message,
+            Err(error) => {
+                warn!("could not compose the email {subject}: {error}");
+                return;
+            }
+        };
+        let transport = self.transport.clone();
+        tokio::spawn(async move {
+            match transport.send(message).await {
+                Ok(_) => info!("email {subject} sent"),
+                Err(error) => warn!("could not send the email {subject}: {error}"),
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events;
+    use crate::simulation::sample;
+    use crate::GameTime;
+
+    #[tokio::test]
+    async fn test_compose() {
+        let notifier = EmailNotifier::new(
+            "smtp://localhost:2525",
+            "Crawler <crawler@example.com>",
+            &["fan@example.com".to_string()],
+            "{my_team} won {my_team_score}:{opponent_team_score}",
+        )
+        .unwrap();
+        let previous = sample(GameTime::Playing(60), 3, 1);
+        let latest = GameResult {
+            game_time: GameTime::Played,
+            ..previous.clone()
+        };
+        let composed: Vec<_> = events::between(&previous, &latest)
+            .iter()
+            .filter_map(|event| notifier.compose(event, &latest))
+            .collect();
+        assert_eq!(
+            composed,
+            [(
+                "Final score: Sparta Praha 3:1 Kometa Brno".to_string(),
+                "Sparta Praha won 3:1".to_string()
+            )]
+        );
+        assert!(EmailNotifier::new("smtp://localhost", "crawler", &[], "").is_err());
+    }
+}
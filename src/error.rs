@@ -0,0 +1,149 @@
+//! Errors of the library API, classified so that the callers (and their retry policies) can
+//! react to each class differently.
+
+use fantoccini::error::CmdError;
+use std::fmt;
+
+#[derive(Debug)]
+pub enum CrawlerError {
+    /// No browser session could be started on the WebDriver server
+    DriverStartup(anyhow::Error),
+    /// The browser, its window or the connection to the WebDriver server went away
+    SessionLost(anyhow::Error),
+    /// An element expected on the page is missing, e.g. after a redesign of the site
+    SelectorMissing {
+        field: String,
+    },
+    /// Text of the page that cannot be interpreted
+    ParseFailed {
+        field: String,
+        raw: String,
+    },
+    Timeout(anyhow::Error),
+    Other(anyhow::Error),
+}
+
+impl CrawlerError {
+    /// Whether trying again (in a new session for `SessionLost`) may help, unlike for a page
+    /// the scraper does not understand.
+    pub fn is_transient(&self) -> bool {
+        matches!(
+            self,
+            CrawlerError::SessionLost(_) | CrawlerError::Timeout(_) | CrawlerError::Other(_)
+        )
+    }
+}
+
+impl fmt::Display for CrawlerError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CrawlerError::DriverStartup(error) => {
+                write!(f, "cannot start the browser session: {error:#}")
+            }
+            CrawlerError::SessionLost(error) => write!(f, "browser session lost: {error:#}"),
+            CrawlerError::SelectorMissing { field } => write!(f, "could not find {field} element"),
+            CrawlerError::ParseFailed { field, raw } => write!(f, "cannot parse {field} {raw:?}"),
+            CrawlerError::Timeout(error) => write!(f, "timed out: {error:#}"),
+            CrawlerError::Other(error) => write!(f, "{error:#}"),
+        }
+    }
+}
+
+impl std::error::Error for CrawlerError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CrawlerError::DriverStartup(error)
+            | CrawlerError::SessionLost(error)
+            | CrawlerError::Timeout(error)
+            | CrawlerError::Other(error) => Some(error.as_ref()),
+            CrawlerError::SelectorMissing { .. } | CrawlerError::ParseFailed { .. } => None,
+        }
+    }
+}
+
+/// Classify an error of the internals by the first cause telling its class.
+impl From<anyhow::Error> for CrawlerError {
+    fn from(error: anyhow::Error) -> Self {
+        let error = match error.downcast::<CrawlerError>() {
+            Ok(error) => return error,
+            Err(error) => error,
+        };
+        match error.chain().find_map(classify) {
+            Some(Class::Page(page)) => page,
+            Some(Class::SessionLost) => CrawlerError::SessionLost(error),
+            Some(Class::Timeout) => CrawlerError::Timeout(error),
+            None => CrawlerError::Other(error),
+        }
+    }
+}
+
+enum Class {
+    /// What the page looked like, without the context of the internals
+    Page(CrawlerError),
+    SessionLost,
+    Timeout,
+}
+
+fn classify(cause: &(dyn std::error::Error + 'static)) -> Option<Class> {
+    if let Some(cause) = cause.downcast_ref::<CrawlerError>() {
+        return match cause {
+            CrawlerError::SelectorMissing { field } => {
+                Some(Class::Page(CrawlerError::SelectorMissing {
+                    field: field.clone(),
+                }))
+            }
+            CrawlerError::ParseFailed { field, raw } => {
+                Some(Class::Page(CrawlerError::ParseFailed {
+                    field: field.clone(),
+                    raw: raw.clone(),
+                }))
+            }
+            _ => None,
+        };
+    }
+    if let Some(cause) = cause.downcast_ref::<CmdError>() {
+        if matches!(cause, CmdError::Lost(_))
+            || cause.is_invalid_session_id()
+            || cause.is_no_such_window()
+        {
+            return Some(Class::SessionLost);
+        }
+        if matches!(cause, CmdError::WaitTimeout) || cause.is_timeout() || cause.is_script_timeout()
+        {
+            return Some(Class::Timeout);
+        }
+    }
+    cause
+        .is::<tokio::time::error::Elapsed>()
+        .then_some(Class::Timeout)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn test_classify() {
+        let missing: anyhow::Result<()> = Err(CrawlerError::SelectorMissing {
+            field: ".event__match".to_string(),
+        }
+        .into());
+        let error = CrawlerError::from(missing.context("cannot read the team page").unwrap_err());
+        assert!(matches!(
+            &error,
+            CrawlerError::SelectorMissing { field } if field == ".event__match"
+        ));
+        assert!(!error.is_transient());
+
+        let lost = anyhow::Error::new(CmdError::Lost(std::io::ErrorKind::BrokenPipe.into()));
+        assert!(matches!(
+            CrawlerError::from(lost.context("cannot load the page")),
+            CrawlerError::SessionLost(_)
+        ));
+        assert!(matches!(
+            CrawlerError::from(anyhow::anyhow!("something else")),
+            CrawlerError::Other(_)
+        ));
+    }
+}
@@ -0,0 +1,126 @@
+use chrono::{DateTime, Local};
+
+use crate::{GameTime, Periods};
+
+/// Wall-clock minutes one minute of play takes because of the stopped clock.
+const REAL_MINUTES_PER_GAME_MINUTE: f64 = 1.7;
+const INTERMISSION_MINUTES: f64 = 18.0;
+
+/// Rough wall-clock minutes left until the final whistle of a running match.
+pub fn time_remaining(game_time: &GameTime, periods: Periods) -> Option<u64> {
+    let (minute, intermissions) = match *game_time {
+        GameTime::Playing(minute) => {
+            let period = periods.completed(minute.saturating_sub(1));
+            (minute, periods.count.saturating_sub(period + 1) as f64)
+        }
+        // assume we are in the middle of the current intermission
+        GameTime::BreakAfter(minute) => {
+            let period = periods.completed(minute);
+            (
+                minute,
+                periods.count.saturating_sub(period + 1) as f64 + 0.5,
+            )
+        }
+        GameTime::WillBePlayed(_) | GameTime::Played => return None,
+    };
+    let play = periods.regulation().saturating_sub(minute) as f64;
+    Some(
+        (play * REAL_MINUTES_PER_GAME_MINUTE + intermissions * INTERMISSION_MINUTES).round() as u64,
+    )
+}
+
+/// Rough wall-clock start of a running match, for a crawler joining it in the middle.
+pub fn started_at(
+    game_time: &GameTime,
+    periods: Periods,
+    generated: DateTime<Local>,
+) -> Option<DateTime<Local>> {
+    let (minute, intermissions) = match *game_time {
+        GameTime::Playing(minute) => (minute, periods.completed(minute.saturating_sub(1))),
+        GameTime::BreakAfter(minute) => (minute, periods.completed(minute)),
+        GameTime::WillBePlayed(_) | GameTime::Played => return None,
+    };
+    let minutes =
+        minute as f64 * REAL_MINUTES_PER_GAME_MINUTE + intermissions as f64 * INTERMISSION_MINUTES;
+    Some(generated - chrono::Duration::minutes(minutes.round() as i64))
+}
+
+/// Rough minute of play in the given period (counted from 1) of a match which started
+/// `elapsed` wall-clock minutes ago, for a source without the running clock.
+pub fn minute_in_period(period: u64, periods: Periods, elapsed: i64) -> u64 {
+    let start = periods.minutes * period.saturating_sub(1);
+    let played = (elapsed as f64 - period.saturating_sub(1) as f64 * INTERMISSION_MINUTES)
+        / REAL_MINUTES_PER_GAME_MINUTE;
+    (played.max(0.0).round() as u64).clamp(start + 1, start + periods.minutes)
+}
+
+pub fn end_at(
+    game_time: &GameTime,
+    periods: Periods,
+    generated: DateTime<Local>,
+) -> Option<DateTime<Local>> {
+    time_remaining(game_time, periods)
+        .map(|minutes| generated + chrono::Duration::minutes(minutes as i64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_time_remaining() {
+        assert_eq!(time_remaining(&GameTime::Played, Periods::HOCKEY), None);
+        // two whole periods and both intermissions
+        assert_eq!(
+            time_remaining(&GameTime::Playing(20), Periods::HOCKEY),
+            Some(104)
+        );
+        assert_eq!(
+            time_remaining(&GameTime::Playing(21), Periods::HOCKEY),
+            Some(84)
+        );
+        assert_eq!(
+            time_remaining(&GameTime::BreakAfter(40), Periods::HOCKEY),
+            Some(43)
+        );
+        assert_eq!(
+            time_remaining(&GameTime::Playing(60), Periods::HOCKEY),
+            Some(0)
+        );
+        // overtime
+        assert_eq!(
+            time_remaining(&GameTime::Playing(63), Periods::HOCKEY),
+            Some(0)
+        );
+        // the second basketball quarter, two intermissions to go
+        assert_eq!(
+            time_remaining(&GameTime::Playing(15), Periods::BASKETBALL),
+            Some(79)
+        );
+    }
+
+    #[test]
+    fn test_minute_in_period() {
+        // 17 wall-clock minutes are 10 minutes of play
+        assert_eq!(minute_in_period(1, Periods::HOCKEY, 17), 10);
+        // kept within the period the feed says is being played
+        assert_eq!(minute_in_period(2, Periods::HOCKEY, 17), 21);
+        assert_eq!(minute_in_period(1, Periods::HOCKEY, 90), 20);
+        assert_eq!(minute_in_period(3, Periods::BASKETBALL, 0), 21);
+    }
+
+    #[test]
+    fn test_started_at() {
+        let now = Local::now();
+        assert_eq!(started_at(&GameTime::Played, Periods::HOCKEY, now), None);
+        assert_eq!(
+            started_at(&GameTime::Playing(10), Periods::HOCKEY, now),
+            Some(now - chrono::Duration::minutes(17))
+        );
+        // one period and the intermission after it
+        assert_eq!(
+            started_at(&GameTime::Playing(30), Periods::HOCKEY, now),
+            Some(now - chrono::Duration::minutes(69))
+        );
+    }
+}
@@ -0,0 +1,408 @@
+//! What happened between two results of the same team, shared by the sinks announcing it.
+
+use chrono::{DateTime, Local};
+use serde::Serialize;
+use std::mem;
+
+use crate::details::{self, Goal, Statistic};
+use crate::{GameResult, GameTime};
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimelineKind {
+    Started,
+    GoalFor,
+    GoalAgainst,
+    Break,
+    /// Start of the next period after a break
+    Resumed,
+    Finished,
+    StatisticChanged,
+    /// Goal of a player of interest (`--player`), after the goal itself
+    PlayerGoal,
+}
+
+impl TimelineKind {
+    pub const ALL: &'static [TimelineKind] = &[
+        TimelineKind::Started,
+        TimelineKind::GoalFor,
+        TimelineKind::GoalAgainst,
+        TimelineKind::Break,
+        TimelineKind::Resumed,
+        TimelineKind::Finished,
+        TimelineKind::StatisticChanged,
+        TimelineKind::PlayerGoal,
+    ];
+
+    /// Name of the kind as serialized, e.g. `goal_for`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            TimelineKind::Started => "started",
+            TimelineKind::GoalFor => "goal_for",
+            TimelineKind::GoalAgainst => "goal_against",
+            TimelineKind::Break => "break",
+            TimelineKind::Resumed => "resumed",
+            TimelineKind::Finished => "finished",
+            TimelineKind::StatisticChanged => "statistic_changed",
+            TimelineKind::PlayerGoal => "player_goal",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct TimelineEvent {
+    pub kind: TimelineKind,
+    pub minute: Option<u64>,
+    /// Period being played, or the one just over for a break
+    pub period: Option<u64>,
+    pub my_team_score: u64,
+    pub opponent_team_score: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub statistic: Option<Statistic>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub goal: Option<Goal>,
+    pub generated: DateTime<Local>,
+}
+
+/// Event of the given kind at the time of the sample.
+pub fn event(kind: TimelineKind, sample: &GameResult) -> TimelineEvent {
+    TimelineEvent {
+        kind,
+        minute: sample.game_time.minute(),
+        period: sample.period(),
+        my_team_score: sample.my_team_score,
+        opponent_team_score: sample.opponent_team_score,
+        statistic: None,
+        goal: None,
+        generated: sample.generated,
+    }
+}
+
+/// Events that happened between two consecutive samples of the same match.
+pub fn between(previous: &GameResult, current: &GameResult) -> Vec<TimelineEvent> {
+    let mut events = Vec::new();
+    if !previous.game_time.is_running() && current.game_time.is_running() {
+        events.push(event(TimelineKind::Started, current));
+    }
+    for _ in previous.my_team_score..current.my_team_score {
+        events.push(event(TimelineKind::GoalFor, current));
+    }
+    for _ in previous.opponent_team_score..current.opponent_team_score {
+        events.push(event(TimelineKind::GoalAgainst, current));
+    }
+    for goal in current
+        .goals
+        .iter()
+        .filter(|goal| goal.player_of_interest && !previous.goals.contains(goal))
+    {
+        events.push(TimelineEvent {
+            goal: Some(goal.clone()),
+            ..event(TimelineKind::PlayerGoal, current)
+        });
+    }
+    match (&previous.game_time, &current.game_time) {
+        (GameTime::Playing(_), GameTime::BreakAfter(_)) => {
+            events.push(event(TimelineKind::Break, current))
+        }
+        (GameTime::BreakAfter(_), GameTime::Playing(_)) => {
+            events.push(event(TimelineKind::Resumed, current))
+        }
+        (previous, GameTime::Played) if previous.is_running() => {
+            events.push(event(TimelineKind::Finished, current))
+        }
+        _ => {}
+    }
+    for statistic in details::changed_statistics(&previous.stats, &current.stats) {
+        events.push(TimelineEvent {
+            statistic: Some(statistic.clone()),
+            ..event(TimelineKind::StatisticChanged, current)
+        });
+    }
+    events
+}
+
+/// Whether anything but the sampling time differs.
+pub fn changed(previous: &GameResult, latest: &GameResult) -> bool {
+    previous.my_team != latest.my_team
+        || previous.opponent_team != latest.opponent_team
+        || previous.my_team_score != latest.my_team_score
+        || previous.opponent_team_score != latest.opponent_team_score
+        || previous.period_scores != latest.period_scores
+        || previous.goals != latest.goals
+        || previous.penalties != latest.penalties
+        || previous.stats != latest.stats
+        || previous.game_time != latest.game_time
+}
+
+/// Whether the score or the kind of the game time differs; a minute passing does not count.
+pub fn score_or_state_changed(previous: Option<&GameResult>, latest: &GameResult) -> bool {
+    previous.is_none_or(|previous| {
+        previous.my_team_score != latest.my_team_score
+            || previous.opponent_team_score != latest.opponent_team_score
+            || mem::discriminant(&previous.game_time) != mem::discriminant(&latest.game_time)
+    })
+}
+
+/// Minutes of the running clock, each reported once, for the sinks pushing the clock.
+#[derive(Default)]
+pub struct MinuteTicks {
+    last: Option<u64>,
+}
+
+impl MinuteTicks {
+    /// Minute of the latest result if not reported yet; nothing for the first result, for a
+    /// score or state change (announced as an event) and for a stale result.
+    pub fn tick(&mut self, previous: Option<&GameResult>, latest: &GameResult) -> Option<u64> {
+        if latest.stale_since.is_some() {
+            return None;
+        }
+        let minute = latest.game_time.minute()?;
+        let new = self.last != Some(minute);
+        self.last = Some(minute);
+        (new && !score_or_state_changed(previous, latest)).then_some(minute)
+    }
+}
+
+/// Importance of a push notification, each push service maps it to its own priorities.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Urgency {
+    Silent,
+    Low,
+    Default,
+    High,
+}
+
+/// Phone push notification, the same for all the push services.
+#[derive(Debug, PartialEq)]
+pub struct Push {
+    /// The teams and the current score
+    pub title: String,
+    pub message: String,
+    pub urgency: Urgency,
+    /// Emoji short name of the notification
+    pub emoji: &'static str,
+}
+
+impl Push {
+    /// Notification of the event, `None` for the events not pushed.
+    pub fn event(event: &TimelineEvent, latest: &GameResult) -> Option<Self> {
+        let minute = event
+            .minute
+            .map(|minute| format!(" ({minute}')"))
+            .unwrap_or_default();
+        let (message, urgency, emoji) = match event.kind {
+            TimelineKind::GoalFor => (
+                format!("Goal of {}{minute}", latest.my_team),
+                Urgency::High,
+                "tada",
+            ),
+            TimelineKind::GoalAgainst => (
+                format!("Goal of {}{minute}", latest.opponent_team),
+                Urgency::High,
+                "disappointed",
+            ),
+            TimelineKind::Started => (
+                "The match has started".to_string(),
+                Urgency::Default,
+                "whistle",
+            ),
+            TimelineKind::Finished => (
+                "Final result".to_string(),
+                Urgency::Default,
+                "checkered_flag",
+            ),
+            TimelineKind::Break => (format!("Break{minute}"), Urgency::Low, "pause_button"),
+            TimelineKind::Resumed => (format!("Resumed{minute}"), Urgency::Low, "arrow_forward"),
+            TimelineKind::PlayerGoal => (
+                format!("{} scored{minute}", event.goal.as_ref()?.scorer),
+                Urgency::High,
+                "star",
+            ),
+            TimelineKind::StatisticChanged => return None,
+        };
+        Some(Self::new(latest, message, urgency, emoji))
+    }
+
+    /// Silent notification of a minute of the running clock.
+    pub fn minute(minute: u64, latest: &GameResult) -> Self {
+        Self::new(latest, format!("{minute}'"), Urgency::Silent, "stopwatch")
+    }
+
+    fn new(latest: &GameResult, message: String, urgency: Urgency, emoji: &'static str) -> Self {
+        Self {
+            title: format!(
+                "{} {}:{} {}",
+                latest.my_team,
+                latest.my_team_score,
+                latest.opponent_team_score,
+                latest.opponent_team
+            ),
+            message,
+            urgency,
+            emoji,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{quality, MatchRow, Periods};
+
+    fn sample() -> GameResult {
+        MatchRow {
+            home_team: "Sparta Praha".to_string(),
+            away_team: "Kometa Brno".to_string(),
+            home_score: 0,
+            away_score: 0,
+            period_scores: Vec::new(),
+            game_time: GameTime::WillBePlayed(None),
+            clock: None,
+            periods: Periods::HOCKEY,
+            quality: quality::Quality::default(),
+        }
+        .into_result(true)
+    }
+
+    #[test]
+    fn test_score_or_state_changed() {
+        let previous = GameResult {
+            my_team_score: 1,
+            opponent_team_score: 0,
+            game_time: GameTime::Playing(23),
+            ..sample()
+        };
+        assert!(score_or_state_changed(None, &previous));
+
+        let later = GameResult {
+            game_time: GameTime::Playing(24),
+            ..previous.clone()
+        };
+        assert!(!score_or_state_changed(Some(&previous), &later));
+
+        let goal = GameResult {
+            opponent_team_score: 1,
+            ..later.clone()
+        };
+        assert!(score_or_state_changed(Some(&later), &goal));
+
+        let break_ = GameResult {
+            game_time: GameTime::BreakAfter(20),
+            ..later.clone()
+        };
+        assert!(score_or_state_changed(Some(&later), &break_));
+    }
+
+    #[test]
+    fn test_minute_ticks() {
+        let mut ticks = MinuteTicks::default();
+        let first = GameResult {
+            game_time: GameTime::Playing(23),
+            ..sample()
+        };
+        assert_eq!(ticks.tick(None, &first), None);
+        assert_eq!(ticks.tick(Some(&first), &first), None);
+
+        let later = GameResult {
+            game_time: GameTime::Playing(24),
+            ..first.clone()
+        };
+        assert_eq!(ticks.tick(Some(&first), &later), Some(24));
+        assert_eq!(ticks.tick(Some(&later), &later), None);
+
+        let stale = GameResult {
+            game_time: GameTime::Playing(25),
+            stale_since: Some(Local::now()),
+            ..later.clone()
+        };
+        assert_eq!(ticks.tick(Some(&later), &stale), None);
+    }
+
+    #[test]
+    fn test_push() {
+        let previous = GameResult {
+            game_time: GameTime::Playing(18),
+            ..sample()
+        };
+        let latest = GameResult {
+            opponent_team_score: 1,
+            game_time: GameTime::BreakAfter(20),
+            ..previous.clone()
+        };
+        let pushes: Vec<_> = between(&previous, &latest)
+            .iter()
+            .filter_map(|event| Push::event(event, &latest))
+            .map(|push| (push.message, push.urgency, push.emoji))
+            .collect();
+        assert_eq!(
+            pushes,
+            [
+                (
+                    "Goal of Kometa Brno (20')".to_string(),
+                    Urgency::High,
+                    "disappointed"
+                ),
+                ("Break (20')".to_string(), Urgency::Low, "pause_button"),
+            ]
+        );
+        assert_eq!(
+            Push::minute(21, &latest).title,
+            "Sparta Praha 0:1 Kometa Brno"
+        );
+    }
+
+    #[test]
+    fn test_between_periods() {
+        let playing = GameResult {
+            game_time: GameTime::Playing(19),
+            ..sample()
+        };
+        let break_ = GameResult {
+            game_time: GameTime::BreakAfter(20),
+            ..playing.clone()
+        };
+        let resumed = GameResult {
+            game_time: GameTime::Playing(21),
+            ..playing.clone()
+        };
+        let periods: Vec<_> = [(&playing, &break_), (&break_, &resumed)]
+            .into_iter()
+            .flat_map(|(previous, current)| between(previous, current))
+            .map(|event| (event.kind.name(), event.period))
+            .collect();
+        assert_eq!(periods, [("break", Some(1)), ("resumed", Some(2))]);
+    }
+
+    #[test]
+    fn test_player_goal() {
+        let previous = GameResult {
+            game_time: GameTime::Playing(10),
+            ..sample()
+        };
+        let goal = Goal {
+            minute: "11'".to_string(),
+            my_team: true,
+            scorer: "Roman Červenka".to_string(),
+            assists: Vec::new(),
+            player_of_interest: true,
+        };
+        let latest = GameResult {
+            my_team_score: 1,
+            goals: vec![goal.clone()],
+            game_time: GameTime::Playing(11),
+            ..previous.clone()
+        };
+        let events = between(&previous, &latest);
+        let kinds: Vec<_> = events.iter().map(|event| event.kind.name()).collect();
+        assert_eq!(kinds, ["goal_for", "player_goal"]);
+        assert_eq!(events[1].goal.as_ref(), Some(&goal));
+        assert!(between(&latest, &latest).is_empty());
+    }
+
+    #[test]
+    fn test_kind_name() {
+        for kind in TimelineKind::ALL {
+            assert_eq!(serde_json::to_value(kind).unwrap(), kind.name());
+        }
+    }
+}
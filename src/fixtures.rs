@@ -0,0 +1,159 @@
+//! Static HTTP server with captured Livesport pages from `tests/fixtures`.
+
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+pub fn fixtures_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures")
+}
+
+/// Serve the fixtures on a random local port until the runtime ends.
+pub async fn serve() -> std::io::Result<SocketAddr> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let address = listener.local_addr()?;
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            tokio::spawn(handle(stream));
+        }
+    });
+    Ok(address)
+}
+
+async fn handle(mut stream: TcpStream) -> std::io::Result<()> {
+    let mut request = Vec::new();
+    let mut buffer = [0; 1024];
+    while !request.ends_with(b"\r\n\r\n") {
+        let read = stream.read(&mut buffer).await?;
+        if read == 0 {
+            break;
+        }
+        request.extend_from_slice(&buffer[..read]);
+    }
+
+    let request = String::from_utf8_lossy(&request);
+    let path = request
+        .lines()
+        .next()
+        .and_then(|line| line.split_whitespace().nth(1))
+        .unwrap_or("/");
+    let path = path.split(['?', '#']).next().unwrap_or_default();
+
+    let content = if path.contains("..") {
+        None
+    } else {
+        tokio::fs::read(fixtures_dir().join(path.trim_start_matches('/')))
+            .await
+            .ok()
+    };
+    let (status, content) = match content {
+        Some(content) => ("200 OK", content),
+        None => ("404 Not Found", b"not found".to_vec()),
+    };
+
+    stream
+        .write_all(
+            format!(
+                "HTTP/1.1 {status}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                content.len()
+            )
+            .as_bytes(),
+        )
+        .await?;
+    stream.write_all(&content).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn get(address: SocketAddr, path: &str) -> String {
+        let mut stream = TcpStream::connect(address).await.unwrap();
+        stream
+            .write_all(format!("GET {path} HTTP/1.1\r\nHost: localhost\r\n\r\n").as_bytes())
+            .await
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    #[tokio::test]
+    async fn test_serve_fixtures() {
+        let address = serve().await.unwrap();
+
+        let response = get(address, "/team_live.html").await;
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.contains("event__match--live"));
+
+        assert!(get(address, "/missing.html")
+            .await
+            .starts_with("HTTP/1.1 404 Not Found"));
+        assert!(get(address, "/../Cargo.toml")
+            .await
+            .starts_with("HTTP/1.1 404 Not Found"));
+    }
+
+    #[test]
+    fn test_fixture_layout() {
+        let page = std::fs::read_to_string(fixtures_dir().join("team_live.html")).unwrap();
+        let skeleton = crate::layout::skeleton(&page);
+        assert!(skeleton.contains(&"div.event__match".to_string()));
+        assert!(skeleton.contains(&"div.event__participant".to_string()));
+    }
+}
+
+/// Run the real extraction path in a headless browser; needs `chromedriver` in PATH.
+#[cfg(all(test, feature = "webdriver-tests"))]
+mod webdriver_tests {
+    use super::*;
+    use crate::{get_score, Browser, GameTime, ScrapeOptions, WebDriverSource, DRIVER_PORT};
+    use url::Url;
+
+    #[tokio::test]
+    async fn test_get_score_fixtures() {
+        let address = serve().await.unwrap();
+        let browser = Browser::start(DRIVER_PORT, false, false).await.unwrap();
+        let options = ScrapeOptions {
+            details: false,
+            players: Vec::new(),
+            opponent_form: false,
+            stealth: false,
+            timezone: None,
+            context: false,
+            best_of: None,
+            bracket: None,
+            network_feed: false,
+        };
+        let source = WebDriverSource::new(browser.client.clone());
+        let url = |page: &str| Url::parse(&format!("http://{address}/{page}")).unwrap();
+
+        let live = get_score(&source, &url("team_live.html"), "Sparta", &options)
+            .await
+            .unwrap();
+        assert_eq!(live.my_team, "Sparta Praha");
+        assert_eq!((live.my_team_score, live.opponent_team_score), (2, 1));
+        assert_eq!(live.period_scores, [(1, 0), (1, 1)]);
+        assert!(matches!(live.game_time, GameTime::Playing(32)));
+
+        let scheduled = get_score(&source, &url("team_scheduled.html"), "Sparta", &options)
+            .await
+            .unwrap();
+        assert_eq!(scheduled.opponent_team, "Mountfield HK");
+        assert!(matches!(scheduled.game_time, GameTime::WillBePlayed(_)));
+
+        let finished = get_score(&source, &url("team_finished.html"), "Sparta", &options)
+            .await
+            .unwrap();
+        assert_eq!(
+            (finished.my_team_score, finished.opponent_team_score),
+            (4, 1)
+        );
+        assert_eq!(finished.period_scores, [(2, 0), (1, 1), (1, 0)]);
+        assert!(matches!(finished.game_time, GameTime::Played));
+
+        browser.close().await.unwrap();
+    }
+}
@@ -0,0 +1,77 @@
+use anyhow::Context;
+use fantoccini::{Client, Locator};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tokio::time::sleep;
+use url::Url;
+
+use crate::{budget, paging};
+
+use crate::{element_text, parse_score, Outcome};
+
+const FORM_LENGTH: usize = 5;
+const FINISHED_SELECTOR: &str =
+    ".event__match:not(.event__match--scheduled):not(.event__match--live)";
+
+/// Finished match seen from the perspective of the team whose form is evaluated.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct FormResult {
+    pub against: String,
+    pub score: (u64, u64),
+    pub outcome: Outcome,
+}
+
+/// Follow the opponent link on the match page and read its latest results from its team page.
+pub async fn get_opponent_form(
+    client: &mut Client,
+    match_url: &Url,
+    opponent: &str,
+) -> anyhow::Result<Vec<FormResult>> {
+    budget::goto(client, match_url.as_str()).await?;
+    sleep(Duration::from_millis(500)).await;
+
+    let mut team_url = None;
+    for link in client
+        .find_all(Locator::Css("a.participant__participantName"))
+        .await?
+    {
+        if link.text().await?.starts_with(opponent) {
+            if let Some(href) = link.attr("href").await? {
+                team_url = Some(match_url.join(&href)?);
+                break;
+            }
+        }
+    }
+    let team_url = team_url.context("could not find link to the opponent team page")?;
+
+    budget::goto(client, team_url.as_str()).await?;
+    sleep(Duration::from_millis(500)).await;
+    // many scheduled matches may push the results off the first page
+    paging::show_at_least(client, FINISHED_SELECTOR, FORM_LENGTH).await?;
+
+    let mut form = Vec::new();
+    for row in client
+        .find_all(Locator::Css(FINISHED_SELECTOR))
+        .await?
+        .into_iter()
+        .take(FORM_LENGTH)
+    {
+        let home = element_text(&row, ".event__participant--home").await?;
+        let away = element_text(&row, ".event__participant--away").await?;
+        let home_score = parse_score(&element_text(&row, ".event__score--home").await?);
+        let away_score = parse_score(&element_text(&row, ".event__score--away").await?);
+
+        let (against, score) = if home.starts_with(opponent) {
+            (away, (home_score, away_score))
+        } else {
+            (home, (away_score, home_score))
+        };
+        form.push(FormResult {
+            against,
+            score,
+            outcome: Outcome::from_score(score.0, score.1),
+        });
+    }
+
+    Ok(form)
+}
@@ -0,0 +1,96 @@
+//! Push notifications through a self-hosted Gotify server, the priority following the
+//! importance of the change.
+
+use anyhow::Context;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use serde::Serialize;
+use std::time::Duration;
+use url::Url;
+
+use crate::events::{self, Push, TimelineEvent, Urgency};
+use crate::observer::Observer;
+use crate::webhook::Poster;
+use crate::GameResult;
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+const RETRIES: u32 = 1;
+
+/// Gotify priorities: the Android app stays silent for 0, notifies from 4 and alerts from 8.
+const SILENT: u8 = 0;
+const LOW: u8 = 2;
+const DEFAULT: u8 = 5;
+const HIGH: u8 = 8;
+
+#[derive(Serialize)]
+struct Message {
+    title: String,
+    message: String,
+    priority: u8,
+}
+
+pub struct Gotify {
+    poster: Poster,
+    ticks: events::MinuteTicks,
+}
+
+impl Gotify {
+    /// Push through the server with the token of a Gotify application.
+    pub fn new(server: &Url, app_token: &str) -> anyhow::Result<Self> {
+        let mut url = server.clone();
+        url.path_segments_mut()
+            .map_err(|_| anyhow::anyhow!("{server} cannot be a Gotify server"))?
+            .pop_if_empty()
+            .push("message");
+        let mut value = HeaderValue::from_str(app_token).context("invalid Gotify token")?;
+        value.set_sensitive(true);
+        let mut headers = HeaderMap::new();
+        headers.insert(HeaderName::from_static("x-gotify-key"), value);
+        Ok(Self {
+            poster: Poster::with_headers(
+                &format!("Gotify server {}", server.host_str().unwrap_or_default()),
+                url.as_str(),
+                headers,
+                RETRIES,
+                TIMEOUT,
+            )?,
+            ticks: events::MinuteTicks::default(),
+        })
+    }
+
+    /// Gotify has no emoji tags, the title and the message carry the news.
+    fn push(&self, push: Push) {
+        self.poster.post(&Message {
+            title: push.title,
+            message: push.message,
+            priority: priority(push.urgency),
+        });
+    }
+}
+
+fn priority(urgency: Urgency) -> u8 {
+    match urgency {
+        Urgency::Silent => SILENT,
+        Urgency::Low => LOW,
+        Urgency::Default => DEFAULT,
+        Urgency::High => HIGH,
+    }
+}
+
+impl Observer for Gotify {
+    fn announces(&self) -> bool {
+        true
+    }
+
+    /// The periodic refresh of the running clock is pushed silently.
+    fn on_change(&mut self, previous: Option<&GameResult>, latest: &GameResult) {
+        if let Some(minute) = self.ticks.tick(previous, latest) {
+            self.push(Push::minute(minute, latest));
+        }
+    }
+
+    fn on_event(&mut self, event: &TimelineEvent, latest: &GameResult) {
+        if let Some(push) = Push::event(event, latest) {
+            self.push(push);
+        }
+    }
+}
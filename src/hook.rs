@@ -0,0 +1,117 @@
+//! User command run for every match event, e.g. to flash the lights on a goal.
+
+use std::process::Stdio;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tracing::warn;
+
+use crate::events::TimelineEvent;
+use crate::observer::Observer;
+use crate::GameResult;
+
+/// Shell command started with `sh -c` for each event, with the event in `LIVESPORT_*`
+/// variables and the latest result as JSON on its standard input.
+pub struct EventHook {
+    command: String,
+}
+
+impl EventHook {
+    pub fn new(command: &str) -> Self {
+        Self {
+            command: command.to_string(),
+        }
+    }
+}
+
+fn environment(event: &TimelineEvent, latest: &GameResult) -> Vec<(&'static str, String)> {
+    let mut variables = vec![
+        ("LIVESPORT_EVENT", event.kind.name().to_string()),
+        ("LIVESPORT_MY_TEAM", latest.my_team.clone()),
+        ("LIVESPORT_OPPONENT_TEAM", latest.opponent_team.clone()),
+        ("LIVESPORT_MY_TEAM_SCORE", event.my_team_score.to_string()),
+        (
+            "LIVESPORT_OPPONENT_TEAM_SCORE",
+            event.opponent_team_score.to_string(),
+        ),
+    ];
+    if let Some(minute) = event.minute {
+        variables.push(("LIVESPORT_MINUTE", minute.to_string()));
+    }
+    if let Some(statistic) = &event.statistic {
+        variables.push(("LIVESPORT_STATISTIC", statistic.name.clone()));
+    }
+    variables
+}
+
+impl Observer for EventHook {
+    fn on_event(&mut self, event: &TimelineEvent, latest: &GameResult) {
+        let input = match serde_json::to_vec(latest) {
+            Ok(input) => input,
+            Err(error) => {
+                warn!(
+                    "could not serialize the result for {}: {error}",
+                    self.command
+                );
+                return;
+            }
+        };
+        let child = Command::new("sh")
+            .arg("-c")
+            .arg(&self.command)
+            .envs(environment(event, latest))
+            .stdin(Stdio::piped())
+            .spawn();
+        let mut child = match child {
+            Ok(child) => child,
+            Err(error) => {
+                warn!("cannot start the event hook {}: {error}", self.command);
+                return;
+            }
+        };
+        // the hook may take its time, the scraping goes on
+        let command = self.command.clone();
+        tokio::spawn(async move {
+            if let Some(mut stdin) = child.stdin.take() {
+                // a hook not interested in the result may not read it
+                let _ = stdin.write_all(&input).await;
+            }
+            match child.wait().await {
+                Ok(status) if !status.success() => {
+                    warn!("event hook {command} failed with {status}")
+                }
+                Ok(_) => {}
+                Err(error) => warn!("event hook {command} failed: {error}"),
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events;
+    use crate::simulation::sample;
+    use crate::GameTime;
+
+    #[test]
+    fn test_environment() {
+        let previous = sample(GameTime::Playing(11), 0, 0);
+        let latest = GameResult {
+            my_team_score: 1,
+            game_time: GameTime::Playing(12),
+            ..previous.clone()
+        };
+        let events = events::between(&previous, &latest);
+        assert_eq!(
+            environment(&events[0], &latest),
+            [
+                ("LIVESPORT_EVENT", "goal_for".to_string()),
+                ("LIVESPORT_MY_TEAM", "Sparta Praha".to_string()),
+                ("LIVESPORT_OPPONENT_TEAM", "Kometa Brno".to_string()),
+                ("LIVESPORT_MY_TEAM_SCORE", "1".to_string()),
+                ("LIVESPORT_OPPONENT_TEAM_SCORE", "0".to_string()),
+                ("LIVESPORT_MINUTE", "12".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,134 @@
+//! Backend parsing a team page saved to disk, for debugging the extraction without a browser.
+
+use async_trait::async_trait;
+use chrono_tz::Tz;
+use scraper::{ElementRef, Html, Selector};
+use std::fs;
+use url::Url;
+
+use crate::source::ScoreSource;
+use crate::timezone;
+use crate::{GameResult, MatchRow, RawRow, ScrapeOptions};
+
+/// Team page read from a `file://` URL.
+pub struct HtmlSource;
+
+#[async_trait]
+impl ScoreSource for HtmlSource {
+    async fn get_score(
+        &self,
+        url: &Url,
+        team_name: &str,
+        options: &ScrapeOptions,
+    ) -> anyhow::Result<GameResult> {
+        anyhow::ensure!(
+            options.page_loads() == 1,
+            "a saved page contains only the match row of the team page"
+        );
+        let path = url
+            .to_file_path()
+            .map_err(|_| anyhow::anyhow!("{url} is not a local file"))?;
+        let page = fs::read_to_string(&path)?;
+        let timezone = options.timezone.or_else(|| site_timezone(&page));
+        let row = MatchRow::parse(&read_row(&page)?, timezone)?;
+        let is_home = row.is_home(team_name);
+        Ok(row.into_result(is_home))
+    }
+}
+
+/// Name of the team in the heading of its page.
+pub fn team_name(page: &str) -> Option<String> {
+    let document = Html::parse_document(page);
+    let heading = document.select(&selector(".heading__name")).next()?;
+    Some(heading.text().collect::<String>().trim().to_string())
+}
+
+/// Time zone picked in the settings of the saved page.
+fn site_timezone(page: &str) -> Option<Tz> {
+    let document = Html::parse_document(page);
+    let setting = document
+        .select(&selector(timezone::SETTING_SELECTOR))
+        .next()?;
+    timezone::parse(&setting.text().collect::<String>())
+}
+
+/// Texts of the first match row of the page.
+fn read_row(page: &str) -> anyhow::Result<RawRow> {
+    let document = Html::parse_document(page);
+    let row = document
+        .select(&selector(".event__match"))
+        .next()
+        .ok_or(anyhow::anyhow!("could not find .event__match element"))?;
+
+    Ok(RawRow {
+        class: row
+            .value()
+            .attr("class")
+            .ok_or(anyhow::anyhow!("class attribute should not be empty"))?
+            .to_string(),
+        home_team: text(row, ".event__participant--home"),
+        away_team: text(row, ".event__participant--away"),
+        home_score: text(row, ".event__score--home"),
+        away_score: text(row, ".event__score--away"),
+        home_parts: texts(row, ".event__part--home"),
+        away_parts: texts(row, ".event__part--away"),
+        event_time: text(row, ".event__time"),
+        clock: text(row, ".eventTime"),
+        basketball: row.ancestors().filter_map(ElementRef::wrap).any(|parent| {
+            parent.value().name() == "div"
+                && parent
+                    .value()
+                    .attr("class")
+                    .is_some_and(|class| class.contains("basketball"))
+        }),
+    })
+}
+
+fn selector(selector: &str) -> Selector {
+    Selector::parse(selector).expect("selector should be valid")
+}
+
+fn text(row: ElementRef, selector_text: &str) -> Option<String> {
+    row.select(&selector(selector_text))
+        .next()
+        .map(|element| element.text().collect::<String>().trim().to_string())
+}
+
+fn texts(row: ElementRef, selector_text: &str) -> Vec<String> {
+    row.select(&selector(selector_text))
+        .map(|element| element.text().collect::<String>().trim().to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GameTime;
+
+    fn fixture(name: &str) -> Url {
+        let path = format!("{}/tests/fixtures/{name}", env!("CARGO_MANIFEST_DIR"));
+        Url::from_file_path(path).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_live_page() {
+        let result = HtmlSource
+            .get_score(
+                &fixture("team_live.html"),
+                "Sparta Praha",
+                &ScrapeOptions::default(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(result.opponent_team, "Kometa Brno");
+        assert_eq!((result.my_team_score, result.opponent_team_score), (2, 1));
+        assert_eq!(result.period_scores, vec![(1, 0), (1, 1)]);
+        assert_eq!(result.game_time, GameTime::Playing(32));
+    }
+
+    #[test]
+    fn test_team_name() {
+        let page = fs::read_to_string(fixture("team_live.html").to_file_path().unwrap()).unwrap();
+        assert_eq!(team_name(&page).as_deref(), Some("Sparta Praha"));
+    }
+}
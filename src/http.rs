@@ -0,0 +1,244 @@
+//! Backend reading the match feed embedded in the team page, without any browser.
+//!
+//! The page carries its initial data as `key÷value` fields separated by `¬`, one match per
+//! record starting with the `AA` (event id) field.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use url::Url;
+
+use crate::quality::{self, Source};
+use crate::source::ScoreSource;
+use crate::{
+    bandwidth, budget, details, estimate, GameResult, GameTime, MatchRow, Periods, ScrapeOptions,
+};
+
+const USER_AGENT: &str =
+    "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/128.0 Safari/537.36";
+const FEED_START: &str = "data: `";
+
+/// Status of the event in the `AB` field.
+const SCHEDULED: &str = "1";
+const LIVE: &str = "2";
+
+/// Stages of the event in the `AC` field: half time and the break between the periods.
+const BREAK_STAGES: &[&str] = &["38", "46"];
+
+/// Sport of the records following the `SA` field.
+const BASKETBALL: &str = "3";
+
+/// Home and away score fields of the periods, in order.
+const PERIOD_FIELDS: &[(&str, &str)] = &[("BA", "BB"), ("BC", "BD"), ("BE", "BF"), ("BG", "BH")];
+
+/// Team page fetched over plain HTTP.
+pub struct HttpSource {
+    client: reqwest::Client,
+}
+
+impl HttpSource {
+    pub fn new() -> anyhow::Result<Self> {
+        Ok(Self {
+            client: reqwest::Client::builder().user_agent(USER_AGENT).build()?,
+        })
+    }
+}
+
+#[async_trait]
+impl ScoreSource for HttpSource {
+    async fn get_score(
+        &self,
+        url: &Url,
+        team_name: &str,
+        options: &ScrapeOptions,
+    ) -> anyhow::Result<GameResult> {
+        anyhow::ensure!(
+            options.page_loads() == 1 && !details::is_match_url(url),
+            "the HTTP backend reads only the match row of the team page"
+        );
+        budget::reserve(url.as_str()).await;
+        let page = self
+            .client
+            .get(url.as_str())
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+        bandwidth::record(page.len() as u64);
+
+        let events = parse_feed(&page);
+        let event = latest_event(&events)
+            .ok_or(anyhow::anyhow!("could not find any match in the page feed"))?;
+        let row = read_event(event, Utc::now())?;
+        let is_home = row.is_home(team_name);
+        Ok(row.into_result(is_home))
+    }
+}
+
+/// All match records of the feeds embedded in the page.
+fn parse_feed(page: &str) -> Vec<HashMap<&str, &str>> {
+    page.split(FEED_START)
+        .skip(1)
+        .filter_map(|feed| feed.split_once('`').map(|(feed, _)| feed))
+        .flat_map(parse_records)
+        .collect()
+}
+
+/// Match records, each with the `SA` (sport) field of the section it belongs to.
+fn parse_records(feed: &str) -> impl Iterator<Item = HashMap<&str, &str>> {
+    let mut sport = None;
+    feed.split('~')
+        .map(|record| {
+            record
+                .split('¬')
+                .filter_map(|field| field.split_once('÷'))
+                .collect::<HashMap<_, _>>()
+        })
+        .filter_map(move |mut record| {
+            if let Some(value) = record.get("SA") {
+                sport = Some(*value);
+            }
+            if !record.contains_key("AA") {
+                return None;
+            }
+            if let Some(sport) = sport {
+                record.entry("SA").or_insert(sport);
+            }
+            Some(record)
+        })
+}
+
+/// Row of the latest match in feeds downloaded by the page itself.
+pub(crate) fn feed_row(feeds: &[String]) -> Option<MatchRow> {
+    let events: Vec<_> = feeds.iter().flat_map(|feed| parse_records(feed)).collect();
+    latest_event(&events).and_then(|event| read_event(event, Utc::now()).ok())
+}
+
+/// The live match, otherwise the nearest scheduled one, otherwise the last finished one.
+fn latest_event<'a, 'b>(
+    events: &'a [HashMap<&'b str, &'b str>],
+) -> Option<&'a HashMap<&'b str, &'b str>> {
+    let start = |event: &&HashMap<&str, &str>| {
+        event
+            .get("AD")
+            .and_then(|timestamp| timestamp.parse::<i64>().ok())
+            .unwrap_or_default()
+    };
+
+    events
+        .iter()
+        .find(|event| event.get("AB") == Some(&LIVE))
+        .or_else(|| {
+            events
+                .iter()
+                .filter(|event| event.get("AB") == Some(&SCHEDULED))
+                .min_by_key(start)
+        })
+        .or_else(|| events.iter().max_by_key(start))
+}
+
+fn read_event(event: &HashMap<&str, &str>, now: DateTime<Utc>) -> anyhow::Result<MatchRow> {
+    let mut quality = quality::Quality::default();
+    let field = |key: &str| event.get(key).copied();
+    let score = |key: &str| field(key).and_then(|value| value.parse::<u64>().ok());
+
+    let home_team = field("AE").ok_or(anyhow::anyhow!("match without the home team"))?;
+    let away_team = field("AF").ok_or(anyhow::anyhow!("match without the away team"))?;
+    quality.record_parsed("home_score", score("AG"));
+    quality.record_parsed("away_score", score("AH"));
+
+    let periods = if field("SA") == Some(BASKETBALL) {
+        Periods::BASKETBALL
+    } else {
+        Periods::HOCKEY
+    };
+    let period_scores: Vec<_> = PERIOD_FIELDS
+        .iter()
+        .map_while(|(home, away)| Some((score(home)?, score(away)?)))
+        .collect();
+
+    let kickoff = field("AD")
+        .and_then(|timestamp| timestamp.parse().ok())
+        .and_then(|timestamp| DateTime::from_timestamp(timestamp, 0));
+    let game_time = match field("AB") {
+        Some(SCHEDULED) => {
+            quality.record_parsed("game_time", kickoff);
+            GameTime::WillBePlayed(kickoff.map(|kickoff| {
+                let minutes = (kickoff - now).num_minutes().max(0) as u64;
+                (minutes / 60, minutes % 60)
+            }))
+        }
+        // the scores of a period appear once it starts
+        Some(LIVE) if field("AC").is_some_and(|stage| BREAK_STAGES.contains(&stage)) => {
+            quality.record("game_time", Source::Primary);
+            GameTime::BreakAfter(period_scores.len().max(1) as u64 * periods.minutes)
+        }
+        // the feed has no running clock, estimate it within the current period
+        Some(LIVE) => {
+            quality.record("game_time", Source::Fallback);
+            let elapsed = kickoff.map_or(0, |kickoff| (now - kickoff).num_minutes());
+            GameTime::Playing(estimate::minute_in_period(
+                period_scores.len().max(1) as u64,
+                periods,
+                elapsed,
+            ))
+        }
+        _ => {
+            quality.record("game_time", Source::Primary);
+            GameTime::Played
+        }
+    };
+
+    Ok(MatchRow {
+        home_team: home_team.to_string(),
+        away_team: away_team.to_string(),
+        home_score: score("AG").unwrap_or_default(),
+        away_score: score("AH").unwrap_or_default(),
+        period_scores,
+        game_time,
+        clock: None,
+        periods,
+        quality,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = "<script>cjs.initialFeeds['summary-fixtures'] = {\n data: `SA÷4¬~ZA÷ČESKO: Extraliga¬~AA÷jqTwpB3K¬AD÷1735660800¬AB÷1¬AE÷Mountfield HK¬AF÷Sparta Praha¬~`,\n};\ncjs.initialFeeds['summary-results'] = {\n data: `SA÷4¬~AA÷xYz12345¬AD÷1735400000¬AB÷3¬AE÷Sparta Praha¬AF÷Kometa Brno¬AG÷4¬AH÷1¬BA÷2¬BB÷0¬BC÷1¬BD÷1¬BE÷1¬BF÷0¬~`,\n};</script>";
+
+    #[test]
+    fn test_parse_feed() {
+        let events = parse_feed(PAGE);
+        assert_eq!(events.len(), 2);
+
+        let now = DateTime::from_timestamp(1735653600, 0).unwrap();
+        let row = read_event(latest_event(&events).unwrap(), now).unwrap();
+        assert_eq!(row.home_team, "Mountfield HK");
+        assert_eq!(row.game_time, GameTime::WillBePlayed(Some((2, 0))));
+
+        let row = read_event(&events[1], now).unwrap();
+        assert_eq!((row.home_score, row.away_score), (4, 1));
+        assert_eq!(row.period_scores, [(2, 0), (1, 1), (1, 0)]);
+        assert_eq!(row.game_time, GameTime::Played);
+        assert_eq!(row.periods, Periods::HOCKEY);
+    }
+
+    #[test]
+    fn test_live_event() {
+        let live = "SA÷3¬~AA÷b4sk3t¬AD÷1735653600¬AB÷2¬AE÷Nymburk¬AF÷Opava¬AG÷38¬AH÷31¬BA÷20¬BB÷17¬BC÷18¬BD÷14¬~";
+        let events: Vec<_> = parse_records(live).collect();
+        let now = DateTime::from_timestamp(1735653600 + 30 * 60, 0).unwrap();
+        let row = read_event(&events[0], now).unwrap();
+        assert_eq!(row.periods, Periods::BASKETBALL);
+        assert_eq!(row.game_time, GameTime::Playing(11));
+        assert_eq!(row.quality.fields["game_time"], Source::Fallback);
+
+        let break_ = live.replace("AB÷2¬", "AB÷2¬AC÷46¬");
+        let events: Vec<_> = parse_records(&break_).collect();
+        let row = read_event(&events[0], now).unwrap();
+        assert_eq!(row.game_time, GameTime::BreakAfter(20));
+    }
+}
@@ -0,0 +1,80 @@
+use std::time::Duration;
+
+use crate::{ClockDirection, GameResult, GameTime};
+
+/// Staleness of the scraped match clock: how much later than in the freshest sample of the
+/// running period the clock is shown, comparing both with the wall clock.
+#[derive(Default)]
+pub struct LatencyEstimator {
+    period: usize,
+    best_offset: Option<i64>,
+}
+
+impl LatencyEstimator {
+    /// Record a sample; the estimate needs a running clock shown with seconds.
+    pub fn sample(&mut self, result: &GameResult) -> Option<Duration> {
+        let clock = result
+            .clock
+            .as_ref()
+            .filter(|_| matches!(result.game_time, GameTime::Playing(_)));
+        let Some((clock, second)) = clock.and_then(|clock| Some((clock, clock.second?))) else {
+            self.best_offset = None;
+            return None;
+        };
+        let shown = (clock.minute * 60 + second) as i64;
+        let elapsed = match clock.direction {
+            ClockDirection::Up => shown,
+            ClockDirection::Down => -shown,
+        };
+
+        let period = result.period_scores.len();
+        if period != self.period {
+            self.period = period;
+            self.best_offset = None;
+        }
+        let offset = result.generated.timestamp_millis() - elapsed * 1000;
+        let best_offset = *self
+            .best_offset
+            .insert(self.best_offset.map_or(offset, |best| best.min(offset)));
+        Some(Duration::from_millis((offset - best_offset) as u64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{DateTime, Local};
+    use livesport_crawler::Clock;
+
+    fn sample(now: DateTime<Local>, minute: u64, second: u64, after: i64) -> GameResult {
+        let mut result = crate::simulation::Simulation::new("Sparta").next_result();
+        result.game_time = GameTime::Playing(minute + 1);
+        result.period_scores = vec![(0, 0)];
+        result.clock = Some(Clock {
+            minute,
+            second: Some(second),
+            direction: ClockDirection::Up,
+        });
+        result.generated = now + chrono::Duration::seconds(after);
+        result
+    }
+
+    #[test]
+    fn test_latency() {
+        let now = Local::now();
+        let mut estimator = LatencyEstimator::default();
+        assert_eq!(
+            estimator.sample(&sample(now, 5, 0, 0)),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(
+            estimator.sample(&sample(now, 5, 30, 30)),
+            Some(Duration::ZERO)
+        );
+        // the clock moved 10 seconds in the last 30 seconds of the wall clock
+        assert_eq!(
+            estimator.sample(&sample(now, 5, 40, 60)),
+            Some(Duration::from_secs(20))
+        );
+    }
+}
@@ -0,0 +1,152 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+use tracing::warn;
+
+/// Portion of the skeleton that has to differ to report a layout change.
+const CHANGE_THRESHOLD: f64 = 0.2;
+
+#[derive(Deserialize, Serialize)]
+struct Snapshot {
+    hash: u64,
+    skeleton: Vec<String>,
+}
+
+/// Classes of the nodes present only in some states of a match: the kickoff time of a
+/// scheduled match, the stage and the period scores of a live or finished one.
+const STATE_CLASSES: &[&str] = &["event__time", "event__stage", "event__part"];
+
+/// Tag/class tree of an HTML fragment, e.g. `div.event__match`; state modifiers (`--live`)
+/// and the nodes specific to a state of the match are ignored.
+pub fn skeleton(html: &str) -> Vec<String> {
+    let mut skeleton = Vec::new();
+    for tag in html.split('<').skip(1) {
+        if tag.starts_with('/') || tag.starts_with('!') {
+            continue;
+        }
+        let end = tag.find('>').unwrap_or(tag.len());
+        let tag = &tag[..end];
+        let name = tag
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or_default();
+
+        let mut classes: Vec<_> = tag
+            .split_once("class=\"")
+            .and_then(|(_, rest)| rest.split_once('"'))
+            .map(|(classes, _)| classes.split_whitespace().collect())
+            .unwrap_or_default();
+        if classes
+            .iter()
+            .any(|class| STATE_CLASSES.contains(&class.split("--").next().unwrap_or_default()))
+        {
+            continue;
+        }
+        classes.retain(|class| !class.contains("--"));
+        classes.sort_unstable();
+        classes.insert(0, name);
+        skeleton.push(classes.join("."));
+    }
+    skeleton
+}
+
+/// FNV-1a hash, stable across Rust versions unlike `DefaultHasher`.
+fn hash(skeleton: &[String]) -> u64 {
+    skeleton
+        .iter()
+        .flat_map(|node| node.bytes().chain(std::iter::once(b'\n')))
+        .fold(0xcbf29ce484222325, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+        })
+}
+
+/// Portion of distinct nodes which are not shared by both skeletons.
+fn difference(a: &[String], b: &[String]) -> f64 {
+    let a: HashSet<_> = a.iter().collect();
+    let b: HashSet<_> = b.iter().collect();
+    let union = a.union(&b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    1.0 - a.intersection(&b).count() as f64 / union as f64
+}
+
+/// Compares skeleton of the match row with the previous run, optionally persisted in a file.
+pub struct LayoutDetector {
+    snapshot_path: Option<PathBuf>,
+    previous: Option<Snapshot>,
+}
+
+impl LayoutDetector {
+    pub fn new(snapshot_path: Option<PathBuf>) -> Self {
+        let previous = snapshot_path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str(&content).ok());
+        Self {
+            snapshot_path,
+            previous,
+        }
+    }
+
+    /// Record the row HTML; returns the difference if the layout changed significantly.
+    pub fn check(&mut self, html: &str) -> Option<f64> {
+        let skeleton = skeleton(html);
+        let snapshot = Snapshot {
+            hash: hash(&skeleton),
+            skeleton,
+        };
+
+        let change = match &self.previous {
+            Some(previous) if previous.hash == snapshot.hash => return None,
+            Some(previous) => Some(difference(&previous.skeleton, &snapshot.skeleton)),
+            None => None,
+        };
+
+        if let Some(path) = &self.snapshot_path {
+            if let Err(error) = fs::write(path, serde_json::to_string(&snapshot).unwrap()) {
+                warn!("could not write layout snapshot: {error}");
+            }
+        }
+        self.previous = Some(snapshot);
+
+        change.filter(|change| *change >= CHANGE_THRESHOLD)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_skeleton() {
+        assert_eq!(
+            skeleton(
+                r#"<div class="event__match event__match--live"><div class="event__time">18:00</div><img src="a.png"/></div>"#
+            ),
+            ["div.event__match", "img"]
+        );
+    }
+
+    #[test]
+    fn test_state_transition() {
+        let mut detector = LayoutDetector::new(None);
+        let scheduled = r#"<div class="event__match event__match--scheduled"><div class="event__time">18:00</div><div class="event__participant event__participant--home">Sparta Praha</div><div class="event__participant event__participant--away">Kometa Brno</div><div class="event__score event__score--home">-</div><div class="event__score event__score--away">-</div></div>"#;
+        let live = r#"<div class="event__match event__match--live"><div class="event__stage"><div class="event__stage--block">2. třetina</div></div><div class="event__participant event__participant--home">Sparta Praha</div><div class="event__participant event__participant--away">Kometa Brno</div><div class="event__score event__score--home">1</div><div class="event__score event__score--away">0</div><div class="event__part event__part--home event__part--1">1</div><div class="event__part event__part--away event__part--1">0</div></div>"#;
+        assert_eq!(detector.check(scheduled), None);
+        assert_eq!(detector.check(live), None);
+        assert_eq!(skeleton(scheduled), skeleton(live));
+    }
+
+    #[test]
+    fn test_layout_change() {
+        let mut detector = LayoutDetector::new(None);
+        let row = r#"<div class="event__match"><div class="event__score--home">1</div><div class="event__score--away">0</div></div>"#;
+        assert_eq!(detector.check(row), None);
+        assert_eq!(detector.check(row), None);
+        assert!(detector
+            .check(r#"<div class="match-row"><span class="score">1</span></div>"#)
+            .is_some());
+    }
+}
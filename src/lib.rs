@@ -0,0 +1,1507 @@
+//! Scraping of team pages on Livesport and its mirrors, usable without the crawler binary.
+//!
+//! ```no_run
+//! # async fn example() -> anyhow::Result<()> {
+//! use livesport_crawler::{Crawler, ScrapeOptions};
+//!
+//! let mut crawler = Crawler::connect("http://localhost:9515", ScrapeOptions::default()).await?;
+//! let url = "https://www.livesport.cz/tym/sparta-praha/zcG9U7N6/".parse()?;
+//! let result = crawler.get_score(&url, "Sparta Praha").await?;
+//! println!("{} {}:{} {}", result.my_team, result.my_team_score, result.opponent_team_score, result.opponent_team);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! or keep following the team:
+//!
+//! ```no_run
+//! # async fn example(mut crawler: livesport_crawler::Crawler, url: url::Url) -> anyhow::Result<()> {
+//! use livesport_crawler::observer::Callbacks;
+//! use livesport_crawler::poll::Adaptive;
+//! use std::time::Duration;
+//!
+//! let mut policy = Adaptive { live: Duration::from_secs(10), idle: Duration::from_secs(600) };
+//! let mut callbacks = Callbacks::default().on_event(|event, _| println!("{:?}", event.kind));
+//! crawler.watch(&url, "Sparta Praha", &mut policy, &mut callbacks).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use anyhow::Context;
+use chrono::{DateTime, Datelike, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use fantoccini::elements::Element;
+use fantoccini::{wd::Capabilities, Client, ClientBuilder, Locator};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::time::sleep;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, warn};
+use url::Url;
+
+pub use error::CrawlerError;
+pub use source::{ScoreSource, WebDriverSource};
+
+pub mod bandwidth;
+pub mod bracket;
+pub mod budget;
+pub mod calendar;
+pub mod cdp;
+pub mod context;
+pub mod details;
+pub mod error;
+pub mod estimate;
+pub mod events;
+pub mod form;
+pub mod html;
+pub mod http;
+pub mod layout;
+pub mod observer;
+mod paging;
+pub mod poll;
+pub mod quality;
+pub mod reporting;
+pub mod results;
+pub mod schedule;
+pub mod season;
+mod series;
+pub mod source;
+pub mod standings;
+pub mod stealth;
+mod timezone;
+
+#[cfg(not(any(feature = "rustls", feature = "native-tls")))]
+compile_error!("either the rustls or the native-tls feature has to be enabled");
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub enum GameTime {
+    WillBePlayed(Option<(u64, u64)>),
+    Played,
+    BreakAfter(u64),
+    Playing(u64),
+}
+
+impl GameTime {
+    pub fn is_running(&self) -> bool {
+        matches!(self, GameTime::BreakAfter(_) | GameTime::Playing(_))
+    }
+
+    pub fn minute(&self) -> Option<u64> {
+        match self {
+            GameTime::BreakAfter(minute) | GameTime::Playing(minute) => Some(*minute),
+            GameTime::WillBePlayed(_) | GameTime::Played => None,
+        }
+    }
+}
+
+/// Regular periods of the sport, e.g. 3 × 20 minutes of ice hockey.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Periods {
+    /// Length of one period
+    pub minutes: u64,
+    pub count: u64,
+}
+
+impl Periods {
+    pub const HOCKEY: Periods = Periods {
+        minutes: PERIOD_MINUTES,
+        count: PERIODS,
+    };
+    /// Quarters under the FIBA rules; the 12 minutes of the NBA are not told apart.
+    pub const BASKETBALL: Periods = Periods {
+        minutes: 10,
+        count: 4,
+    };
+
+    /// Minutes of the regulation time.
+    pub fn regulation(&self) -> u64 {
+        self.minutes * self.count
+    }
+
+    /// Periods over before the given minute of play.
+    pub fn completed(&self, minute: u64) -> u64 {
+        minute / self.minutes
+    }
+
+    /// Period (counted from 1) being played, or the one just over during a break.
+    pub fn of(&self, game_time: &GameTime) -> Option<u64> {
+        match game_time {
+            GameTime::Playing(minute) => Some(self.completed(minute.saturating_sub(1)) + 1),
+            GameTime::BreakAfter(minute) => Some(self.completed(*minute).max(1)),
+            GameTime::WillBePlayed(_) | GameTime::Played => None,
+        }
+    }
+}
+
+impl Default for Periods {
+    fn default() -> Self {
+        Self::HOCKEY
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClockDirection {
+    Up,
+    Down,
+}
+
+/// Result of the match from the perspective of my team.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Outcome {
+    Win,
+    Loss,
+    Draw,
+    /// Not finished yet
+    Pending,
+}
+
+impl Outcome {
+    /// Outcome of a finished match.
+    pub fn from_score(score: u64, other_score: u64) -> Self {
+        match score.cmp(&other_score) {
+            Ordering::Greater => Outcome::Win,
+            Ordering::Equal => Outcome::Draw,
+            Ordering::Less => Outcome::Loss,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Clock {
+    pub minute: u64,
+    pub second: Option<u64>,
+    pub direction: ClockDirection,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GameResult {
+    pub my_team: String,
+    pub my_team_score: u64,
+    pub opponent_team: String,
+    pub opponent_team_score: u64,
+    pub period_scores: Vec<(u64, u64)>,
+    pub goals: Vec<details::Goal>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub penalties: Vec<details::Penalty>,
+    pub stats: Vec<details::Statistic>,
+    /// TV channels and streams listed on the match page
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub broadcast: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub opponent_form: Option<Vec<form::FormResult>>,
+    /// Wins of my team and of the opponent in the running playoff series
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub series_score: Option<(u64, u64)>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub series_best_of: Option<u64>,
+    /// Score progression of the match as (minute, my team score, opponent score)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub timeline: Vec<(u64, u64, u64)>,
+    /// Rough wall-clock minutes until the end of the running match
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub estimated_time_remaining: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub estimated_end_at: Option<DateTime<Local>>,
+    /// Wall-clock start of the match as first seen running (estimated when joined later)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<DateTime<Local>>,
+    /// Wall-clock minutes since the start, frozen at the final whistle
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub elapsed_minutes: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_goal_at: Option<DateTime<Local>>,
+    /// Wall-clock minutes since the last goal, or since the start of a goalless match
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub minutes_since_last_goal: Option<u64>,
+    /// Derived from the scores and the game time, so that simple consumers (badges, LEDs)
+    /// do not need to interpret them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub outcome: Option<Outcome>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub goal_difference: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub is_leading: Option<bool>,
+    /// Period clock as shown on the site, with seconds when available
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub clock: Option<Clock>,
+    /// How the fields were obtained, to judge how trustworthy the sample is
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quality: Option<quality::Quality>,
+    /// Since when the result is not refreshed because the browser is being restarted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stale_since: Option<DateTime<Local>>,
+    /// Team page (on the primary domain or a mirror) the result was read from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub served_by: Option<String>,
+    pub game_time: GameTime,
+    /// Periods of the sport, the game time counts in them
+    #[serde(default)]
+    pub periods: Periods,
+    pub generated: DateTime<Local>,
+}
+
+impl GameResult {
+    /// Period (counted from 1) being played, or the one just over during a break.
+    pub fn period(&self) -> Option<u64> {
+        self.periods.of(&self.game_time)
+    }
+
+    /// Whether both results are of the same match (or of the same series of matches).
+    pub fn same_match(&self, other: &GameResult) -> bool {
+        self.my_team == other.my_team && self.opponent_team == other.opponent_team
+    }
+
+    /// Whether anything but the sampling time differs from the previous result.
+    pub fn changed_from(&self, previous: &GameResult) -> bool {
+        events::changed(previous, self)
+    }
+
+    /// Events that happened between this result and a later one of the same match.
+    pub fn diff(&self, later: &GameResult) -> Vec<events::TimelineEvent> {
+        events::between(self, later)
+    }
+
+    /// Fill in what this sample did not read from the previous sample of the same match, e.g.
+    /// when the match page failed to load this time.
+    pub fn merge(&mut self, previous: &GameResult) {
+        if !self.same_match(previous) {
+            return;
+        }
+        if self.stats.is_empty() {
+            self.stats = previous.stats.clone();
+        }
+        if self.broadcast.is_empty() {
+            self.broadcast = previous.broadcast.clone();
+        }
+        if self.opponent_form.is_none() {
+            self.opponent_form = previous.opponent_form.clone();
+        }
+        if self.series_score.is_none() {
+            self.series_score = previous.series_score;
+            self.series_best_of = self.series_best_of.or(previous.series_best_of);
+        }
+    }
+
+    /// Fill in the fields derived from the scores and the game time.
+    pub fn derive_perspective(&mut self) {
+        let difference = self.my_team_score as i64 - self.opponent_team_score as i64;
+        let started = !matches!(self.game_time, GameTime::WillBePlayed(_));
+        self.outcome = Some(match self.game_time {
+            GameTime::Played => Outcome::from_score(self.my_team_score, self.opponent_team_score),
+            _ => Outcome::Pending,
+        });
+        self.goal_difference = Some(difference);
+        self.is_leading = Some(started && difference > 0);
+    }
+}
+
+/// What should be scraped besides the match row.
+#[derive(Clone, Debug, Default)]
+pub struct ScrapeOptions {
+    pub details: bool,
+    pub players: Vec<String>,
+    pub opponent_form: bool,
+    pub stealth: bool,
+    pub timezone: Option<Tz>,
+    pub context: bool,
+    pub best_of: Option<u64>,
+    pub bracket: Option<Url>,
+    /// Read the match row from the feed the page downloads instead of its DOM
+    pub network_feed: bool,
+}
+
+impl ScrapeOptions {
+    /// Upper estimate of pages loaded by one scrape.
+    pub fn page_loads(&self) -> u32 {
+        1 + u32::from(self.details)
+            + 2 * u32::from(self.opponent_form)
+            + u32::from(self.context)
+            + u32::from(self.bracket.is_some())
+    }
+}
+
+pub const PERIOD_MINUTES: u64 = 20;
+pub const PERIODS: u64 = 3;
+
+/// Texts of one `.event__match` row, read from a rendered or a saved page.
+pub(crate) struct RawRow {
+    pub(crate) class: String,
+    pub(crate) home_team: Option<String>,
+    pub(crate) away_team: Option<String>,
+    pub(crate) home_score: Option<String>,
+    pub(crate) away_score: Option<String>,
+    pub(crate) home_parts: Vec<String>,
+    pub(crate) away_parts: Vec<String>,
+    /// Kickoff of a scheduled match
+    pub(crate) event_time: Option<String>,
+    /// Clock of a running match, missing during breaks
+    pub(crate) clock: Option<String>,
+    /// Basketball-like sports show the time left in the period
+    pub(crate) basketball: bool,
+}
+
+impl RawRow {
+    async fn read(row: &Element) -> anyhow::Result<Self> {
+        async fn text(row: &Element, selector: &str) -> Option<String> {
+            row.find(Locator::Css(selector))
+                .await
+                .ok()?
+                .text()
+                .await
+                .ok()
+        }
+        async fn texts(row: &Element, selector: &str) -> anyhow::Result<Vec<String>> {
+            let mut texts = Vec::new();
+            for element in row.find_all(Locator::Css(selector)).await? {
+                texts.push(element.text().await?);
+            }
+            Ok(texts)
+        }
+
+        Ok(Self {
+            class: row
+                .attr("class")
+                .await?
+                .ok_or(anyhow::anyhow!("class attribute should not be empty"))?,
+            home_team: text(row, ".event__participant--home").await,
+            away_team: text(row, ".event__participant--away").await,
+            home_score: text(row, ".event__score--home").await,
+            away_score: text(row, ".event__score--away").await,
+            home_parts: texts(row, ".event__part--home").await?,
+            away_parts: texts(row, ".event__part--away").await?,
+            event_time: text(row, ".event__time").await,
+            clock: match row.find(Locator::Css(".eventTime")).await {
+                Ok(clock) => Some(clock.text().await.unwrap_or_default()),
+                Err(_) => None,
+            },
+            basketball: row
+                .find(Locator::XPath(
+                    "./ancestor::div[contains(@class, 'basketball')]",
+                ))
+                .await
+                .is_ok(),
+        })
+    }
+
+    /// The header of a match detail page in the shape of a match row.
+    async fn read_detail(client: &mut Client) -> anyhow::Result<Self> {
+        async fn text(client: &mut Client, selector: &str) -> Option<String> {
+            client
+                .find(Locator::Css(selector))
+                .await
+                .ok()?
+                .text()
+                .await
+                .ok()
+        }
+
+        let mut scores = Vec::new();
+        for score in client
+            .find_all(Locator::Css(
+                ".detailScore__wrapper span:not(.detailScore__divider)",
+            ))
+            .await?
+        {
+            scores.push(score.text().await?);
+        }
+        // period scores are in the headers of the incidents, e.g. `1 - 0`
+        let (mut home_parts, mut away_parts) = (Vec::new(), Vec::new());
+        for header in client
+            .find_all(Locator::Css(".smv__incidentsHeader"))
+            .await?
+        {
+            let text = header.text().await?;
+            if let Some((home, away)) = text.lines().last().and_then(|line| line.split_once('-')) {
+                home_parts.push(home.trim().to_string());
+                away_parts.push(away.trim().to_string());
+            }
+        }
+        let live = client
+            .find(Locator::Css(".detailScore__live"))
+            .await
+            .is_ok();
+        let scheduled = !live && scores.iter().all(|score| score_digits(score).is_none());
+
+        Ok(Self {
+            class: if live {
+                "event__match--live"
+            } else if scheduled {
+                "event__match--scheduled"
+            } else {
+                ""
+            }
+            .to_string(),
+            home_team: text(
+                client,
+                ".duelParticipant__home .participant__participantName",
+            )
+            .await,
+            away_team: text(
+                client,
+                ".duelParticipant__away .participant__participantName",
+            )
+            .await,
+            home_score: Some(scores.first().cloned().unwrap_or_default()),
+            away_score: Some(scores.get(1).cloned().unwrap_or_default()),
+            home_parts,
+            away_parts,
+            event_time: if scheduled {
+                text(client, ".duelParticipant__startTime").await
+            } else {
+                None
+            },
+            clock: if live {
+                text(client, ".detailScore__status .eventTime").await
+            } else {
+                None
+            },
+            // the first link of the breadcrumb above the teams leads to the sport
+            basketball: match client
+                .find(Locator::Css(".tournamentHeader__sportNav a"))
+                .await
+            {
+                // the property, unlike the attribute, is resolved to an absolute URL
+                Ok(link) => link
+                    .prop("href")
+                    .await?
+                    .and_then(|href| Url::parse(&href).ok())
+                    .is_some_and(|url| details::is_basketball_url(&url)),
+                Err(_) => false,
+            },
+        })
+    }
+
+    fn required(value: &Option<String>, selector: &str) -> anyhow::Result<String> {
+        value.clone().ok_or_else(|| {
+            CrawlerError::SelectorMissing {
+                field: selector.to_string(),
+            }
+            .into()
+        })
+    }
+
+    fn periods(&self) -> Periods {
+        if self.basketball {
+            Periods::BASKETBALL
+        } else {
+            Periods::HOCKEY
+        }
+    }
+
+    fn minute_of_game(&self, quality: &mut quality::Quality) -> (GameTime, Option<Clock>) {
+        let period_minutes = self.periods().minutes;
+        let periods = self
+            .home_parts
+            .iter()
+            .filter(|part| !part.is_empty())
+            .count() as u64;
+        let mut minute = period_minutes * periods.saturating_sub(1);
+
+        if let Some(clock) = &self.clock {
+            let direction = if self.basketball {
+                ClockDirection::Down
+            } else {
+                ClockDirection::Up
+            };
+            let clock = parse_clock(clock, direction);
+            quality.record_parsed("game_time", clock.as_ref());
+            minute += match &clock {
+                Some(Clock {
+                    minute,
+                    direction: ClockDirection::Up,
+                    ..
+                }) => *minute,
+                Some(Clock {
+                    minute,
+                    second,
+                    direction: ClockDirection::Down,
+                }) => period_minutes.saturating_sub(minute + u64::from(second.unwrap_or(0) > 0)),
+                None => 0,
+            };
+            (GameTime::Playing(minute), clock)
+        } else {
+            // It must be break otherwise
+            quality.record("game_time", quality::Source::Fallback);
+            minute += period_minutes;
+            (GameTime::BreakAfter(minute), None)
+        }
+    }
+
+    fn period_scores(&self) -> Vec<(u64, u64)> {
+        self.home_parts
+            .iter()
+            .zip(self.away_parts.iter())
+            .take_while(|(home, _)| !home.is_empty())
+            .map(|(home, away)| (parse_score(home), parse_score(away)))
+            .collect()
+    }
+}
+
+pub async fn get_minute_of_game(
+    row: &Element,
+    quality: &mut quality::Quality,
+) -> anyhow::Result<(GameTime, Option<Clock>)> {
+    Ok(RawRow::read(row).await?.minute_of_game(quality))
+}
+
+async fn get_latest_match_element(client: &mut Client) -> anyhow::Result<Option<Element>> {
+    for _ in 0..10 {
+        sleep(Duration::from_millis(200)).await;
+        let last_match_row = client
+            .find_all(Locator::Css(".event__match"))
+            .await?
+            .into_iter()
+            .next();
+        if last_match_row.is_some() {
+            return Ok(last_match_row);
+        }
+        debug!("sleeping in find_all for .event__match");
+    }
+
+    Ok(None)
+}
+
+pub(crate) async fn element_text(parent: &Element, selector: &str) -> anyhow::Result<String> {
+    let element = match parent.find(Locator::Css(selector)).await {
+        Err(error) if error.is_no_such_element() => {
+            return Err(CrawlerError::SelectorMissing {
+                field: selector.to_string(),
+            }
+            .into())
+        }
+        result => result.with_context(|| format!("could not find {selector} element"))?,
+    };
+    element
+        .text()
+        .await
+        .with_context(|| format!("could not read text of {selector} element"))
+}
+
+/// Parse minute of the game like `67'` or `45+2'` (with added time).
+fn parse_minute(value: &str) -> Option<u64> {
+    let value = value.trim().trim_end_matches('\'');
+    value
+        .split('+')
+        .map(|part| part.trim().parse::<u64>().ok())
+        .try_fold(0u64, |minute, part| minute.checked_add(part?))
+}
+
+/// Parse the period clock like `12'`, `45+2'` or `07:45`.
+fn parse_clock(value: &str, direction: ClockDirection) -> Option<Clock> {
+    let value = value.trim();
+    match value.split_once(':') {
+        Some((minute, second)) => Some(Clock {
+            minute: minute.trim().parse().ok()?,
+            second: Some(second.trim().parse().ok().filter(|second| *second < 60)?),
+            direction,
+        }),
+        None => Some(Clock {
+            minute: parse_minute(value)?,
+            second: None,
+            direction,
+        }),
+    }
+}
+
+/// Digits of a score, none for a missing score like `-`; the Arabic-Indic and full-width
+/// digits of some locales are read as well.
+fn score_digits(value: &str) -> Option<u64> {
+    value
+        .trim()
+        .trim_matches(|c| matches!(c, '(' | ')' | '[' | ']' | '\u{ff08}' | '\u{ff09}'))
+        .chars()
+        .map(|c| {
+            let zero = match c {
+                '\u{660}'..='\u{669}' => '\u{660}',
+                '\u{6f0}'..='\u{6f9}' => '\u{6f0}',
+                '\u{ff10}'..='\u{ff19}' => '\u{ff10}',
+                _ => return c,
+            };
+            char::from(b'0' + (c as u32 - zero as u32) as u8)
+        })
+        .collect::<String>()
+        .parse()
+        .ok()
+}
+
+/// Parse score like `2` or a period score like `(1)`; missing score (`-`) is zero.
+pub(crate) fn parse_score(value: &str) -> u64 {
+    score_digits(value).unwrap_or_default()
+}
+
+/// Parse the start of a match: a time like `18:00`, `18.00`, `18h00` or `6:00 PM`, optionally
+/// preceded by a date like `07.09.`, `07/09`, `07-09-2025` or `2025-09-07`.
+///
+/// The order of day and month follows the locale of the site: dates starting with the year
+/// are ISO, a part over 12 can only be the day, and otherwise the month goes first on
+/// 12-hour-clock (US) sites and the day everywhere else.
+pub fn parse_datetime(value: &str) -> anyhow::Result<NaiveDateTime> {
+    let parse_time = |time: &str, meridiem: Option<&str>| -> anyhow::Result<_> {
+        let (hour, minute) = time
+            .split_once([':', '.', 'h', 'H'])
+            .context("time should have hours and minutes")?;
+        let mut hour: u32 = hour.parse().context("hour cannot be parsed")?;
+        if let Some(meridiem) = meridiem {
+            anyhow::ensure!((1..=12).contains(&hour), "hour is not on 12-hour clock");
+            hour = hour % 12 + if meridiem == "pm" { 12 } else { 0 };
+        }
+        NaiveTime::from_hms_opt(hour, minute.parse().context("minute cannot be parsed")?, 0)
+            .context("cannot parse NaiveTime")
+    };
+
+    let parse_date = |date: &str, month_first: bool| -> anyhow::Result<_> {
+        let mut date_parts: Vec<_> = date
+            .split(['.', '/', '-'])
+            .filter(|part| !part.is_empty())
+            .collect();
+        if date_parts.len() == 3 && date_parts[0].len() == 4 {
+            date_parts.rotate_left(1);
+            date_parts.swap(0, 1);
+        } else if date_parts.len() >= 2 {
+            let number = |index: usize| date_parts[index].parse::<u32>().unwrap_or_default();
+            if number(0) <= 12 && (number(1) > 12 || month_first) {
+                date_parts.swap(0, 1);
+            }
+        }
+        let day = date_parts.first().context("date: day part missing")?;
+        let month = date_parts.get(1).context("date: month part missing")?;
+        let year = match date_parts.get(2) {
+            Some(year) => match year.parse().context("year cannot be parsed")? {
+                year @ 0..=99 => 2000 + year,
+                year => year,
+            },
+            None => Local::now().year(),
+        };
+        NaiveDate::from_ymd_opt(
+            year,
+            month.parse().context("month cannot be parsed")?,
+            day.parse().context("day cannot be parsed")?,
+        )
+        .context("cannot parse NaiveDate")
+    };
+
+    let mut parts: Vec<_> = value.split_whitespace().collect();
+    let mut meridiem = None;
+    if let Some(last) = parts.last_mut() {
+        if let Some(suffix) = ["am", "pm"]
+            .into_iter()
+            .find(|suffix| last.to_ascii_lowercase().ends_with(suffix))
+        {
+            meridiem = Some(suffix);
+            *last = &last[..last.len() - suffix.len()];
+            if last.is_empty() {
+                parts.pop();
+            }
+        }
+    }
+
+    match parts.as_slice() {
+        [time] => Ok(NaiveDateTime::new(
+            Local::now().date_naive(),
+            parse_time(time, meridiem)?,
+        )),
+        [date, time] => Ok(NaiveDateTime::new(
+            parse_date(date, meridiem.is_some())?,
+            parse_time(time, meridiem)?,
+        )),
+        _ => anyhow::bail!("unexpected date and time format"),
+    }
+}
+
+/// Hours and minutes until the kickoff given in local time of the site, correct across DST changes.
+fn countdown<Z: TimeZone>(kickoff: NaiveDateTime, now: &DateTime<Z>) -> (u64, u64) {
+    let timezone = now.timezone();
+    // a kickoff in the hour skipped by the DST change is shown as the hour after it
+    let Some(kickoff) = timezone
+        .from_local_datetime(&kickoff)
+        .earliest()
+        .or_else(|| {
+            timezone
+                .from_local_datetime(&(kickoff + chrono::Duration::hours(1)))
+                .earliest()
+        })
+    else {
+        return (0, 0);
+    };
+
+    let delta = kickoff.signed_duration_since(now.clone());
+    if delta < chrono::Duration::zero() {
+        (0, 0)
+    } else {
+        (delta.num_hours() as u64, (delta.num_minutes() as u64) % 60)
+    }
+}
+
+/// Latest (live, scheduled or finished) match of the team.
+pub async fn get_score(
+    source: &dyn ScoreSource,
+    url: &Url,
+    team_name: &str,
+    options: &ScrapeOptions,
+) -> anyhow::Result<GameResult> {
+    let mut result = source.get_score(url, team_name, options).await?;
+    result.served_by = Some(url.to_string());
+    Ok(result)
+}
+
+pub(crate) async fn scrape_team_page(
+    client: &mut Client,
+    url: &Url,
+    team_name: &str,
+    options: &ScrapeOptions,
+    layout: &Mutex<layout::LayoutDetector>,
+) -> anyhow::Result<GameResult> {
+    if options.network_feed {
+        cdp::clear(client).await?;
+    }
+    budget::goto(client, url.as_str()).await?;
+
+    // wait for a reasonable time before we inspect DOM
+    let settle_time = Duration::from_millis(500);
+    tokio::time::sleep(if options.stealth {
+        stealth::jitter(settle_time)
+    } else {
+        settle_time
+    })
+    .await;
+
+    let last_match_row =
+        get_latest_match_element(client)
+            .await?
+            .ok_or(CrawlerError::SelectorMissing {
+                field: ".event__match".to_string(),
+            })?;
+
+    let html = last_match_row.html(false).await?;
+    let change = layout.lock().unwrap().check(&html);
+    if let Some(change) = change {
+        warn!(
+            "layout of the match row changed by {:.0} %, the site was probably redesigned",
+            change * 100.0
+        );
+        reporting::report_layout_change(url, change);
+    }
+
+    let feed_row = if options.network_feed {
+        http::feed_row(&cdp::feeds(client).await?)
+    } else {
+        None
+    };
+    let row = match feed_row {
+        Some(mut row) => {
+            // the feed has no running clock, the page computes it
+            if row.game_time.is_running() {
+                if let Ok((game_time, clock)) =
+                    get_minute_of_game(&last_match_row, &mut row.quality).await
+                {
+                    row.game_time = game_time;
+                    row.clock = clock;
+                }
+            }
+            row
+        }
+        None => {
+            if options.network_feed {
+                warn!("no match found in the network feed, reading the page");
+            }
+            let timezone = match options.timezone {
+                Some(timezone) => Some(timezone),
+                None => timezone::detect(client).await,
+            };
+            MatchRow::read(&last_match_row, timezone).await?
+        }
+    };
+    let is_home = row.is_home(team_name);
+    let opponent = if is_home {
+        row.away_team.clone()
+    } else {
+        row.home_team.clone()
+    };
+    let mut series_score = match (options.best_of, &options.bracket) {
+        (Some(_), None) => {
+            Some(series::get_series_score(client, team_name, &opponent, options).await?)
+        }
+        _ => None,
+    };
+
+    // the detail page lags behind the row (e.g. no statistics tab right after the
+    // kickoff), so the row result is kept without what could not be read
+    let scheduled = matches!(row.game_time, GameTime::WillBePlayed(_));
+    let mut match_details = None;
+    let mut broadcast = None;
+    let mut opponent_form = None;
+    if options.details && !scheduled {
+        match_details = best_effort(
+            "the match details",
+            async {
+                let match_url = details::match_url(&last_match_row).await?;
+                details::get_details(client, &match_url).await
+            }
+            .await,
+        );
+    } else if scheduled {
+        if options.details {
+            broadcast = best_effort(
+                "the broadcast",
+                async {
+                    let match_url = details::match_url(&last_match_row).await?;
+                    details::get_broadcast(client, &match_url).await
+                }
+                .await,
+            );
+        }
+        if options.opponent_form {
+            opponent_form = best_effort(
+                "the opponent form",
+                async {
+                    let match_url = details::match_url(&last_match_row).await?;
+                    form::get_opponent_form(client, &match_url, &opponent).await
+                }
+                .await,
+            );
+        }
+    }
+    if let Some(bracket) = &options.bracket {
+        series_score = Some(series::get_bracket_score(client, bracket, team_name).await?);
+    }
+
+    // a real visitor does not bounce through an empty page
+    if !options.stealth {
+        client.goto("about:blank").await?;
+    }
+
+    let mut latest_match = row.into_result(is_home);
+    let details_missing = options.details && match_details.is_none() && broadcast.is_none();
+    let form_missing = scheduled && options.opponent_form && opponent_form.is_none();
+    latest_match.broadcast = broadcast.unwrap_or_default();
+    if let Some(details) = match_details {
+        add_details(&mut latest_match, details, is_home, &options.players);
+    }
+    if let Some(quality) = &mut latest_match.quality {
+        if options.details {
+            quality.record_parsed("broadcast", latest_match.broadcast.first());
+        }
+        // the pipeline carries the previous values over
+        if details_missing {
+            quality.record("details", quality::Source::Default);
+        }
+        if form_missing {
+            quality.record("opponent_form", quality::Source::Default);
+        }
+    }
+    latest_match.opponent_form = opponent_form;
+    latest_match.series_score = series_score;
+    latest_match.series_best_of = options.best_of;
+
+    Ok(latest_match)
+}
+
+/// Value of an optional part of the result, logged and left out when it cannot be read.
+fn best_effort<T>(what: &str, result: anyhow::Result<T>) -> Option<T> {
+    result
+        .map_err(|error| warn!("could not read {what}: {error:#}"))
+        .ok()
+}
+
+/// Latest state of the match of a detail page; there is no row of a team page to pick.
+pub(crate) async fn scrape_match_page(
+    client: &mut Client,
+    url: &Url,
+    team_name: &str,
+    options: &ScrapeOptions,
+) -> anyhow::Result<GameResult> {
+    budget::goto(client, url.as_str()).await?;
+    sleep(Duration::from_millis(500)).await;
+
+    let timezone = match options.timezone {
+        Some(timezone) => Some(timezone),
+        None => timezone::detect(client).await,
+    };
+    let row = MatchRow::parse(&RawRow::read_detail(client).await?, timezone)?;
+    let is_home = row.is_home(team_name);
+    let scheduled = matches!(row.game_time, GameTime::WillBePlayed(_));
+    let mut latest_match = row.into_result(is_home);
+    if options.details && !scheduled {
+        let details = details::get_details(client, url).await?;
+        add_details(&mut latest_match, details, is_home, &options.players);
+    } else if options.details {
+        latest_match.broadcast = details::get_broadcast(client, url).await?;
+    }
+    if let Some(quality) = &mut latest_match.quality {
+        if options.details {
+            quality.record_parsed("broadcast", latest_match.broadcast.first());
+        }
+    }
+
+    if !options.stealth {
+        client.goto("about:blank").await?;
+    }
+    Ok(latest_match)
+}
+
+/// Goals, penalties, statistics and broadcast of the detail page from my team's perspective.
+fn add_details(
+    result: &mut GameResult,
+    details: details::MatchDetails,
+    is_home: bool,
+    players: &[String],
+) {
+    result.goals = details
+        .goals
+        .into_iter()
+        .map(|goal| goal.into_goal(is_home, players))
+        .collect();
+    result.penalties = details
+        .penalties
+        .into_iter()
+        .map(|penalty| penalty.into_penalty(is_home))
+        .collect();
+    result.stats = details
+        .statistics
+        .into_iter()
+        .map(|(name, home, away)| {
+            let (my_team, opponent_team) = if is_home { (home, away) } else { (away, home) };
+            details::Statistic {
+                name,
+                my_team,
+                opponent_team,
+            }
+        })
+        .collect();
+    result.broadcast = details.broadcast;
+}
+
+/// Content of one `.event__match` row, home team first.
+pub(crate) struct MatchRow {
+    pub(crate) home_team: String,
+    pub(crate) away_team: String,
+    pub(crate) home_score: u64,
+    pub(crate) away_score: u64,
+    pub(crate) period_scores: Vec<(u64, u64)>,
+    pub(crate) game_time: GameTime,
+    pub(crate) clock: Option<Clock>,
+    pub(crate) periods: Periods,
+    pub(crate) quality: quality::Quality,
+}
+
+impl MatchRow {
+    pub(crate) async fn read(row: &Element, timezone: Option<Tz>) -> anyhow::Result<Self> {
+        Self::parse(&RawRow::read(row).await?, timezone)
+    }
+
+    pub(crate) fn parse(row: &RawRow, timezone: Option<Tz>) -> anyhow::Result<Self> {
+        let home_team = RawRow::required(&row.home_team, ".event__participant--home")?;
+        let away_team = RawRow::required(&row.away_team, ".event__participant--away")?;
+        let mut quality = quality::Quality::default();
+        let home_score = RawRow::required(&row.home_score, ".event__score--home")?;
+        let away_score = RawRow::required(&row.away_score, ".event__score--away")?;
+        quality.record_parsed(
+            "score",
+            score_digits(&home_score).zip(score_digits(&away_score)),
+        );
+        let (home_score, away_score) = (parse_score(&home_score), parse_score(&away_score));
+
+        let period_scores = row.period_scores();
+
+        let event_time = if let Some(event_time) = &row.event_time {
+            let match_date_time =
+                parse_datetime(event_time).map_err(|_| CrawlerError::ParseFailed {
+                    field: "event_time".to_string(),
+                    raw: event_time.clone(),
+                })?;
+            debug!("Match will be played: {match_date_time}");
+            Some(match timezone {
+                Some(timezone) => countdown(match_date_time, &Utc::now().with_timezone(&timezone)),
+                None => countdown(match_date_time, &Local::now()),
+            })
+        } else {
+            None
+        };
+
+        let (game_time, clock) = if row.class.contains("event__match--live") {
+            row.minute_of_game(&mut quality)
+        } else if row.class.contains("event__match--scheduled") {
+            quality.record_parsed("game_time", event_time);
+            (GameTime::WillBePlayed(event_time), None)
+        } else {
+            quality.record("game_time", quality::Source::Primary);
+            (GameTime::Played, None)
+        };
+
+        Ok(Self {
+            home_team,
+            away_team,
+            home_score,
+            away_score,
+            period_scores,
+            game_time,
+            clock,
+            periods: row.periods(),
+            quality,
+        })
+    }
+
+    pub(crate) fn is_home(&self, team_name: &str) -> bool {
+        self.home_team.starts_with(team_name)
+    }
+
+    /// Result from my team's perspective, without anything from the detail page.
+    pub(crate) fn into_result(self, is_home: bool) -> GameResult {
+        let (my_team, opponent_team, my_team_score, opponent_team_score, period_scores) = if is_home
+        {
+            (
+                self.home_team,
+                self.away_team,
+                self.home_score,
+                self.away_score,
+                self.period_scores,
+            )
+        } else {
+            (
+                self.away_team,
+                self.home_team,
+                self.away_score,
+                self.home_score,
+                self.period_scores
+                    .into_iter()
+                    .map(|(home, away)| (away, home))
+                    .collect(),
+            )
+        };
+
+        GameResult {
+            my_team,
+            my_team_score,
+            opponent_team,
+            opponent_team_score,
+            period_scores,
+            goals: Vec::new(),
+            penalties: Vec::new(),
+            stats: Vec::new(),
+            broadcast: Vec::new(),
+            opponent_form: None,
+            series_score: None,
+            series_best_of: None,
+            timeline: Vec::new(),
+            estimated_time_remaining: None,
+            estimated_end_at: None,
+            started_at: None,
+            elapsed_minutes: None,
+            last_goal_at: None,
+            minutes_since_last_goal: None,
+            outcome: None,
+            goal_difference: None,
+            is_leading: None,
+            clock: self.clock,
+            quality: Some(self.quality),
+            stale_since: None,
+            served_by: None,
+            game_time: self.game_time,
+            periods: self.periods,
+            generated: Local::now(),
+        }
+    }
+}
+
+/// Open a headless Chrome session on a running WebDriver server.
+pub async fn connect(webdriver: &str, stealth: bool, network_feed: bool) -> anyhow::Result<Client> {
+    let mut chrome_args = vec!["--headless".to_string()];
+    if stealth {
+        chrome_args.extend(stealth::chrome_args());
+    }
+    let mut cap = serde_json::json!({"goog:chromeOptions": {"args": chrome_args}});
+    if network_feed {
+        cap["goog:loggingPrefs"] = cdp::logging_preferences();
+    }
+    let cap: Capabilities = serde_json::from_value(cap).unwrap();
+
+    #[cfg(feature = "rustls")]
+    let mut builder = ClientBuilder::rustls()?;
+    #[cfg(all(feature = "native-tls", not(feature = "rustls")))]
+    let mut builder = ClientBuilder::native();
+    Ok(builder.capabilities(cap).connect(webdriver).await?)
+}
+
+/// Scraper of team pages keeping its WebDriver session between the scrapes.
+pub struct Crawler {
+    source: WebDriverSource,
+    options: ScrapeOptions,
+    shutdown: CancellationToken,
+}
+
+impl Crawler {
+    pub fn new(client: Client, options: ScrapeOptions) -> Self {
+        Self {
+            source: WebDriverSource::new(client),
+            options,
+            shutdown: CancellationToken::new(),
+        }
+    }
+
+    /// Token stopping [`Crawler::watch`], e.g. to be cancelled from a signal handler while
+    /// the crawler is borrowed by the loop.
+    pub fn shutdown_token(&self) -> CancellationToken {
+        self.shutdown.clone()
+    }
+
+    /// Stop [`Crawler::watch`], which closes the WebDriver session on its way out.
+    pub fn shutdown(&self) {
+        self.shutdown.cancel();
+    }
+
+    /// Start a headless Chrome session on a running WebDriver server.
+    pub async fn connect(webdriver: &str, options: ScrapeOptions) -> Result<Self, CrawlerError> {
+        let client = connect(webdriver, options.stealth, options.network_feed)
+            .await
+            .map_err(CrawlerError::DriverStartup)?;
+        Ok(Self::new(client, options))
+    }
+
+    /// Latest (live, scheduled or finished) match of the team.
+    pub async fn get_score(
+        &mut self,
+        url: &Url,
+        team_name: &str,
+    ) -> Result<GameResult, CrawlerError> {
+        Ok(get_score(&self.source, url, team_name, &self.options).await?)
+    }
+
+    /// Previous, current (or next) and following match of the team.
+    pub async fn get_context(
+        &mut self,
+        url: &Url,
+        team_name: &str,
+    ) -> Result<context::MatchContext, CrawlerError> {
+        let current = self.get_score(url, team_name).await?;
+        Ok(
+            context::get_context(self.source.client(), url, team_name, &self.options, current)
+                .await?,
+        )
+    }
+
+    /// Scrape the team at the pace of the policy and tell the observer about the changes, the
+    /// match events and the failed scrapes; returns when the session is lost or after a
+    /// shutdown, which also ends the session.
+    pub async fn watch(
+        &mut self,
+        url: &Url,
+        team_name: &str,
+        policy: &mut dyn poll::PollPolicy,
+        observer: &mut dyn observer::Observer,
+    ) -> Result<(), CrawlerError> {
+        let mut previous: Option<GameResult> = None;
+        while !self.shutdown.is_cancelled() {
+            let shutdown = self.shutdown.clone();
+            // a page load can take long, do not let it hold up the shutdown
+            let scrape = tokio::select! {
+                _ = shutdown.cancelled() => break,
+                scrape = self.get_score(url, team_name) => scrape,
+            };
+            let latest = match scrape {
+                Ok(latest) => Some(latest),
+                Err(error @ CrawlerError::SessionLost(_)) => return Err(error),
+                Err(error) => {
+                    observer.on_error(&anyhow::Error::new(error));
+                    None
+                }
+            };
+            if let Some(latest) = &latest {
+                if previous
+                    .as_ref()
+                    .is_none_or(|previous| events::changed(previous, latest))
+                {
+                    observer.on_change(previous.as_ref(), latest);
+                }
+                if let Some(previous) = &previous {
+                    for event in events::between(previous, latest) {
+                        observer.on_event(&event, latest);
+                    }
+                }
+            }
+            let delay = policy.next_delay(latest.as_ref(), Local::now());
+            previous = latest.or(previous);
+            tokio::select! {
+                _ = self.shutdown.cancelled() => break,
+                _ = sleep(delay) => {}
+            }
+        }
+        // the client is a handle of the session, closing a clone ends it
+        self.source
+            .client()
+            .clone()
+            .close()
+            .await
+            .map_err(|error| anyhow::Error::from(error).into())
+    }
+
+    pub fn client(&mut self) -> &mut Client {
+        self.source.client()
+    }
+
+    /// End the WebDriver session.
+    pub async fn close(self) -> Result<(), CrawlerError> {
+        self.source
+            .into_client()
+            .close()
+            .await
+            .map_err(|error| anyhow::Error::from(error).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Timelike;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_parse_minute() {
+        assert_eq!(parse_minute("67'"), Some(67));
+        assert_eq!(parse_minute("45+2'"), Some(47));
+        assert_eq!(parse_minute("5"), Some(5));
+        assert_eq!(parse_minute(""), None);
+        assert_eq!(parse_minute("Přestávka"), None);
+    }
+
+    #[test]
+    fn test_minute_of_game_basketball() {
+        let row = RawRow {
+            class: "event__match event__match--live".to_string(),
+            home_team: Some("Nymburk".to_string()),
+            away_team: Some("Opava".to_string()),
+            home_score: Some("38".to_string()),
+            away_score: Some("31".to_string()),
+            home_parts: vec!["20".to_string(), "18".to_string()],
+            away_parts: vec!["17".to_string(), "14".to_string()],
+            event_time: None,
+            clock: Some("04:30".to_string()),
+            basketball: true,
+        };
+        let mut quality = quality::Quality::default();
+        let (game_time, clock) = row.minute_of_game(&mut quality);
+        // 10 minutes of the first quarter and 5 started ones of the second
+        assert_eq!(game_time, GameTime::Playing(15));
+        assert_eq!(row.periods().of(&game_time), Some(2));
+        assert_eq!(
+            clock.map(|clock| clock.direction),
+            Some(ClockDirection::Down)
+        );
+
+        let break_ = RawRow { clock: None, ..row };
+        assert_eq!(
+            break_.minute_of_game(&mut quality).0,
+            GameTime::BreakAfter(20)
+        );
+    }
+
+    #[test]
+    fn test_parse_clock() {
+        let clock = |value| {
+            parse_clock(value, ClockDirection::Up).map(|clock| (clock.minute, clock.second))
+        };
+        assert_eq!(clock("12'"), Some((12, None)));
+        assert_eq!(clock("45+2'"), Some((47, None)));
+        assert_eq!(clock(" 07:45 "), Some((7, Some(45))));
+        assert_eq!(clock("07:75"), None);
+        assert_eq!(clock("Přestávka"), None);
+    }
+
+    #[test]
+    fn test_derive_perspective() {
+        let mut result = MatchRow {
+            home_team: "Sparta Praha".to_string(),
+            away_team: "Kometa Brno".to_string(),
+            home_score: 3,
+            away_score: 2,
+            period_scores: Vec::new(),
+            game_time: GameTime::Playing(50),
+            clock: None,
+            periods: Periods::HOCKEY,
+            quality: quality::Quality::default(),
+        }
+        .into_result(false);
+        result.derive_perspective();
+        assert_eq!(result.outcome, Some(Outcome::Pending));
+        assert_eq!(result.goal_difference, Some(-1));
+        assert_eq!(result.is_leading, Some(false));
+
+        result.my_team_score = 4;
+        result.game_time = GameTime::Played;
+        result.derive_perspective();
+        assert_eq!(result.outcome, Some(Outcome::Win));
+        assert_eq!(result.is_leading, Some(true));
+    }
+
+    #[test]
+    fn test_diff_and_merge() {
+        let previous = MatchRow {
+            home_team: "Sparta Praha".to_string(),
+            away_team: "Kometa Brno".to_string(),
+            home_score: 1,
+            away_score: 0,
+            period_scores: Vec::new(),
+            game_time: GameTime::Playing(18),
+            clock: None,
+            periods: Periods::HOCKEY,
+            quality: quality::Quality::default(),
+        }
+        .into_result(true);
+        let mut latest = GameResult {
+            my_team_score: 2,
+            game_time: GameTime::BreakAfter(20),
+            ..previous.clone()
+        };
+        let kinds: Vec<_> = previous
+            .diff(&latest)
+            .iter()
+            .map(|event| event.kind.name())
+            .collect();
+        assert_eq!(kinds, ["goal_for", "break"]);
+        assert!(latest.changed_from(&previous));
+
+        let previous = GameResult {
+            broadcast: vec!["ČT sport".to_string()],
+            series_score: Some((2, 1)),
+            ..previous
+        };
+        latest.merge(&previous);
+        assert_eq!(latest.broadcast, ["ČT sport"]);
+        assert_eq!(latest.series_score, Some((2, 1)));
+    }
+
+    #[test]
+    fn test_parse_score() {
+        assert_eq!(parse_score("3"), 3);
+        assert_eq!(parse_score("(1)"), 1);
+        assert_eq!(parse_score("-"), 0);
+        assert_eq!(parse_score(" [2]\u{a0}"), 2);
+        assert_eq!(parse_score("\u{663}"), 3);
+        assert_eq!(parse_score("\u{ff08}\u{ff11}\u{ff09}"), 1);
+    }
+
+    proptest! {
+        #[test]
+        fn proptest_parse_datetime_never_panics(value in "\\PC*") {
+            let _ = parse_datetime(&value);
+        }
+
+        #[test]
+        fn proptest_parse_datetime(
+            day in 1u32..=28,
+            month in 1u32..=12,
+            hour in 0u32..24,
+            minute in 0u32..60,
+        ) {
+            let parsed = parse_datetime(&format!("{day:02}.{month:02}. {hour:02}:{minute:02}")).unwrap();
+            prop_assert_eq!(
+                (parsed.day(), parsed.month(), parsed.hour(), parsed.minute()),
+                (day, month, hour, minute)
+            );
+        }
+
+        #[test]
+        fn proptest_parse_minute_never_panics(value in "\\PC*") {
+            let _ = parse_minute(&value);
+        }
+
+        #[test]
+        fn proptest_parse_minute(minute in any::<u64>(), added in 0u64..20) {
+            prop_assert_eq!(
+                parse_minute(&format!("{minute}+{added}'")),
+                minute.checked_add(added)
+            );
+        }
+
+        #[test]
+        fn proptest_parse_score(score in any::<u64>(), value in "\\PC*") {
+            prop_assert_eq!(parse_score(&score.to_string()), score);
+            prop_assert_eq!(parse_score(&format!("({score})")), score);
+            let _ = parse_score(&value);
+        }
+    }
+
+    #[test]
+    fn test_parse_datetime() {
+        let today = Local::now();
+
+        assert_eq!(
+            parse_datetime("07.09. 18:00").unwrap().to_string(),
+            format!("{}-09-07 18:00:00", today.year())
+        );
+        assert_eq!(
+            parse_datetime("18:00").unwrap().to_string(),
+            format!(
+                "{}-{:02}-{:02} 18:00:00",
+                today.year(),
+                today.month(),
+                today.day()
+            )
+        );
+    }
+
+    #[test]
+    fn test_countdown_dst() {
+        let prague = |date: &str| {
+            chrono_tz::Europe::Prague
+                .from_local_datetime(
+                    &NaiveDateTime::parse_from_str(date, "%Y-%m-%d %H:%M").unwrap(),
+                )
+                .unwrap()
+        };
+        let kickoff = |date: &str| NaiveDateTime::parse_from_str(date, "%Y-%m-%d %H:%M").unwrap();
+
+        // spring forward: the night is one hour shorter
+        assert_eq!(
+            countdown(kickoff("2025-03-30 18:00"), &prague("2025-03-29 20:00")),
+            (21, 0)
+        );
+        // fall back: the night is one hour longer
+        assert_eq!(
+            countdown(kickoff("2025-10-26 18:00"), &prague("2025-10-25 20:00")),
+            (23, 0)
+        );
+        // the skipped hour
+        assert_eq!(
+            countdown(kickoff("2025-03-30 02:30"), &prague("2025-03-30 01:00")),
+            (1, 30)
+        );
+        assert_eq!(
+            countdown(kickoff("2025-03-29 18:00"), &prague("2025-03-29 20:00")),
+            (0, 0)
+        );
+    }
+
+    #[test]
+    fn test_parse_datetime_locales() {
+        let time = |value| parse_datetime(value).unwrap().time().to_string();
+        assert_eq!(time("18.00"), "18:00:00");
+        assert_eq!(time("18h30"), "18:30:00");
+        assert_eq!(time("6:00 PM"), "18:00:00");
+        assert_eq!(time("6:00pm"), "18:00:00");
+        assert_eq!(time("12:15 AM"), "00:15:00");
+        assert_eq!(time("12:15 PM"), "12:15:00");
+        assert_eq!(time("07.09.\u{a0}18:00"), "18:00:00");
+
+        let date = |value| parse_datetime(value).unwrap().date().to_string();
+        assert_eq!(date("07/09/2025 18:00"), "2025-09-07");
+        assert_eq!(date("07-09-25 18:00"), "2025-09-07");
+        assert_eq!(date("2025-09-07 18:00"), "2025-09-07");
+        assert_eq!(date("2025/09/07 6:00 PM"), "2025-09-07");
+        // US sites with the 12-hour clock put the month first
+        assert_eq!(date("09/07/2025 6:00 PM"), "2025-09-07");
+        assert_eq!(
+            date("09/07 6:00pm"),
+            format!("{}-09-07", Local::now().year())
+        );
+        // unambiguous either way
+        assert_eq!(date("09/17/2025 18:00"), "2025-09-17");
+        assert_eq!(date("17/09/2025 6:00 PM"), "2025-09-17");
+        assert!(parse_datetime("07.09. 18:00 extra").is_err());
+        assert!(parse_datetime("6:00 XM").is_err());
+        assert!(parse_datetime("PM").is_err());
+    }
+}
@@ -1,63 +1,675 @@
 use anyhow::Context;
-use chrono::{DateTime, Datelike, Local, NaiveDate, NaiveDateTime, NaiveTime};
-use clap::Parser;
-use fantoccini::elements::Element;
+use chrono::{Local, NaiveDate};
+use chrono_tz::Tz;
+use clap::{Args, CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum};
 use fantoccini::Client;
-use fantoccini::{wd::Capabilities, ClientBuilder, Locator};
-use serde::Serialize;
-use std::fs::File;
-use std::path::PathBuf;
+use livesport_crawler::html::{self, HtmlSource};
+use livesport_crawler::http::HttpSource;
+use livesport_crawler::poll::PollPolicy;
+use livesport_crawler::{
+    bandwidth, bracket, budget, calendar, context, details, estimate, events, get_score, layout,
+    observer, poll, quality, reporting, results, schedule, season, standings, stealth,
+    ClockDirection, GameResult, GameTime, Periods, ScrapeOptions, WebDriverSource, PERIODS,
+    PERIOD_MINUTES,
+};
+#[cfg(feature = "server")]
+use rand::Rng;
+use serde::Deserialize;
+use std::fs;
+#[cfg(feature = "server")]
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 use std::{
     process::{Child, Command, Stdio},
     thread,
-    time::Duration,
+    time::{Duration, Instant},
 };
+#[cfg(feature = "server")]
+use tokio::net::TcpListener;
 use tokio::signal;
-use tokio::time::sleep;
+use tokio::sync::Notify;
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, info, warn};
 use url::Url;
 
-const DRIVER_PORT: u16 = 9515;
-
-#[derive(Debug, Serialize)]
-enum GameTime {
-    WillBePlayed(Option<(u64, u64)>),
-    Played,
-    BreakAfter(u64),
-    Playing(u64),
-}
+mod archive;
+mod bench;
+mod chart;
+mod config;
+#[cfg(feature = "redis")]
+mod dedup;
+#[cfg(feature = "desktop")]
+mod desktop;
+mod domains;
+#[cfg(feature = "smtp")]
+mod email;
+#[cfg(test)]
+mod fixtures;
+#[cfg(feature = "chat")]
+mod gotify;
+mod hook;
+mod latency;
+#[cfg(feature = "chat")]
+mod matrix;
+mod metrics;
+#[cfg(feature = "mqtt")]
+mod mqtt;
+#[cfg(feature = "chat")]
+mod ntfy;
+mod openligadb;
+mod permissions;
+mod pipeline;
+mod plugin;
+mod privileges;
+mod profile;
+mod replay;
+mod search;
+mod secrets;
+#[cfg(feature = "server")]
+mod server;
+mod simulation;
+#[cfg(feature = "chat")]
+mod slack;
+mod standby;
+mod state;
+mod summary;
+mod teams;
+#[cfg(feature = "chat")]
+mod telegram;
+mod template;
+mod ticker;
+mod transition;
+mod trigger;
+mod upcoming;
+#[cfg(feature = "wasm")]
+mod wasm;
+mod webhook;
 
-#[allow(dead_code)]
-#[derive(Debug, Serialize)]
-struct GameResult {
-    my_team: String,
-    my_team_score: u64,
-    opponent_team: String,
-    opponent_team_score: u64,
-    game_time: GameTime,
-    generated: DateTime<Local>,
-}
+const DRIVER_PORT: u16 = 9515;
 
 #[derive(Parser)]
-#[command(version, about, long_about = None)]
+#[command(
+    version,
+    about,
+    long_about = None,
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
 struct Cli {
-    /// Livescore URL of the team
-    url: Url,
+    #[command(subcommand)]
+    command: Option<Commands>,
+
+    /// TOML file with the options; options given on the command line take precedence
+    #[arg(short, long)]
+    config: Option<PathBuf>,
+
+    /// Livescore URL of the team, or of a single match detail page (e.g. .../zapas/...) to
+    /// follow that match instead of guessing its row on the team page
+    #[arg(required_unless_present_any = ["config", "teams", "from_html"])]
+    url: Option<Url>,
 
     /// Team name
-    team_name: String,
+    #[arg(required_unless_present_any = ["config", "teams", "from_html"])]
+    team_name: Option<String>,
 
     /// JSON output file
-    output: PathBuf,
+    #[arg(required_unless_present_any = ["config", "teams", "from_html"])]
+    output: Option<PathBuf>,
+
+    /// Another team to track as URL,NAME,OUTPUT sharing the browser sessions (can be repeated)
+    #[arg(long = "team")]
+    teams: Vec<teams::Team>,
+
+    /// Address where the latest results are served over HTTP as `GET /score` and
+    /// `GET /score/<team name>`, pushed to WebSocket clients of `/ws` and `/ws/<team name>`
+    /// when they change and streamed with goals and status changes as Server-Sent Events
+    /// on `/events` and `/events/<team name>` (e.g. 127.0.0.1:8080)
+    #[cfg(feature = "server")]
+    #[arg(long, value_name = "ADDRESS")]
+    serve: Option<SocketAddr>,
+
+    /// Bearer token of the control API of --serve and of its /admin page, adding teams with
+    /// `POST /teams` and removing them with `DELETE /teams/<team>`, both saved to --config, and
+    /// pausing, resuming or refreshing them; also as @FILE or cmd:COMMAND
+    #[cfg(feature = "server")]
+    #[arg(long, requires = "serve")]
+    admin_token: Option<String>,
+
+    /// Octal mode of the written output files (e.g. 644)
+    #[arg(long, value_parser = privileges::parse_mode)]
+    output_mode: Option<u32>,
+
+    /// Octal mode of the created output directories (e.g. 755)
+    #[arg(long, value_parser = privileges::parse_mode)]
+    dir_mode: Option<u32>,
+
+    /// Owner of the written output files and created directories as USER[:GROUP] or :GROUP
+    #[arg(long, value_name = "USER[:GROUP]")]
+    output_owner: Option<String>,
+
+    /// User to run as once the --serve port is bound, for a start as root
+    #[arg(long)]
+    user: Option<String>,
+
+    /// Group to run as once the --serve port is bound, the primary group of --user by default
+    #[arg(long)]
+    group: Option<String>,
+
+    /// Octal mask of the permissions of the created files and directories (e.g. 027)
+    #[arg(long, value_parser = privileges::parse_mode)]
+    umask: Option<u32>,
+
+    /// Heartbeat beaten after every scrape as file:///PATH or mqtt://HOST[:PORT]/TOPIC, for
+    /// a standby instance to take over when it stops; --serve also serves it on /heartbeat
+    #[arg(long, value_name = "URL")]
+    heartbeat: Option<Url>,
+
+    /// Run as a standby of the primary beating the heartbeat at file:///PATH,
+    /// http://HOST:PORT/heartbeat or mqtt://HOST[:PORT]/TOPIC: scrape, but announce nothing
+    /// until the heartbeat goes stale, then take over
+    #[arg(long, value_name = "URL")]
+    standby: Option<Url>,
+
+    /// Seconds without a heartbeat after which the standby takes over
+    #[arg(long, default_value_t = 60)]
+    standby_timeout: u64,
+
+    /// Track only the teams of the club or the team given as CLUB or CLUB/TEAM of the config
+    /// (can be repeated)
+    #[arg(long, value_name = "CLUB/TEAM")]
+    only: Vec<String>,
+
+    /// Parse a team page saved to disk instead of crawling and print the result;
+    /// the team name defaults to the heading of the page
+    #[arg(long, value_name = "PATH", conflicts_with = "teams")]
+    from_html: Option<PathBuf>,
+
+    /// Mirror domain used when the team URL fails (e.g. www.flashscore.com, can be repeated)
+    #[arg(long = "mirror")]
+    mirrors: Vec<String>,
+
+    /// File keeping the layout of the match row to detect site redesigns across runs; with
+    /// several teams each gets its own file with the team inserted before the extension
+    #[arg(long)]
+    layout_snapshot: Option<PathBuf>,
+
+    #[command(flatten)]
+    sinks: SinkArgs,
+
+    /// Scrape the match detail page (penalties, statistics, broadcasters) as well
+    #[arg(long)]
+    details: bool,
+
+    /// Write the previous, the current (or next) and the following match into the output file
+    #[arg(long)]
+    context: bool,
+
+    /// Track the score of a best-of-N playoff series with the current opponent
+    #[arg(long)]
+    best_of: Option<u64>,
+
+    /// Playoff bracket page used for the series score instead of counting the mutual matches
+    #[arg(long, requires = "best_of")]
+    bracket: Option<Url>,
+
+    /// Player to watch in scoring events (can be repeated)
+    #[arg(long = "player")]
+    players: Vec<String>,
+
+    /// Scrape recent results of the opponent when the next match is scheduled
+    #[arg(long)]
+    opponent_form: bool,
+
+    /// Randomize browser fingerprint and timings to look less like a bot
+    #[arg(long)]
+    stealth: bool,
+
+    /// File keeping the site cookies across runs
+    #[arg(long, requires = "stealth")]
+    cookie_file: Option<PathBuf>,
+
+    /// Maximum number of page loads per hour and domain; the refresh is slowed down to fit
+    #[arg(long, value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+    max_page_loads: Option<usize>,
+
+    /// Megabytes the page loads may transfer per calendar month before the refresh is slowed
+    /// down to --capped-refresh; the usage is kept in --state-dir across restarts
+    #[arg(long, value_name = "MB")]
+    bandwidth_cap: Option<u64>,
+
+    /// Refresh interval in seconds once the monthly bandwidth cap is reached
+    #[arg(long, default_value_t = 600)]
+    capped_refresh: u64,
+
+    /// Time zone of the times shown on the site (e.g. Europe/Prague), read from the page by
+    /// default, the system one when the page does not tell
+    #[arg(long)]
+    timezone: Option<Tz>,
+
+    /// Hours between checks that the team has any upcoming match, 0 disables the check
+    #[arg(long, default_value_t = 24)]
+    fixture_check_hours: u64,
+
+    /// Read the match from the feed the page downloads (seen through Chrome DevTools)
+    /// instead of its DOM, falling back to the DOM
+    #[arg(long)]
+    network_feed: bool,
+
+    /// How the team page is read
+    #[arg(long, value_enum, default_value_t = Backend::WebDriver)]
+    backend: Backend,
+
+    /// Keep a colored score line of the teams, with a chart of the goal difference, updated
+    /// in place on the terminal; the log goes to the standard error then
+    #[arg(long)]
+    ticker: bool,
+
+    /// Produce a synthetic match instead of scraping the site
+    #[arg(long)]
+    simulate: bool,
+
+    /// Run the browser only from 30 minutes before to 3 hours after known kickoffs
+    #[arg(long, conflicts_with = "simulate")]
+    calendar: bool,
+
+    /// Start the browser for every refresh and shut it down right after the scrape
+    #[arg(long)]
+    ephemeral_browser: bool,
+
+    /// Browser sessions, each with its own chromedriver, the teams are spread over so that
+    /// a flaky page does not take down the updates of all of them
+    #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u16).range(1..))]
+    browsers: u16,
+
+    /// Scrape whenever this file is touched instead of every refresh interval
+    #[arg(long, conflicts_with = "schedule")]
+    trigger_file: Option<PathBuf>,
+
+    /// Cron expression of the scrape times (e.g. "* 17-21 * * Fri,Sat,Sun"), can be repeated;
+    /// replaces the refresh interval
+    #[arg(long)]
+    schedule: Vec<schedule::Expression>,
+
+    /// Estimate how much the scraped match clock lags behind and emit it as a metric
+    #[arg(long)]
+    measure_latency: bool,
+
+    /// Log the CPU and memory usage of the crawler and of the browser at most every SECONDS,
+    /// emit it as metrics and log its peaks at the exit
+    #[arg(long, value_name = "SECONDS")]
+    profile_interval: Option<u64>,
+
+    /// Minutes the last result is served marked as stale while the browser cannot be restarted
+    /// before exiting; with several browsers the teams of one that is down are served stale
+    /// until it is back and the crawler exits only once all of them are down for that long
+    #[arg(long, default_value_t = 10)]
+    max_outage: u64,
 
     /// Refresh interval
     #[arg(short, long, default_value_t = 30)]
     refresh: u64,
+
+    /// Sentry DSN used for error reporting (or @FILE / cmd:COMMAND printing it)
+    #[arg(long, env = "SENTRY_DSN")]
+    sentry_dsn: Option<String>,
+
+    /// StatsD or Graphite endpoint for metrics (e.g. statsd://localhost:8125)
+    #[arg(long)]
+    metrics: Option<Url>,
+
+    /// Prefix of the emitted metric names
+    #[arg(long, default_value = "livesport_crawler")]
+    metrics_prefix: String,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+enum Backend {
+    /// Headless Chrome driven by chromedriver
+    #[value(name = "webdriver")]
+    WebDriver,
+    /// Plain HTTP request reading the feed embedded in the page, only the match row is available
+    Http,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+enum OutputFormat {
+    /// Pretty JSON document of the latest result, rewritten on every refresh
+    Json,
+    /// One JSON line appended on every refresh, a time series to tail
+    Jsonl,
+    /// One JSON line appended when anything but the sampling time changes
+    JsonlChanges,
+    /// YAML document of the latest result, rewritten on every refresh
+    Yaml,
+    /// Compact MessagePack map of the latest result, rewritten on every refresh
+    #[value(name = "msgpack")]
+    #[serde(rename = "msgpack")]
+    MessagePack,
+}
+
+impl Cli {
+    /// The positional team followed by all --team ones, narrowed down by --only.
+    fn teams(&self) -> anyhow::Result<Vec<teams::Team>> {
+        let positional = match (&self.url, &self.team_name, &self.output) {
+            (Some(url), Some(name), Some(output)) => Some(teams::Team {
+                id: None,
+                url: url.clone(),
+                name: name.clone(),
+                output: output.clone(),
+                options: teams::TeamOptions::default(),
+                browser: None,
+                #[cfg(feature = "server")]
+                token: None,
+                club: None,
+            }),
+            _ => None,
+        };
+        teams::select(
+            positional
+                .into_iter()
+                .chain(self.teams.iter().cloned())
+                .collect(),
+            &self.only,
+        )
+    }
+}
+
+/// Outputs fed with every result besides the JSON output file.
+#[derive(Args)]
+struct SinkArgs {
+    /// How results are written to the output file
+    #[arg(long, value_enum, default_value_t = OutputFormat::Json)]
+    output_format: OutputFormat,
+
+    /// Directory where a consolidated archive of each finished match is stored
+    #[arg(long)]
+    archive_dir: Option<PathBuf>,
+
+    /// File where a summary of the last finished match is written
+    #[arg(long)]
+    summary_output: Option<PathBuf>,
+
+    /// Template of the match summary with {my_team}, {opponent_team}, {my_team_score},
+    /// {opponent_team_score}, {period_scores}, {scorers}, {broadcast}, {date}, {status},
+    /// {period} and {clock} placeholders, optionally piped through filters like
+    /// {opponent_team|truncate:12} or {status|status:cs}
+    #[arg(long)]
+    summary_template: Option<PathBuf>,
+
+    /// File where the match is written in the shape of the OpenLigaDB match data API
+    #[arg(long)]
+    openligadb_output: Option<PathBuf>,
+
+    /// Directory keeping the last result of every team across restarts, so that nothing
+    /// is announced twice
+    #[arg(long)]
+    state_dir: Option<PathBuf>,
+
+    /// Redis server as redis://[:PASSWORD@]HOST[:PORT] where match events are claimed before
+    /// they are announced, so that redundant instances announce each of them once; the
+    /// password may be percent-encoded @FILE or cmd:COMMAND
+    #[cfg(feature = "redis")]
+    #[arg(long)]
+    dedup_redis: Option<Url>,
+
+    /// MQTT broker as mqtt://[USER:PASSWORD@]HOST[:PORT] every result is published to; the
+    /// password may be percent-encoded @FILE or cmd:COMMAND
+    #[cfg(feature = "mqtt")]
+    #[arg(long)]
+    mqtt_broker: Option<Url>,
+
+    /// MQTT topic of the results, {team} stands for the team name; the last score is
+    /// retained under <topic>/score
+    #[cfg(feature = "mqtt")]
+    #[arg(long, default_value = "livesport/{team}")]
+    mqtt_topic: String,
+
+    /// JSON Lines file where goals involving the watched players are appended
+    #[arg(long)]
+    player_output: Option<PathBuf>,
+
+    /// Token of the Telegram bot announcing goals, the kick-off and the final result, also
+    /// as @FILE or cmd:COMMAND
+    #[cfg(feature = "chat")]
+    #[arg(long, requires = "telegram_chat")]
+    telegram_token: Option<String>,
+
+    /// Telegram chat id (or @channel) the bot writes to
+    #[cfg(feature = "chat")]
+    #[arg(long, requires = "telegram_token")]
+    telegram_chat: Option<String>,
+
+    /// Slack incoming webhook URL announcing the match events, also as @FILE or cmd:COMMAND
+    #[cfg(feature = "chat")]
+    #[arg(long)]
+    slack_webhook: Option<String>,
+
+    /// Slack message of an event as EVENT=TEMPLATE, EVENT being started, goal_for,
+    /// goal_against, player_goal, break, resumed or finished, with {my_team},
+    /// {opponent_team}, {my_team_score}, {opponent_team_score}, {minute} and {scorer}
+    /// placeholders; an empty template silences the event (can be repeated)
+    #[cfg(feature = "chat")]
+    #[arg(long, value_parser = slack::parse_template, requires = "slack_webhook")]
+    slack_template: Vec<(String, String)>,
+
+    /// SMTP server as smtps://[USER:PASSWORD@]HOST[:PORT] (or smtp://...?tls=required)
+    /// emailing the start of the match and the final score summary, also as @FILE or
+    /// cmd:COMMAND
+    #[cfg(feature = "smtp")]
+    #[arg(long, requires_all = ["email_from", "email_to"])]
+    smtp: Option<String>,
+
+    /// Sender of the emails, e.g. "Crawler <crawler@example.com>"
+    #[cfg(feature = "smtp")]
+    #[arg(long, requires = "smtp")]
+    email_from: Option<String>,
+
+    /// Recipient of the emails (can be repeated)
+    #[cfg(feature = "smtp")]
+    #[arg(long, requires = "smtp")]
+    email_to: Vec<String>,
+
+    /// Matrix homeserver URL (e.g. https://matrix.org) sending the score updates to a room
+    #[cfg(feature = "chat")]
+    #[arg(long, value_name = "URL", requires_all = ["matrix_token", "matrix_room"])]
+    matrix_homeserver: Option<Url>,
+
+    /// Access token of the Matrix account writing to the room, also as @FILE or cmd:COMMAND
+    #[cfg(feature = "chat")]
+    #[arg(long, requires = "matrix_homeserver")]
+    matrix_token: Option<String>,
+
+    /// Id of the Matrix room (e.g. !abcdef:matrix.org) the account has joined
+    #[cfg(feature = "chat")]
+    #[arg(long, requires = "matrix_homeserver")]
+    matrix_room: Option<String>,
+
+    /// ntfy topic URL (e.g. https://ntfy.sh/TOPIC) receiving push notifications of goals with
+    /// high priority, other events with lower priority and the running clock silently
+    #[cfg(feature = "chat")]
+    #[arg(long, value_name = "URL")]
+    ntfy: Option<Url>,
+
+    /// Access token of the ntfy topic, also as @FILE or cmd:COMMAND
+    #[cfg(feature = "chat")]
+    #[arg(long, requires = "ntfy")]
+    ntfy_token: Option<String>,
+
+    /// Gotify server URL receiving push notifications of goals with high priority, other
+    /// events with lower priority and the running clock silently
+    #[cfg(feature = "chat")]
+    #[arg(long, value_name = "URL", requires = "gotify_token")]
+    gotify: Option<Url>,
+
+    /// Token of the Gotify application the notifications are pushed as, also as @FILE or
+    /// cmd:COMMAND
+    #[cfg(feature = "chat")]
+    #[arg(long, requires = "gotify")]
+    gotify_token: Option<String>,
+
+    /// URL receiving a POST with the latest and the previous result whenever the score or
+    /// the state of the match changes (can be repeated)
+    #[arg(long)]
+    webhook: Vec<Url>,
+
+    /// Retries of a failed webhook delivery
+    #[arg(long, default_value_t = 3)]
+    webhook_retries: u32,
+
+    /// Timeout of one webhook delivery in seconds
+    #[arg(long, default_value_t = 10)]
+    webhook_timeout: u64,
+
+    /// Do not publish a result that cannot follow the previous one (score going down, minute
+    /// going backwards), as served briefly by stale markup, unless the next one repeats it
+    #[arg(long)]
+    drop_glitches: bool,
+
+    /// Shell command started once and fed with results, events and errors as JSON lines
+    /// on its standard input (can be repeated)
+    #[arg(long = "sink-exec")]
+    sink_exec: Vec<String>,
+
+    /// Shell command run for every match event with LIVESPORT_EVENT (started, goal_for,
+    /// goal_against, break, resumed, finished or statistic_changed), LIVESPORT_MY_TEAM,
+    /// LIVESPORT_OPPONENT_TEAM, LIVESPORT_MY_TEAM_SCORE, LIVESPORT_OPPONENT_TEAM_SCORE and
+    /// LIVESPORT_MINUTE set and the latest result as JSON on its standard input (can be
+    /// repeated)
+    #[arg(long)]
+    on_event: Vec<String>,
+
+    /// Show a desktop notification on goals and at full time
+    #[cfg(feature = "desktop")]
+    #[arg(long)]
+    notify_desktop: bool,
+
+    /// WebAssembly module fed with the same JSON messages as --sink-exec, sandboxed from
+    /// the host (can be repeated)
+    #[cfg(feature = "wasm")]
+    #[arg(long = "sink-wasm")]
+    sink_wasm: Vec<PathBuf>,
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    /// Search the site for a team and print the candidate team URLs
+    FindTeam {
+        /// Team name
+        name: String,
+
+        /// Livesport site used for the search
+        #[arg(long, default_value = "https://www.livesport.cz/")]
+        site: Url,
+
+        /// Only check that the team URL still belongs to the team
+        #[arg(long)]
+        verify: Option<Url>,
+    },
+    /// Scrape a playoff bracket page into rounds and series as JSON
+    Bracket {
+        /// Livescore URL of the bracket page
+        url: Url,
+
+        /// JSON output file, standard output by default
+        output: Option<PathBuf>,
+    },
+    /// Scrape a standings page into tables as JSON, one per group of a tournament
+    Standings {
+        /// Livescore URL of the standings page
+        url: Url,
+
+        /// JSON output file, standard output by default
+        output: Option<PathBuf>,
+    },
+    /// Import the finished matches of the season from the results page of the team into the
+    /// match archive, so that the history started mid-season has no gap
+    Backfill {
+        /// Livescore URL of the results page of the team
+        url: Url,
+
+        /// Team name as shown on the page
+        team_name: String,
+
+        /// Directory of the match archive (see --archive-dir)
+        archive_dir: PathBuf,
+
+        /// Import only the matches played on this day (YYYY-MM-DD) or later
+        #[arg(long)]
+        since: Option<NaiveDate>,
+    },
+    /// Scrape a team page repeatedly and print the latency percentiles of each phase: `load`
+    /// of the page, `render` of its first match row and the whole `scrape` (with its settle
+    /// pause) as the crawler does it
+    Bench {
+        /// Livescore URL of the team, or of a local server serving a saved team page
+        url: Url,
+        /// Team name
+        team_name: String,
+        /// Number of scrapes
+        #[arg(long, default_value_t = 10)]
+        runs: u64,
+        /// How the team page is read
+        #[arg(long, value_enum, default_value_t = Backend::WebDriver)]
+        backend: Backend,
+    },
+    /// List the final scores of the archived matches
+    History {
+        /// Directory of the match archive (see --archive-dir)
+        archive_dir: PathBuf,
+        /// Only the matches against an opponent whose name contains this text
+        #[arg(long)]
+        opponent: Option<String>,
+        /// Only the matches played on this day (YYYY-MM-DD) or later
+        #[arg(long)]
+        since: Option<NaiveDate>,
+        /// Only the matches played on this day (YYYY-MM-DD) or earlier
+        #[arg(long)]
+        until: Option<NaiveDate>,
+    },
+    /// Publish a recorded match again with its original (or scaled) timing
+    Replay {
+        /// Match archive file or directory with result snapshots
+        recording: PathBuf,
+
+        /// JSON output file
+        output: PathBuf,
+
+        /// Speed-up factor of the original timing
+        #[arg(long, default_value_t = 1.0)]
+        speed: f64,
+
+        // boxed, the sinks would make every command as large as this one
+        #[command(flatten)]
+        sinks: Box<SinkArgs>,
+    },
+    /// Print the shell completion script
+    #[cfg(feature = "completions")]
+    Completions { shell: clap_complete::Shell },
+    /// Print the man page
+    #[cfg(feature = "completions")]
+    Manpage,
+    /// Work with the configuration file
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommands,
+    },
 }
 
-fn start_driver() -> anyhow::Result<Child> {
+#[derive(Subcommand)]
+enum ConfigCommands {
+    /// Check the configuration file and print all problems with their line numbers
+    Validate {
+        /// TOML configuration file
+        path: PathBuf,
+    },
+}
+
+fn start_driver(port: u16) -> anyhow::Result<Child> {
     let driver = Command::new("chromedriver")
-        .arg(format!("--port={}", DRIVER_PORT))
+        .arg(format!("--port={port}"))
         .stderr(Stdio::null())
         .stdout(Stdio::null())
         .spawn()?;
@@ -66,222 +678,1070 @@ fn start_driver() -> anyhow::Result<Child> {
     Ok(driver)
 }
 
-const PERIOD_MINUTES: u64 = 20;
+struct Browser {
+    driver: Child,
+    client: Client,
+}
 
-async fn get_minute_of_game(row: &Element) -> anyhow::Result<GameTime> {
-    let event_parts = row.find_all(Locator::Css(".event__part--home")).await?;
-    let mut periods = 0;
-    for part in event_parts {
-        if part.text().await.is_ok_and(|text| !text.is_empty()) {
-            periods += 1;
-        }
+impl Browser {
+    /// Start chromedriver on the port and a session in it.
+    async fn start(port: u16, stealth: bool, network_feed: bool) -> anyhow::Result<Self> {
+        let driver = start_driver(port)?;
+        let client =
+            livesport_crawler::connect(&format!("http://localhost:{port}"), stealth, network_feed)
+                .await
+                .context("failed to connect to WebDriver")?;
+
+        Ok(Self { driver, client })
     }
-    assert!(periods >= 1);
-    let mut minute = PERIOD_MINUTES * (periods - 1);
 
-    let event_time_element = row.find(Locator::Css(".eventTime")).await;
-    if let Ok(event_time_element) = event_time_element {
-        minute += event_time_element.text().await.map_or(0, |text| {
-            text.strip_suffix('\'')
-                .unwrap_or(&text)
-                .parse()
-                .unwrap_or_default()
-        });
-        Ok(GameTime::Playing(minute))
-    } else {
-        // It must be break otherwise
-        minute += PERIOD_MINUTES;
-        Ok(GameTime::BreakAfter(minute))
+    async fn close(mut self) -> anyhow::Result<()> {
+        self.driver.kill().unwrap();
+        self.client.close().await?;
+        Ok(())
+    }
+}
+
+async fn find_team(
+    client: &mut Client,
+    name: &str,
+    site: &Url,
+    verify: Option<&Url>,
+) -> anyhow::Result<()> {
+    if let Some(url) = verify {
+        let heading = search::verify_team(client, url, name).await?;
+        println!("{url} belongs to {heading}");
+        return Ok(());
     }
+
+    let candidates = search::find_team(client, site, name).await?;
+    anyhow::ensure!(!candidates.is_empty(), "no team matches {name:?}");
+    for candidate in candidates {
+        println!("{}\t{}", candidate.name, candidate.url);
+    }
+    Ok(())
 }
 
-async fn get_latest_match_element(client: &mut Client) -> anyhow::Result<Option<Element>> {
-    for _ in 0..10 {
-        sleep(Duration::from_millis(200)).await;
-        let last_match_row = client
-            .find_all(Locator::Css(".event__match"))
-            .await?
-            .into_iter()
-            .next();
-        if last_match_row.is_some() {
-            return Ok(last_match_row);
+async fn write_bracket(
+    client: &mut Client,
+    url: &Url,
+    output: Option<&Path>,
+) -> anyhow::Result<()> {
+    let rounds = bracket::get_bracket(client, url).await?;
+    match output {
+        Some(output) => serde_json::to_writer_pretty(permissions::create(output)?, &rounds)?,
+        None => println!("{}", serde_json::to_string_pretty(&rounds)?),
+    }
+    Ok(())
+}
+
+async fn write_standings(
+    client: &mut Client,
+    url: &Url,
+    output: Option<&Path>,
+) -> anyhow::Result<()> {
+    let groups = standings::get_standings(client, url).await?;
+    match output {
+        Some(output) => serde_json::to_writer_pretty(permissions::create(output)?, &groups)?,
+        None => println!("{}", serde_json::to_string_pretty(&groups)?),
+    }
+    Ok(())
+}
+
+async fn backfill(
+    client: &mut Client,
+    url: &Url,
+    team_name: &str,
+    archive_dir: &Path,
+    since: Option<NaiveDate>,
+    timezone: Option<Tz>,
+) -> anyhow::Result<()> {
+    let results = results::get_results(client, url, team_name, since, timezone).await?;
+    let mut imported = 0;
+    for result in &results {
+        if let Some(path) = archive::import(archive_dir, result)? {
+            info!("imported {}", path.display());
+            imported += 1;
         }
-        debug!("sleeping in find_all for .event__match");
     }
+    info!(
+        "imported {imported} of {} finished matches, the others were archived already",
+        results.len()
+    );
+    Ok(())
+}
 
-    Ok(None)
+fn print_history(
+    archive_dir: &Path,
+    opponent: Option<&str>,
+    since: Option<NaiveDate>,
+    until: Option<NaiveDate>,
+) -> anyhow::Result<()> {
+    let opponent = opponent.map(str::to_lowercase);
+    for (day, archived) in archive::history(archive_dir)? {
+        if opponent
+            .as_ref()
+            .is_some_and(|opponent| !archived.opponent_team.to_lowercase().contains(opponent))
+            || since.is_some_and(|since| day < since)
+            || until.is_some_and(|until| day > until)
+        {
+            continue;
+        }
+        let (my_team_score, opponent_team_score) = archived.final_score;
+        println!(
+            "{}  {} {my_team_score}:{opponent_team_score} {}",
+            day, archived.my_team, archived.opponent_team
+        );
+    }
+    Ok(())
 }
 
-fn parse_datetime(value: &str) -> anyhow::Result<NaiveDateTime> {
-    let parse_time = |time: &str| -> anyhow::Result<_> {
-        let time_parts = time.split_once(':').context("time should have one colon")?;
-        NaiveTime::from_hms_opt(
-            time_parts.0.parse().context("hour cannot be parsed")?,
-            time_parts.1.parse().context("minute cannot be parsed")?,
-            0,
-        )
-        .context("cannot parse NaiveTime")
+async fn print_saved_page(
+    path: &Path,
+    team_name: Option<&str>,
+    timezone: Option<Tz>,
+) -> anyhow::Result<()> {
+    let path = fs::canonicalize(path)
+        .with_context(|| format!("cannot read saved page {}", path.display()))?;
+    let team_name = match team_name {
+        Some(team_name) => team_name.to_string(),
+        None => html::team_name(&fs::read_to_string(&path)?)
+            .context("the page has no team heading, pass the team name")?,
     };
+    let url = Url::from_file_path(&path)
+        .map_err(|_| anyhow::anyhow!("invalid path {}", path.display()))?;
+    let options = ScrapeOptions {
+        timezone,
+        ..ScrapeOptions::default()
+    };
+    let result = get_score(&HtmlSource, &url, &team_name, &options).await?;
+    println!("{}", serde_json::to_string_pretty(&result)?);
+    Ok(())
+}
 
-    let parse_date = |date: &str| -> anyhow::Result<_> {
-        let date_parts: Vec<_> = date.split('.').collect();
-        let day = date_parts.first().context("date: day part missing")?;
-        let month = date_parts.get(1).context("date: month part missing")?;
-        NaiveDate::from_ymd_opt(
-            Local::now().year(),
-            month.parse().context("month cannot be parsed")?,
-            day.parse().context("day cannot be parsed")?,
-        )
-        .context("cannot parse NaiveDate")
+/// Scrape the team page `runs` times and print the latencies of the phases.
+async fn bench(
+    url: &Url,
+    team_name: &str,
+    runs: u64,
+    backend: Backend,
+    timezone: Option<Tz>,
+) -> anyhow::Result<()> {
+    let options = ScrapeOptions {
+        timezone,
+        ..ScrapeOptions::default()
     };
+    let mut timings = bench::Timings::default();
+    match backend {
+        Backend::Http => {
+            let source = HttpSource::new()?;
+            for run in 1..=runs {
+                let started = Instant::now();
+                get_score(&source, url, team_name, &options)
+                    .await
+                    .with_context(|| format!("run {run} failed"))?;
+                timings.record("scrape", started.elapsed());
+            }
+        }
+        Backend::WebDriver => {
+            let started = Instant::now();
+            let browser = Browser::start(DRIVER_PORT, false, false).await?;
+            timings.record("session", started.elapsed());
+            let mut client = browser.client.clone();
+            let source = WebDriverSource::new(client.clone());
+            let result = async {
+                for run in 1..=runs {
+                    let started = Instant::now();
+                    budget::goto(&mut client, url.as_str()).await?;
+                    timings.record("load", started.elapsed());
+                    client
+                        .wait()
+                        .at_most(Duration::from_secs(30))
+                        .for_element(fantoccini::Locator::Css(".event__match"))
+                        .await
+                        .with_context(|| format!("run {run} shows no match row"))?;
+                    timings.record("render", started.elapsed());
 
-    if let Some((date, time)) = value.split_once(' ') {
-        Ok(NaiveDateTime::new(parse_date(date)?, parse_time(time)?))
-    } else {
-        Ok(NaiveDateTime::new(
-            Local::now().date_naive(),
-            parse_time(value)?,
-        ))
+                    let started = Instant::now();
+                    get_score(&source, url, team_name, &options)
+                        .await
+                        .with_context(|| format!("run {run} failed"))?;
+                    timings.record("scrape", started.elapsed());
+                }
+                anyhow::Ok(())
+            }
+            .await;
+            browser.close().await?;
+            result?;
+        }
     }
+    print!("{}", timings.report());
+    Ok(())
 }
 
-async fn get_score(client: &mut Client, url: &Url, team_name: &str) -> anyhow::Result<GameResult> {
-    client.goto(url.as_str()).await?;
-
-    // wait for a reasonable time before we inspect DOM
-    tokio::time::sleep(Duration::from_millis(500)).await;
-
-    let last_match_row = get_latest_match_element(client)
-        .await?
-        .ok_or(anyhow::anyhow!("could not find .event__match element"))?;
-
-    let home_team = last_match_row
-        .find(Locator::Css(".event__participant--home"))
-        .await?
-        .text()
-        .await?;
-
-    let away_team = last_match_row
-        .find(Locator::Css(".event__participant--away"))
-        .await?
-        .text()
-        .await?;
-
-    let home_score = last_match_row
-        .find(Locator::Css(".event__score--home"))
-        .await?
-        .text()
-        .await?
-        .parse()
-        .unwrap_or_default();
-
-    let away_score = last_match_row
-        .find(Locator::Css(".event__score--away"))
-        .await?
-        .text()
-        .await?
-        .parse()
-        .unwrap_or_default();
-
-    let last_match_class = last_match_row
-        .attr("class")
-        .await?
-        .ok_or(anyhow::anyhow!("class attribute should not be empty"))?;
-
-    let event_time_element = last_match_row.find(Locator::Css(".event__time")).await;
-    let event_time = if let Ok(event_time_element) = event_time_element {
-        let match_date_time = parse_datetime(&event_time_element.text().await?)?;
-        let now = Local::now().naive_local();
-        debug!("Match will be played: {match_date_time}");
-        if match_date_time < now {
-            Some((0, 0))
+/// Layout snapshot file of the team; each team gets its own when several are tracked,
+/// e.g. `layout.sparta-men.json`.
+fn layout_snapshot(path: &Path, team: &teams::Team, several_teams: bool) -> PathBuf {
+    if !several_teams {
+        return path.to_path_buf();
+    }
+    let key = team.key().replace(['/', ' '], "-");
+    match path.extension() {
+        Some(extension) => path.with_extension(format!("{key}.{}", extension.to_string_lossy())),
+        None => path.with_extension(key),
+    }
+}
+
+/// Per-team state of the main loop.
+struct Tracker {
+    team: teams::Team,
+    options: ScrapeOptions,
+    mirrors: domains::Mirrors,
+    pipeline: pipeline::Pipeline,
+    simulation: Option<simulation::Simulation>,
+    fixture_check: Option<upcoming::FixtureCheck>,
+    /// Time to the next scrape of the team
+    policy: Box<dyn poll::PollPolicy>,
+    delay: Duration,
+    latency: Option<latency::LatencyEstimator>,
+    /// Layout of the match row of the team, compared with its previous scrape only
+    layout: Arc<Mutex<layout::LayoutDetector>>,
+    /// Index of the browser session scraping the team
+    browser: usize,
+    /// Not scraped until resumed over the control API
+    paused: bool,
+}
+
+impl Tracker {
+    fn new(
+        team: teams::Team,
+        browser: usize,
+        cli: &Cli,
+        options: &ScrapeOptions,
+        layout_snapshot: Option<PathBuf>,
+    ) -> anyhow::Result<Self> {
+        let mut pipeline = pipeline::Pipeline::new(&team.output, &cli.sinks)?;
+        pipeline.observe(observer::EventLog);
+        let simulation = cli
+            .simulate
+            .then(|| simulation::Simulation::new(&team.name));
+        // a match page has no fixtures to check
+        let fixture_check = (cli.fixture_check_hours > 0
+            && simulation.is_none()
+            && !details::is_match_url(&team.url))
+        .then(|| upcoming::FixtureCheck::new(Duration::from_secs(cli.fixture_check_hours * 3600)));
+        let refresh = poll::Cron {
+            expressions: cli.schedule.clone(),
+            fallback: Duration::from_secs(cli.refresh),
+        };
+        let policy: Box<dyn poll::PollPolicy> = if cli.calendar {
+            Box::new(poll::Calendar::new(refresh))
         } else {
-            let delta = match_date_time - now;
-            Some((delta.num_hours() as u64, (delta.num_minutes() as u64) % 60))
+            Box::new(refresh)
+        };
+        Ok(Self {
+            mirrors: domains::Mirrors::new(&team.url, &cli.mirrors)?,
+            options: team.options.apply(options),
+            team,
+            pipeline,
+            simulation,
+            fixture_check,
+            policy,
+            delay: Duration::from_secs(cli.refresh),
+            latency: cli.measure_latency.then(latency::LatencyEstimator::default),
+            layout: Arc::new(Mutex::new(layout::LayoutDetector::new(layout_snapshot))),
+            browser,
+            paused: false,
+        })
+    }
+
+    async fn scrape(
+        &mut self,
+        cli: &Cli,
+        mut browser: Option<&mut Browser>,
+        http: Option<&HttpSource>,
+        metrics: Option<&metrics::Metrics>,
+        iteration: u64,
+    ) -> anyhow::Result<()> {
+        let team_name = &self.team.name;
+        let options = &self.options;
+        if !self.mirrors.available(Instant::now()) {
+            debug!("all pages of {team_name} are backing off after failures");
+            return Ok(());
+        }
+        let url = self.mirrors.current().clone();
+        if let (Some(fixture_check), Some(browser)) =
+            (self.fixture_check.as_mut(), browser.as_deref_mut())
+        {
+            if fixture_check.due() {
+                match upcoming::has_upcoming(&mut browser.client, &url, cli.stealth).await {
+                    Ok(true) => {}
+                    Ok(false) => {
+                        let message = format!(
+                            "{team_name} has no upcoming match on {url}, the URL is probably stale"
+                        );
+                        warn!("{message}");
+                        reporting::report_alert(&message, &url);
+                        self.pipeline.alert(&message);
+                    }
+                    Err(error) => warn!("could not check upcoming matches: {error:#}"),
+                }
+            }
         }
-    } else {
-        None
-    };
 
-    let game_time = if last_match_class.contains("event__match--live") {
-        get_minute_of_game(&last_match_row).await?
-    } else if last_match_class.contains("event__match--scheduled") {
-        GameTime::WillBePlayed(event_time)
-    } else {
-        GameTime::Played
-    };
+        let started = Instant::now();
+        let result = match (&mut self.simulation, http, browser.as_deref_mut()) {
+            (Some(simulation), _, _) => Ok(simulation.next_result()),
+            (None, Some(http), _) => get_score(http, &url, team_name, options).await,
+            (None, None, Some(browser)) => {
+                let source =
+                    WebDriverSource::with_layout(browser.client.clone(), self.layout.clone());
+                get_score(&source, &url, team_name, options).await
+            }
+            (None, None, None) => anyhow::bail!("no browser to scrape with"),
+        };
+        if let Some(metrics) = metrics {
+            metrics.record_scrape(&result, started.elapsed()).await;
+        }
+        self.delay = self.policy.next_delay(result.as_ref().ok(), Local::now());
 
-    client.goto("about:blank").await?;
+        match result {
+            Ok(latest_match) => {
+                info!("latest match = {latest_match:?}");
+                self.mirrors.succeed();
+                if let Some(latency) = self
+                    .latency
+                    .as_mut()
+                    .and_then(|latency| latency.sample(&latest_match))
+                {
+                    debug!("clock of {team_name} lags by {latency:?}");
+                    if let Some(metrics) = metrics {
+                        metrics.record_latency(latency).await;
+                    }
+                }
+                if let (Some(cookie_file), Some(browser)) =
+                    (&cli.cookie_file, browser.as_deref_mut())
+                {
+                    if let Err(error) =
+                        stealth::save_cookies(&mut browser.client, cookie_file).await
+                    {
+                        warn!("could not save cookies: {error}");
+                    }
+                }
+                match (options.context, browser.as_deref_mut()) {
+                    (true, Some(browser)) => {
+                        let context = context::get_context(
+                            &mut browser.client,
+                            &url,
+                            team_name,
+                            options,
+                            latest_match.clone(),
+                        )
+                        .await
+                        .unwrap_or_else(|error| {
+                            warn!("could not read the neighbouring matches: {error:#}");
+                            context::MatchContext {
+                                previous_match: None,
+                                current_or_next_match: latest_match,
+                                following_match: None,
+                            }
+                        });
+                        self.pipeline.publish_context(context).await?;
+                    }
+                    _ => self.pipeline.publish(latest_match).await?,
+                }
+            }
+            Err(error) => {
+                warn!("got error for {team_name}: {error:#}");
+                reporting::report_scrape_error(&error, &url, iteration);
+                self.pipeline.fail(&error);
+                let missing_bracket = error
+                    .chain()
+                    .any(|cause| cause.is::<bracket::MissingBracket>());
+                match (missing_bracket, browser) {
+                    (true, Some(browser)) => self.roll_over_season(cli, browser, &url).await,
+                    _ => {
+                        if self.mirrors.fail(Instant::now()) {
+                            info!("switching to mirror {}", self.mirrors.current());
+                        }
+                    }
+                }
+            }
+        }
 
-    let now = Local::now();
+        Ok(())
+    }
 
-    let latest_match = if home_team.starts_with(team_name) {
-        GameResult {
-            my_team: home_team,
-            my_team_score: home_score,
-            opponent_team: away_team,
-            opponent_team_score: away_score,
-            generated: now,
-            game_time,
-        }
-    } else {
-        GameResult {
-            my_team: away_team,
-            my_team_score: away_score,
-            opponent_team: home_team,
-            opponent_team_score: home_score,
-            generated: now,
-            game_time,
+    /// Follow the bracket to the new season the team page links to, in the configuration
+    /// file as well.
+    async fn roll_over_season(&mut self, cli: &Cli, browser: &mut Browser, team_url: &Url) {
+        let Some(stale) = self.options.bracket.clone() else {
+            return;
+        };
+        let url = match season::find_new_season(&mut browser.client, team_url, &stale).await {
+            Ok(Some(url)) => url,
+            Ok(None) => {
+                debug!("{team_url} links no new season of {stale}");
+                return;
+            }
+            Err(error) => {
+                warn!("could not look the new season of {stale} up: {error:#}");
+                return;
+            }
+        };
+        let message = format!("the bracket {stale} moved to {url} with the new season");
+        info!("{message}");
+        if let Some(path) = &cli.config {
+            if let Err(error) = config::replace_url(path, &stale, &url) {
+                warn!("could not update the configuration: {error:#}");
+            }
         }
-    };
+        self.options.bracket = Some(url);
+        self.pipeline.alert(&message);
+    }
+}
 
-    Ok(latest_match)
+/// Start the browser session of the given index, each has its own chromedriver.
+async fn start_browser(cli: &Cli, index: usize, url: &Url) -> anyhow::Result<Browser> {
+    let mut browser =
+        Browser::start(DRIVER_PORT + index as u16, cli.stealth, cli.network_feed).await?;
+    if let Some(cookie_file) = &cli.cookie_file {
+        stealth::load_cookies(&mut browser.client, cookie_file, url).await?;
+    }
+    Ok(browser)
 }
 
-// let's set up the sequence of steps we want the browser to take
-#[tokio::main]
-async fn main() -> anyhow::Result<()> {
-    tracing_subscriber::fmt::init();
+async fn crawl(
+    cli: &Cli,
+    browsers: &mut [Option<Browser>],
+    shutdown: CancellationToken,
+) -> anyhow::Result<()> {
+    let teams = cli.teams()?;
+    anyhow::ensure!(
+        !teams.is_empty(),
+        "URL, team name and output file (or --team) are required"
+    );
 
-    let cli = Cli::parse();
+    let metrics = cli
+        .metrics
+        .as_ref()
+        .map(|url| metrics::Metrics::new(url, &cli.metrics_prefix))
+        .transpose()?;
+    let mut profiler = cli
+        .profile_interval
+        .map(|seconds| profile::Profiler::new(Duration::from_secs(seconds)))
+        .transpose()?;
+    let options = ScrapeOptions {
+        details: cli.details,
+        players: cli.players.clone(),
+        opponent_form: cli.opponent_form,
+        stealth: cli.stealth,
+        timezone: cli.timezone,
+        context: cli.context,
+        best_of: cli.best_of,
+        bracket: cli.bracket.clone(),
+        network_feed: cli.network_feed,
+    };
+    // bound as root, if at all, everything else runs unprivileged
+    #[cfg(feature = "server")]
+    let listener = match cli.serve {
+        Some(address) => Some(
+            TcpListener::bind(address)
+                .await
+                .with_context(|| format!("cannot listen on {address}"))?,
+        ),
+        None => None,
+    };
+    privileges::drop_to(cli.user.as_deref(), cli.group.as_deref())?;
 
-    let mut driver = start_driver()?;
+    let ticker = cli.ticker.then(ticker::Ticker::default);
+    // woken by the control API to apply its commands without waiting for the next scrape
+    let wake = Arc::new(Notify::new());
+    #[cfg(feature = "server")]
+    let mut scoreboard = listener.as_ref().map(|_| server::Scoreboard::default());
+    #[cfg(feature = "server")]
+    let mut commands = match (&mut scoreboard, &cli.admin_token) {
+        (Some(scoreboard), Some(token)) => {
+            let token = secrets::resolve(token).context("cannot read the admin token")?;
+            anyhow::ensure!(!token.is_empty(), "the admin token is empty");
+            Some(scoreboard.control(token, wake.clone()))
+        }
+        _ => None,
+    };
+    let standby = cli
+        .standby
+        .as_ref()
+        .map(|url| standby::Standby::watch(url, Duration::from_secs(cli.standby_timeout)))
+        .transpose()?;
+    if standby.is_some() {
+        info!("standing by until the primary stops beating");
+    }
 
-    let cap: Capabilities =
-        serde_json::from_str(r#"{"goog:chromeOptions":{"args":["--headless"]}}"#).unwrap();
+    let browsers_count = usize::from(cli.browsers);
+    let track = |team: teams::Team, index: usize, several_teams: bool| {
+        let browser = team.browser.unwrap_or(index % browsers_count);
+        anyhow::ensure!(
+            browser < browsers_count,
+            "{} is assigned to browser {browser}, only {browsers_count} of them run",
+            team.name
+        );
+        let snapshot = cli
+            .layout_snapshot
+            .as_deref()
+            .map(|path| layout_snapshot(path, &team, several_teams));
+        let mut tracker = Tracker::new(team, browser, cli, &options, snapshot)?;
+        if let Some(ticker) = &ticker {
+            tracker.pipeline.observe(ticker.segment());
+        }
+        #[cfg(feature = "server")]
+        if let Some(scoreboard) = &scoreboard {
+            if let Some(token) = &tracker.team.token {
+                let token = secrets::resolve(token)
+                    .with_context(|| format!("cannot read the token of {}", tracker.team.name))?;
+                scoreboard.grant(&tracker.team.name, token);
+            }
+            tracker.pipeline.serve(scoreboard.add(&tracker.team.name));
+            tracker
+                .pipeline
+                .observe(scoreboard.pusher(&tracker.team.name));
+        }
+        if let Some(standby) = &standby {
+            tracker.pipeline.stand_by(standby.clone());
+        }
+        Ok(tracker)
+    };
+    let several_teams = teams.len() > 1;
+    let mut trackers = teams
+        .into_iter()
+        .enumerate()
+        .map(|(index, team)| track(team, index, several_teams))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    #[cfg(feature = "server")]
+    if let (Some(listener), Some(scoreboard)) = (listener, &scoreboard) {
+        let address = server::serve(listener, scoreboard.clone())?;
+        info!("serving the latest results on http://{address}/score");
+    }
+    let heartbeat = cli
+        .heartbeat
+        .as_ref()
+        .map(standby::Heartbeat::new)
+        .transpose()?;
 
-    let mut c = ClientBuilder::rustls()?
-        .capabilities(cap)
-        .connect(&format!("http://localhost:{DRIVER_PORT}"))
-        .await
-        .expect("failed to connect to WebDriver");
+    let http = match cli.backend {
+        Backend::Http => {
+            anyhow::ensure!(
+                trackers
+                    .iter()
+                    .all(|tracker| tracker.options.page_loads() == 1),
+                "the HTTP backend reads only the match row, drop the options needing other pages"
+            );
+            Some(HttpSource::new()?)
+        }
+        Backend::WebDriver => None,
+    };
+    let mut trigger = cli.trigger_file.as_deref().map(trigger::TriggerFile::new);
+    let mut outage_since = vec![None; browsers.len()];
 
-    loop {
-        match get_score(&mut c, &cli.url, &cli.team_name).await {
-            Ok(latest_match) => {
-                info!("latest match = {latest_match:?}");
-                serde_json::to_writer_pretty(File::create(cli.output.clone())?, &latest_match)?;
+    // the delay of the teams without the calendar, anything longer is an idle period
+    let mut refresh_policy = poll::Cron {
+        expressions: cli.schedule.clone(),
+        fallback: Duration::from_secs(cli.refresh),
+    };
+
+    for iteration in 1u64.. {
+        #[cfg(feature = "server")]
+        if let Some(commands) = &mut commands {
+            while let Ok((command, reply)) = commands.try_recv() {
+                let outcome = apply_command(
+                    command,
+                    &mut trackers,
+                    // a team added later does not share the layout snapshot of a single team
+                    |team, index| track(team, index, true),
+                    scoreboard.as_ref(),
+                    cli.config.as_deref(),
+                );
+                // the client may have stopped waiting
+                let _ = reply.send(outcome);
             }
-            Err(error) => {
-                warn!("got error: {error}");
+        }
+        for (index, browser) in browsers.iter_mut().enumerate() {
+            if let Some(running) = browser.as_mut() {
+                if let Some(status) = running.driver.try_wait()? {
+                    reporting::report_driver_exit(status);
+                    warn!("chromedriver {index} exited unexpectedly: {status}, restarting it");
+                    outage_since[index].get_or_insert_with(Local::now);
+                    *browser = None;
+                }
+            }
+            // a browser without any team is not started at all, or closed with its last team
+            let Some(first) = trackers.iter().find(|tracker| tracker.browser == index) else {
+                if let Some(browser) = browser.take() {
+                    browser.close().await?;
+                }
+                continue;
+            };
+            if browser.is_none() && !cli.simulate && http.is_none() {
+                match start_browser(cli, index, &first.team.url).await {
+                    Ok(started) => *browser = Some(started),
+                    Err(error) => {
+                        warn!("could not start browser {index}: {error:#}");
+                        outage_since[index].get_or_insert_with(Local::now);
+                    }
+                }
+            }
+            if browser.is_some() || cli.simulate || http.is_some() {
+                outage_since[index] = None;
+            }
+        }
+
+        let max_outage = chrono::Duration::minutes(cli.max_outage as i64);
+        let expired: Vec<_> = trackers
+            .iter()
+            .map(|tracker| outage_since[tracker.browser])
+            .map(|since| since.filter(|since| Local::now() - *since > max_outage))
+            .collect();
+        if !expired.is_empty() && expired.iter().all(Option::is_some) {
+            let since = expired
+                .into_iter()
+                .flatten()
+                .max()
+                .unwrap_or_else(Local::now);
+            anyhow::bail!("all browsers are down since {since}");
+        }
+        let scrape = async {
+            for tracker in trackers.iter_mut().filter(|tracker| !tracker.paused) {
+                // only the teams of a browser that is down are served stale
+                match outage_since[tracker.browser] {
+                    Some(since) => {
+                        tracker.pipeline.publish_stale(since)?;
+                        tracker.delay = tracker.policy.next_delay(None, Local::now());
+                    }
+                    None => {
+                        tracker
+                            .scrape(
+                                cli,
+                                browsers[tracker.browser].as_mut(),
+                                http.as_ref(),
+                                metrics.as_ref(),
+                                iteration,
+                            )
+                            .await?
+                    }
+                }
+            }
+            anyhow::Ok(())
+        };
+        // a page load can take long, do not let it hold up the shutdown
+        tokio::select! {
+            _ = shutdown.cancelled() => {
+                info!("scrape interrupted, exitting the main loop");
+                break;
+            },
+            result = scrape => result?,
+        }
+        // a standby that took over beats in place of the primary
+        if let Some(heartbeat) = heartbeat
+            .as_ref()
+            .filter(|_| outage_since.iter().all(Option::is_none))
+            .filter(|_| standby.as_ref().is_none_or(|standby| standby.is_active()))
+        {
+            heartbeat.beat();
+        }
+
+        if let Some(used) = bandwidth::used() {
+            debug!("{} kB transferred this month", used / 1000);
+            if let Some(metrics) = &metrics {
+                metrics.record_bandwidth(used).await;
             }
         }
+        if let Err(error) = bandwidth::save() {
+            warn!("could not save the bandwidth usage: {error:#}");
+        }
+        if let Some(profiler) = &mut profiler {
+            let drivers: Vec<_> = browsers
+                .iter()
+                .flatten()
+                .map(|browser| browser.driver.id())
+                .collect();
+            let (crawler, browser) = profiler.sample(&drivers);
+            if let Some(metrics) = &metrics {
+                metrics.record_usage(crawler, browser).await;
+            }
+        }
+
+        if cli.ephemeral_browser {
+            for browser in browsers.iter_mut() {
+                if let Some(browser) = browser.take() {
+                    browser.close().await?;
+                }
+            }
+        }
+
+        // the team due the soonest decides
+        let delay = trackers
+            .iter()
+            .filter(|tracker| !tracker.paused)
+            .map(|tracker| tracker.delay)
+            .min()
+            .unwrap_or(Duration::from_secs(cli.refresh));
+        let delay = if cli.calendar && delay > refresh_policy.next_delay(None, Local::now()) {
+            info!(
+                "no match around, idling for {} minutes without a browser",
+                delay.as_secs() / 60
+            );
+            for browser in browsers.iter_mut() {
+                if let Some(browser) = browser.take() {
+                    browser.close().await?;
+                }
+            }
+            delay
+        } else {
+            let delay = if cli.stealth {
+                stealth::jitter(delay)
+            } else {
+                delay
+            };
+            let page_loads = trackers
+                .iter()
+                .map(|tracker| tracker.options.page_loads())
+                .sum();
+            bandwidth::throttle(budget::throttle(delay, page_loads))
+        };
 
         tokio::select! {
-            _ = signal::ctrl_c() => {
+            _ = shutdown.cancelled() => {
                 info!("exitting the main loop");
                 break;
             },
-            _ = tokio::time::sleep(Duration::from_secs(cli.refresh)) => {
+            _ = async {
+                match &mut trigger {
+                    Some(trigger) => trigger.wait().await,
+                    None => tokio::time::sleep(delay).await,
+                }
+            } => {
             }
+            _ = wake.notified() => {}
         }
     }
 
-    driver.kill().unwrap();
+    if let Some(profiler) = &profiler {
+        info!("{}", profiler.report());
+    }
+    Ok(())
+}
 
-    c.close().await?;
+/// Apply a command of the control API to the tracked teams, saving the added and removed ones
+/// to the config file.
+#[cfg(feature = "server")]
+fn apply_command(
+    command: server::Command,
+    trackers: &mut Vec<Tracker>,
+    track: impl Fn(teams::Team, usize) -> anyhow::Result<Tracker>,
+    scoreboard: Option<&server::Scoreboard>,
+    config: Option<&Path>,
+) -> server::Outcome {
+    match command {
+        server::Command::AddTeam(team) => {
+            if let Some(tracked) = trackers.iter().find(|tracker| {
+                tracker.team.key() == team.key()
+                    || tracker.team.name.eq_ignore_ascii_case(&team.name)
+                    || tracker.team.output == team.output
+            }) {
+                return server::Outcome::Rejected(format!(
+                    "{} is tracked already with the same name or output",
+                    tracked.team.key()
+                ));
+            }
+            let tracker = match track(*team, trackers.len()) {
+                Ok(tracker) => tracker,
+                Err(error) => return server::Outcome::Rejected(format!("{error:#}")),
+            };
+            let key = tracker.team.key();
+            match config {
+                Some(config) => {
+                    if let Err(error) = config::add_team(config, &tracker.team) {
+                        if let Some(scoreboard) = scoreboard {
+                            scoreboard.remove(&tracker.team.name);
+                        }
+                        return server::Outcome::Failed(format!("{error:#}"));
+                    }
+                }
+                None => warn!("{key} is tracked until the exit only, there is no --config"),
+            }
+            info!("started tracking {key}");
+            trackers.push(tracker);
+            server::Outcome::Done(serde_json::json!({ "team": key }))
+        }
+        server::Command::RemoveTeam(key) => {
+            let Some(index) = trackers
+                .iter()
+                .position(|tracker| tracker.team.key() == key)
+            else {
+                return server::Outcome::NotFound;
+            };
+            match config.map(|config| config::remove_team(config, &trackers[index].team)) {
+                Some(Ok(true)) => {}
+                Some(Ok(false)) | None => {
+                    warn!("{key} is not in the config file, it is tracked again after a restart")
+                }
+                Some(Err(error)) => return server::Outcome::Failed(format!("{error:#}")),
+            }
+            let tracker = trackers.remove(index);
+            if let Some(scoreboard) = scoreboard {
+                scoreboard.remove(&tracker.team.name);
+            }
+            info!("stopped tracking {key}");
+            server::Outcome::Done(serde_json::json!({ "team": key }))
+        }
+        server::Command::Teams => server::Outcome::Done(
+            trackers
+                .iter()
+                .map(|tracker| {
+                    serde_json::json!({
+                        "team": tracker.team.key(),
+                        "name": tracker.team.name,
+                        "output": tracker.team.output,
+                        "paused": tracker.paused,
+                        "result": scoreboard.and_then(|scoreboard| scoreboard.result(&tracker.team.name)),
+                    })
+                })
+                .collect(),
+        ),
+        server::Command::Pause(key)
+        | server::Command::Resume(key)
+        | server::Command::Refresh(key)
+        | server::Command::IssueToken(key)
+            if !trackers.iter().any(|tracker| tracker.team.key() == key) =>
+        {
+            server::Outcome::NotFound
+        }
+        server::Command::Pause(key) => {
+            for tracker in trackers.iter_mut().filter(|tracker| tracker.team.key() == key) {
+                tracker.paused = true;
+            }
+            info!("paused {key}, serving its last result");
+            server::Outcome::Done(serde_json::json!({ "team": key, "paused": true }))
+        }
+        server::Command::Resume(key) => {
+            for tracker in trackers.iter_mut().filter(|tracker| tracker.team.key() == key) {
+                tracker.paused = false;
+            }
+            info!("resumed {key}");
+            server::Outcome::Done(serde_json::json!({ "team": key, "paused": false }))
+        }
+        // every team is scraped once the loop is woken up
+        server::Command::Refresh(key) => {
+            if trackers
+                .iter()
+                .any(|tracker| tracker.team.key() == key && tracker.paused)
+            {
+                return server::Outcome::Rejected(format!("{key} is paused, resume it first"));
+            }
+            server::Outcome::Done(serde_json::json!({ "team": key }))
+        }
+        server::Command::IssueToken(key) => {
+            let Some(tracker) = trackers
+                .iter_mut()
+                .find(|tracker| tracker.team.key() == key)
+            else {
+                return server::Outcome::NotFound;
+            };
+            let token: String = rand::thread_rng()
+                .sample_iter(&rand::distributions::Alphanumeric)
+                .take(32)
+                .map(char::from)
+                .collect();
+            match config.map(|config| config::set_team_token(config, &tracker.team, &token)) {
+                Some(Ok(true)) => {}
+                Some(Ok(false)) | None => {
+                    warn!("the token of {key} is not saved, it is valid until the exit only")
+                }
+                Some(Err(error)) => return server::Outcome::Failed(format!("{error:#}")),
+            }
+            if let Some(scoreboard) = scoreboard {
+                scoreboard.grant(&tracker.team.name, token.clone());
+            }
+            tracker.team.token = Some(token.clone());
+            info!("issued a new read token of {key}");
+            server::Outcome::Done(serde_json::json!({ "team": key, "token": token }))
+        }
+    }
+}
 
-    Ok(())
+/// Wait for Ctrl-C or, on Unix, SIGTERM.
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    if let Ok(mut terminate) = signal::unix::signal(signal::unix::SignalKind::terminate()) {
+        tokio::select! {
+            _ = signal::ctrl_c() => {},
+            _ = terminate.recv() => {},
+        }
+        return;
+    }
+    if let Err(error) = signal::ctrl_c().await {
+        warn!("cannot listen for Ctrl-C: {error}");
+        std::future::pending::<()>().await;
+    }
+}
+
+// let's set up the sequence of steps we want the browser to take
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let matches = Cli::command().get_matches();
+    let mut cli = Cli::from_arg_matches(&matches).unwrap_or_else(|error| error.exit());
+    match &cli.command {
+        #[cfg(feature = "completions")]
+        Some(Commands::Completions { shell }) => {
+            let mut command = Cli::command();
+            let name = command.get_name().to_string();
+            clap_complete::generate(*shell, &mut command, name, &mut std::io::stdout());
+            return Ok(());
+        }
+        #[cfg(feature = "completions")]
+        Some(Commands::Manpage) => {
+            clap_mangen::Man::new(Cli::command()).render(&mut std::io::stdout())?;
+            return Ok(());
+        }
+        Some(Commands::Config {
+            command: ConfigCommands::Validate { path },
+        }) => return config::report(path),
+        _ => {}
+    }
+    if let Some(path) = cli.config.clone() {
+        config::Config::load(&path)?.apply(&mut cli, &matches);
+    }
+    if cli.ticker {
+        tracing_subscriber::fmt()
+            .with_writer(std::io::stderr)
+            .init();
+    } else {
+        tracing_subscriber::fmt::init();
+    }
+    if let Some(umask) = cli.umask {
+        privileges::set_umask(umask);
+    }
+    permissions::init(cli.output_mode, cli.dir_mode, cli.output_owner.as_deref())?;
+
+    if let Some(path) = &cli.from_html {
+        return print_saved_page(path, cli.team_name.as_deref(), cli.timezone).await;
+    }
+
+    let sentry_dsn = cli
+        .sentry_dsn
+        .as_deref()
+        .map(secrets::resolve)
+        .transpose()
+        .context("cannot resolve the Sentry DSN")?;
+    let _sentry = reporting::init(sentry_dsn.as_deref())?;
+    if let Some(max_page_loads) = cli.max_page_loads {
+        budget::init(max_page_loads);
+    }
+    bandwidth::init(
+        cli.bandwidth_cap.map(|megabytes| megabytes * 1_000_000),
+        Duration::from_secs(cli.capped_refresh),
+        cli.sinks
+            .state_dir
+            .as_ref()
+            .map(|state_dir| state_dir.join("bandwidth.json"))
+            .as_deref(),
+    );
+    let shutdown = CancellationToken::new();
+    tokio::spawn({
+        let shutdown = shutdown.clone();
+        async move {
+            shutdown_signal().await;
+            shutdown.cancel();
+        }
+    });
+    // the main loop starts the browser itself when it needs one
+    let mut browsers: Vec<Option<Browser>> = if matches!(
+        cli.command,
+        Some(
+            Commands::FindTeam { .. }
+                | Commands::Bracket { .. }
+                | Commands::Standings { .. }
+                | Commands::Backfill { .. }
+        )
+    ) {
+        vec![Some(Browser::start(DRIVER_PORT, cli.stealth, false).await?)]
+    } else {
+        (0..cli.browsers).map(|_| None).collect()
+    };
+
+    let result = match (&cli.command, browsers.first_mut().and_then(Option::as_mut)) {
+        (Some(Commands::FindTeam { name, site, verify }), Some(browser)) => {
+            find_team(&mut browser.client, name, site, verify.as_ref()).await
+        }
+        (Some(Commands::Bracket { url, output }), Some(browser)) => {
+            write_bracket(&mut browser.client, url, output.as_deref()).await
+        }
+        (Some(Commands::Standings { url, output }), Some(browser)) => {
+            write_standings(&mut browser.client, url, output.as_deref()).await
+        }
+        (
+            Some(Commands::Backfill {
+                url,
+                team_name,
+                archive_dir,
+                since,
+            }),
+            Some(browser),
+        ) => {
+            backfill(
+                &mut browser.client,
+                url,
+                team_name,
+                archive_dir,
+                *since,
+                cli.timezone,
+            )
+            .await
+        }
+        (
+            Some(Commands::Bench {
+                url,
+                team_name,
+                runs,
+                backend,
+            }),
+            _,
+        ) => bench(url, team_name, *runs, *backend, cli.timezone).await,
+        (
+            Some(Commands::History {
+                archive_dir,
+                opponent,
+                since,
+                until,
+            }),
+            _,
+        ) => print_history(archive_dir, opponent.as_deref(), *since, *until),
+        (
+            Some(Commands::Replay {
+                recording,
+                output,
+                speed,
+                sinks,
+            }),
+            _,
+        ) => {
+            let mut pipeline = pipeline::Pipeline::new(output, sinks)?;
+            replay::replay(replay::load(recording)?, &mut pipeline, *speed, &shutdown).await
+        }
+        (Some(_), _) => Err(anyhow::anyhow!(
+            "the browser of the subcommand did not start"
+        )),
+        (None, _) => crawl(&cli, &mut browsers, shutdown.clone()).await,
+    };
+
+    for browser in browsers.into_iter().flatten() {
+        browser.close().await?;
+    }
+    if cli.ticker {
+        // leave the last score line on the terminal
+        println!();
+    }
+
+    result
 }
 
 #[cfg(test)]
@@ -289,21 +1749,22 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_parse_datetime() {
-        let today = Local::now();
-
-        assert_eq!(
-            parse_datetime("07.09. 18:00").unwrap().to_string(),
-            format!("{}-09-07 18:00:00", today.year())
-        );
+    fn test_tracker_for_match_url() {
+        let output = std::env::temp_dir().join("livesport-crawler-test-match.json");
+        let cli = Cli::parse_from([
+            "livesport-crawler",
+            "--mirror",
+            "www.flashscore.com",
+            "https://www.livesport.cz/zapas/IVi1nt8B/",
+            "Sparta Praha",
+            output.to_str().unwrap(),
+        ]);
+        let team = cli.teams().unwrap().remove(0);
+        let tracker = Tracker::new(team, 0, &cli, &ScrapeOptions::default(), None).unwrap();
         assert_eq!(
-            parse_datetime("18:00").unwrap().to_string(),
-            format!(
-                "{}-{:02}-{:02} 18:00:00",
-                today.year(),
-                today.month(),
-                today.day()
-            )
+            tracker.mirrors.current().as_str(),
+            "https://www.livesport.cz/zapas/IVi1nt8B/"
         );
+        assert!(tracker.fixture_check.is_none());
     }
 }
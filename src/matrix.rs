@@ -0,0 +1,154 @@
+//! Score updates sent to a Matrix room through the client-server API.
+
+use anyhow::Context;
+use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION};
+use reqwest::Method;
+use serde::Serialize;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use url::Url;
+
+use crate::events::{TimelineEvent, TimelineKind};
+use crate::observer::Observer;
+use crate::webhook::Poster;
+use crate::GameResult;
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+const RETRIES: u32 = 1;
+
+#[derive(Serialize)]
+struct RoomMessage<'a> {
+    msgtype: &'a str,
+    body: &'a str,
+}
+
+pub struct MatrixRoom {
+    /// Endpoint of the messages of the room, completed by the transaction id
+    send_url: Url,
+    /// Unique prefix of the transaction ids of this run, the homeserver drops a repeated id
+    session: u128,
+    sent: u64,
+    poster: Poster,
+}
+
+impl MatrixRoom {
+    pub fn new(homeserver: &Url, access_token: &str, room_id: &str) -> anyhow::Result<Self> {
+        let mut send_url = homeserver.clone();
+        send_url
+            .path_segments_mut()
+            .map_err(|_| anyhow::anyhow!("{homeserver} cannot be a Matrix homeserver"))?
+            .pop_if_empty()
+            .extend(["_matrix", "client", "v3", "rooms", room_id])
+            .extend(["send", "m.room.message"]);
+
+        let mut headers = HeaderMap::new();
+        let mut value = HeaderValue::from_str(&format!("Bearer {access_token}"))
+            .context("invalid Matrix access token")?;
+        value.set_sensitive(true);
+        headers.insert(AUTHORIZATION, value);
+        Ok(Self {
+            poster: Poster::with_method(
+                &format!("Matrix room {room_id}"),
+                send_url.as_str(),
+                Method::PUT,
+                headers,
+                RETRIES,
+                TIMEOUT,
+            )?,
+            send_url,
+            session: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis(),
+            sent: 0,
+        })
+    }
+}
+
+/// Text of the update, `None` for the events not worth a message.
+fn message(event: &TimelineEvent, latest: &GameResult) -> Option<String> {
+    let score = format!(
+        "{} {}:{} {}",
+        latest.my_team, event.my_team_score, event.opponent_team_score, latest.opponent_team
+    );
+    let minute = event
+        .minute
+        .map(|minute| format!(" ({minute}')"))
+        .unwrap_or_default();
+    match event.kind {
+        TimelineKind::Started => Some(format!("Kick-off: {score}")),
+        TimelineKind::GoalFor | TimelineKind::GoalAgainst => Some(format!("Goal! {score}{minute}")),
+        TimelineKind::Break => Some(format!("Break: {score}{minute}")),
+        TimelineKind::Finished => Some(format!("Final result: {score}")),
+        TimelineKind::PlayerGoal => event
+            .goal
+            .as_ref()
+            .map(|goal| format!("{} scored! {score} ({})", goal.scorer, goal.minute)),
+        TimelineKind::Resumed | TimelineKind::StatisticChanged => None,
+    }
+}
+
+impl Observer for MatrixRoom {
+    fn announces(&self) -> bool {
+        true
+    }
+
+    fn on_event(&mut self, event: &TimelineEvent, latest: &GameResult) {
+        if let Some(body) = message(event, latest) {
+            self.sent += 1;
+            let mut url = self.send_url.clone();
+            url.path_segments_mut()
+                .expect("checked in new")
+                .push(&format!("livesport-{}-{}", self.session, self.sent));
+            self.poster.send_to(
+                url.as_str(),
+                &RoomMessage {
+                    msgtype: "m.text",
+                    body: &body,
+                },
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events;
+    use crate::simulation::sample;
+    use crate::GameTime;
+
+    #[tokio::test]
+    async fn test_new() {
+        let room = MatrixRoom::new(
+            &Url::parse("https://matrix.example.org/").unwrap(),
+            "token",
+            "!room:example.org",
+        )
+        .unwrap();
+        assert_eq!(
+            room.send_url.as_str(),
+            "https://matrix.example.org/_matrix/client/v3/rooms/!room:example.org/send/m.room.message"
+        );
+    }
+
+    #[test]
+    fn test_message() {
+        let previous = sample(GameTime::Playing(18), 0, 0);
+        let latest = GameResult {
+            opponent_team_score: 1,
+            game_time: GameTime::BreakAfter(20),
+            ..previous.clone()
+        };
+        let messages: Vec<_> = events::between(&previous, &latest)
+            .iter()
+            .filter_map(|event| message(event, &latest))
+            .collect();
+        assert_eq!(
+            messages,
+            [
+                "Goal! Sparta Praha 0:1 Kometa Brno (20')",
+                "Break: Sparta Praha 0:1 Kometa Brno (20')"
+            ]
+        );
+    }
+}
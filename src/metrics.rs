@@ -0,0 +1,184 @@
+use anyhow::Context;
+use chrono::Local;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::net::{TcpStream, UdpSocket};
+use tokio::sync::OnceCell;
+use tracing::warn;
+use url::Url;
+
+use crate::profile::Usage;
+use crate::GameResult;
+
+enum Protocol {
+    Statsd,
+    Graphite,
+}
+
+enum Sample<'a> {
+    Counter(&'a str, u64),
+    Timing(&'a str, Duration),
+    Gauge(&'a str, u64),
+    /// Gauge of one of the tracked teams, tagged with its name.
+    TeamGauge(&'a str, &'a str, u64),
+}
+
+/// Emitter of StatsD (UDP) or Graphite plaintext (TCP) metrics.
+pub struct Metrics {
+    protocol: Protocol,
+    address: String,
+    prefix: String,
+    /// StatsD socket, bound on the first send and reused afterwards.
+    socket: OnceCell<UdpSocket>,
+}
+
+impl Metrics {
+    pub fn new(url: &Url, prefix: &str) -> anyhow::Result<Self> {
+        let protocol = match url.scheme() {
+            "statsd" => Protocol::Statsd,
+            "graphite" => Protocol::Graphite,
+            scheme => anyhow::bail!("unsupported metrics scheme: {scheme}"),
+        };
+        let host = url.host_str().context("metrics URL must contain a host")?;
+        let port = url.port().unwrap_or(match protocol {
+            Protocol::Statsd => 8125,
+            Protocol::Graphite => 2003,
+        });
+
+        Ok(Self {
+            protocol,
+            address: format!("{host}:{port}"),
+            prefix: prefix.to_string(),
+            socket: OnceCell::new(),
+        })
+    }
+
+    pub async fn record_scrape(&self, result: &anyhow::Result<GameResult>, elapsed: Duration) {
+        let mut samples = vec![Sample::Timing("scrape.duration", elapsed)];
+        match result {
+            Ok(game) => {
+                samples.push(Sample::Counter("scrape.success", 1));
+                samples.push(Sample::TeamGauge(
+                    "score.my_team",
+                    &game.my_team,
+                    game.my_team_score,
+                ));
+                samples.push(Sample::TeamGauge(
+                    "score.opponent_team",
+                    &game.my_team,
+                    game.opponent_team_score,
+                ));
+            }
+            Err(_) => samples.push(Sample::Counter("scrape.failure", 1)),
+        }
+
+        if let Err(error) = self.send(&samples).await {
+            warn!("could not send metrics to {}: {error}", self.address);
+        }
+    }
+
+    /// Staleness of the scraped match clock.
+    pub async fn record_latency(&self, latency: Duration) {
+        if let Err(error) = self.send(&[Sample::Timing("clock.latency", latency)]).await {
+            warn!("could not send metrics to {}: {error}", self.address);
+        }
+    }
+
+    /// Bytes transferred by the page loads this month.
+    pub async fn record_bandwidth(&self, bytes: u64) {
+        if let Err(error) = self.send(&[Sample::Gauge("bandwidth.bytes", bytes)]).await {
+            warn!("could not send metrics to {}: {error}", self.address);
+        }
+    }
+
+    pub async fn record_usage(&self, crawler: Usage, browser: Usage) {
+        let samples = [
+            Sample::Gauge("usage.crawler.cpu", crawler.cpu.round() as u64),
+            Sample::Gauge("usage.crawler.rss", crawler.rss),
+            Sample::Gauge("usage.browser.cpu", browser.cpu.round() as u64),
+            Sample::Gauge("usage.browser.rss", browser.rss),
+        ];
+        if let Err(error) = self.send(&samples).await {
+            warn!("could not send metrics to {}: {error}", self.address);
+        }
+    }
+
+    async fn send(&self, samples: &[Sample<'_>]) -> anyhow::Result<()> {
+        let payload = samples
+            .iter()
+            .map(|sample| self.format(sample))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        match self.protocol {
+            Protocol::Statsd => {
+                let socket = self
+                    .socket
+                    .get_or_try_init(|| UdpSocket::bind("0.0.0.0:0"))
+                    .await?;
+                socket.send_to(payload.as_bytes(), &self.address).await?;
+            }
+            Protocol::Graphite => {
+                let mut stream = TcpStream::connect(&self.address).await?;
+                stream.write_all(payload.as_bytes()).await?;
+                stream.write_all(b"\n").await?;
+            }
+        }
+        Ok(())
+    }
+
+    fn format(&self, sample: &Sample) -> String {
+        let prefix = &self.prefix;
+        match (&self.protocol, sample) {
+            (Protocol::Statsd, Sample::Counter(name, value)) => {
+                format!("{prefix}.{name}:{value}|c")
+            }
+            (Protocol::Statsd, Sample::Timing(name, value)) => {
+                format!("{prefix}.{name}:{}|ms", value.as_millis())
+            }
+            (Protocol::Statsd, Sample::Gauge(name, value)) => format!("{prefix}.{name}:{value}|g"),
+            (Protocol::Statsd, Sample::TeamGauge(name, team, value)) => {
+                format!("{prefix}.{name}:{value}|g|#team:{}", tag(team))
+            }
+            (Protocol::Graphite, sample) => {
+                let (name, value) = match sample {
+                    Sample::Counter(name, value) | Sample::Gauge(name, value) => {
+                        (name.to_string(), *value)
+                    }
+                    Sample::Timing(name, value) => (name.to_string(), value.as_millis() as u64),
+                    Sample::TeamGauge(name, team, value) => {
+                        (format!("{name};team={}", tag(team)), *value)
+                    }
+                };
+                format!("{prefix}.{name} {value} {}", Local::now().timestamp())
+            }
+        }
+    }
+}
+
+/// Team name usable as a tag value of both DogStatsD and Graphite, e.g. `Sparta_Praha`.
+fn tag(team: &str) -> String {
+    team.chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_team_gauge() {
+        let sample = Sample::TeamGauge("score.my_team", "Sparta Praha", 2);
+        let statsd = Metrics::new(&Url::parse("statsd://localhost").unwrap(), "crawler").unwrap();
+        assert_eq!(
+            statsd.format(&sample),
+            "crawler.score.my_team:2|g|#team:Sparta_Praha"
+        );
+        let graphite =
+            Metrics::new(&Url::parse("graphite://localhost").unwrap(), "crawler").unwrap();
+        assert!(graphite
+            .format(&sample)
+            .starts_with("crawler.score.my_team;team=Sparta_Praha 2 "));
+    }
+}
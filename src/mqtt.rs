@@ -0,0 +1,121 @@
+//! Sink publishing every result to an MQTT broker.
+
+use anyhow::Context;
+use rumqttc::{AsyncClient, ConnectionError, MqttOptions, QoS};
+use serde::Serialize;
+use std::time::Duration;
+use tracing::warn;
+use url::Url;
+
+use crate::{secrets, GameResult, GameTime};
+
+pub const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+const KEEP_ALIVE: Duration = Duration::from_secs(30);
+
+/// Score of the last result, published retained so that new subscribers get it right away.
+#[derive(Serialize)]
+struct LastScore<'a> {
+    my_team: &'a str,
+    my_team_score: u64,
+    opponent_team: &'a str,
+    opponent_team_score: u64,
+    game_time: &'a GameTime,
+}
+
+pub struct MqttSink {
+    client: AsyncClient,
+    topic: String,
+}
+
+impl MqttSink {
+    /// Connect to an `mqtt://[user:password@]host[:port]` broker in the background; `{team}`
+    /// in the topic stands for the team of the published result.
+    pub fn new(broker: &Url, topic: &str, client_id: &str) -> anyhow::Result<Self> {
+        Ok(Self {
+            client: connect(broker, client_id)?,
+            topic: topic.to_string(),
+        })
+    }
+
+    /// Publish the result to the topic and its score retained to `<topic>/score`.
+    pub fn publish(&self, result: &GameResult) -> anyhow::Result<()> {
+        let topic = self.topic.replace("{team}", &slug(&result.my_team));
+        self.client
+            .try_publish(&topic, QoS::AtLeastOnce, false, serde_json::to_vec(result)?)?;
+        let score = LastScore {
+            my_team: &result.my_team,
+            my_team_score: result.my_team_score,
+            opponent_team: &result.opponent_team,
+            opponent_team_score: result.opponent_team_score,
+            game_time: &result.game_time,
+        };
+        self.client.try_publish(
+            format!("{topic}/score"),
+            QoS::AtLeastOnce,
+            true,
+            serde_json::to_vec(&score)?,
+        )?;
+        Ok(())
+    }
+}
+
+/// Connection options of an `mqtt://[user:password@]host[:port]` broker; the password may
+/// be a secret reference, see [`secrets::url_password`].
+pub fn options(broker: &Url, client_id: &str) -> anyhow::Result<MqttOptions> {
+    anyhow::ensure!(
+        broker.scheme() == "mqtt",
+        "unsupported MQTT broker URL {broker}, expected mqtt://host:port"
+    );
+    let host = broker.host_str().context("missing MQTT broker host")?;
+    let mut options = MqttOptions::new(
+        format!("livesport-crawler-{}", slug(client_id)),
+        host,
+        broker.port().unwrap_or(1883),
+    );
+    options.set_keep_alive(KEEP_ALIVE);
+    if !broker.username().is_empty() {
+        let password = secrets::url_password(broker)
+            .context("cannot read the MQTT password")?
+            .unwrap_or_default();
+        options.set_credentials(broker.username(), password);
+    }
+    Ok(options)
+}
+
+/// Client publishing to the broker, its connection kept up in the background.
+pub fn connect(broker: &Url, client_id: &str) -> anyhow::Result<AsyncClient> {
+    let (client, mut eventloop) = AsyncClient::new(options(broker, client_id)?, 16);
+    tokio::spawn(async move {
+        loop {
+            match eventloop.poll().await {
+                Ok(_) => {}
+                Err(ConnectionError::RequestsDone) => break,
+                Err(error) => {
+                    warn!("MQTT connection failed: {error}");
+                    tokio::time::sleep(RECONNECT_DELAY).await;
+                }
+            }
+        }
+    });
+    Ok(client)
+}
+
+/// Name usable in a topic and a client id.
+fn slug(name: &str) -> String {
+    name.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slug() {
+        assert_eq!(slug("HC Sparta Praha"), "hc-sparta-praha");
+        assert_eq!(slug("Kometa  Brno (U20)"), "kometa-brno-u20");
+    }
+}
@@ -0,0 +1,107 @@
+//! Push notifications through an ntfy topic (ntfy.sh or self-hosted), the priority following
+//! the importance of the change.
+
+use anyhow::Context;
+use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION};
+use serde::Serialize;
+use std::time::Duration;
+use url::Url;
+
+use crate::events::{self, Push, TimelineEvent, Urgency};
+use crate::observer::Observer;
+use crate::webhook::Poster;
+use crate::GameResult;
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+const RETRIES: u32 = 1;
+
+/// ntfy priorities, from the silent one to the one breaking through do-not-disturb.
+const MIN: u8 = 1;
+const LOW: u8 = 2;
+const DEFAULT: u8 = 3;
+const HIGH: u8 = 4;
+
+#[derive(Serialize)]
+struct Notification<'a> {
+    topic: &'a str,
+    title: String,
+    message: String,
+    priority: u8,
+    tags: [&'a str; 1],
+}
+
+pub struct Ntfy {
+    topic: String,
+    poster: Poster,
+    ticks: events::MinuteTicks,
+}
+
+impl Ntfy {
+    /// Publish to the topic of a `https://ntfy.sh/<topic>` URL, with the access token if any.
+    pub fn new(url: &Url, token: Option<&str>) -> anyhow::Result<Self> {
+        // a self-hosted server may live under a path, the topic is the last segment
+        let path = url.path().trim_end_matches('/');
+        let (base, topic) = path.rsplit_once('/').unwrap_or(("", path));
+        anyhow::ensure!(!topic.is_empty(), "missing ntfy topic in {url}");
+        let topic = topic.to_string();
+        let mut server = url.clone();
+        server.set_path(&format!("{base}/"));
+
+        let mut headers = HeaderMap::new();
+        if let Some(token) = token {
+            let mut value = HeaderValue::from_str(&format!("Bearer {token}"))
+                .context("invalid ntfy access token")?;
+            value.set_sensitive(true);
+            headers.insert(AUTHORIZATION, value);
+        }
+        Ok(Self {
+            poster: Poster::with_headers(
+                &format!("ntfy topic {topic}"),
+                server.as_str(),
+                headers,
+                RETRIES,
+                TIMEOUT,
+            )?,
+            topic,
+            ticks: events::MinuteTicks::default(),
+        })
+    }
+
+    fn publish(&self, push: Push) {
+        self.poster.post(&Notification {
+            topic: &self.topic,
+            title: push.title,
+            message: push.message,
+            priority: priority(push.urgency),
+            tags: [push.emoji],
+        });
+    }
+}
+
+fn priority(urgency: Urgency) -> u8 {
+    match urgency {
+        Urgency::Silent => MIN,
+        Urgency::Low => LOW,
+        Urgency::Default => DEFAULT,
+        Urgency::High => HIGH,
+    }
+}
+
+impl Observer for Ntfy {
+    fn announces(&self) -> bool {
+        true
+    }
+
+    /// The running clock is pushed silently, the events come with their own priority.
+    fn on_change(&mut self, previous: Option<&GameResult>, latest: &GameResult) {
+        if let Some(minute) = self.ticks.tick(previous, latest) {
+            self.publish(Push::minute(minute, latest));
+        }
+    }
+
+    fn on_event(&mut self, event: &TimelineEvent, latest: &GameResult) {
+        if let Some(push) = Push::event(event, latest) {
+            self.publish(push);
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use tracing::info;
+
+use crate::events::TimelineEvent;
+use crate::GameResult;
+
+/// Callbacks invoked by the polling loop (see [`Crawler::watch`](crate::Crawler::watch));
+/// all of them default to doing nothing.
+pub trait Observer {
+    /// The published result differs from the previous one (or is the first one).
+    fn on_change(&mut self, _previous: Option<&GameResult>, _latest: &GameResult) {}
+
+    /// Scraping of the team page failed.
+    fn on_error(&mut self, _error: &anyhow::Error) {}
+
+    /// Something is wrong with the setup, e.g. the team has no upcoming match.
+    fn on_alert(&mut self, _message: &str) {}
+
+    /// Something happened in the match: a goal, a break, the final whistle, ...
+    fn on_event(&mut self, _event: &TimelineEvent, _latest: &GameResult) {}
+
+    /// The match finished, `summary` is the rendered post-match summary; it follows the
+    /// `Finished` event, an observer announcing the final result reacts to one of them only.
+    fn on_summary(&mut self, _summary: &str, _latest: &GameResult) {}
+
+    /// Whether the observer announces the match outside of this machine; redundant
+    /// instances claim such announcements (see `--dedup-redis`), the local sinks get all of
+    /// them.
+    fn announces(&self) -> bool {
+        false
+    }
+}
+
+type ChangeCallback = Box<dyn FnMut(Option<&GameResult>, &GameResult) + Send>;
+type ErrorCallback = Box<dyn FnMut(&anyhow::Error) + Send>;
+type EventCallback = Box<dyn FnMut(&TimelineEvent, &GameResult) + Send>;
+type AlertCallback = Box<dyn FnMut(&str) + Send>;
+type SummaryCallback = Box<dyn FnMut(&str, &GameResult) + Send>;
+
+/// Observer logging the match events.
+pub struct EventLog;
+
+impl Observer for EventLog {
+    fn on_event(&mut self, event: &TimelineEvent, _latest: &GameResult) {
+        info!(
+            "match event: {:?} ({}:{})",
+            event.kind, event.my_team_score, event.opponent_team_score
+        );
+    }
+}
+
+/// Observer made of closures, for embedders not wanting to implement the trait.
+#[derive(Default)]
+pub struct Callbacks {
+    on_change: Vec<ChangeCallback>,
+    on_error: Vec<ErrorCallback>,
+    on_event: Vec<EventCallback>,
+    on_alert: Vec<AlertCallback>,
+    on_summary: Vec<SummaryCallback>,
+}
+
+impl Callbacks {
+    pub fn on_change(
+        mut self,
+        callback: impl FnMut(Option<&GameResult>, &GameResult) + Send + 'static,
+    ) -> Self {
+        self.on_change.push(Box::new(callback));
+        self
+    }
+
+    pub fn on_error(mut self, callback: impl FnMut(&anyhow::Error) + Send + 'static) -> Self {
+        self.on_error.push(Box::new(callback));
+        self
+    }
+
+    pub fn on_event(
+        mut self,
+        callback: impl FnMut(&TimelineEvent, &GameResult) + Send + 'static,
+    ) -> Self {
+        self.on_event.push(Box::new(callback));
+        self
+    }
+
+    pub fn on_alert(mut self, callback: impl FnMut(&str) + Send + 'static) -> Self {
+        self.on_alert.push(Box::new(callback));
+        self
+    }
+
+    pub fn on_summary(mut self, callback: impl FnMut(&str, &GameResult) + Send + 'static) -> Self {
+        self.on_summary.push(Box::new(callback));
+        self
+    }
+}
+
+impl Observer for Callbacks {
+    fn on_change(&mut self, previous: Option<&GameResult>, latest: &GameResult) {
+        for callback in &mut self.on_change {
+            callback(previous, latest);
+        }
+    }
+
+    fn on_error(&mut self, error: &anyhow::Error) {
+        for callback in &mut self.on_error {
+            callback(error);
+        }
+    }
+
+    fn on_event(&mut self, event: &TimelineEvent, latest: &GameResult) {
+        for callback in &mut self.on_event {
+            callback(event, latest);
+        }
+    }
+
+    fn on_alert(&mut self, message: &str) {
+        for callback in &mut self.on_alert {
+            callback(message);
+        }
+    }
+
+    fn on_summary(&mut self, summary: &str, latest: &GameResult) {
+        for callback in &mut self.on_summary {
+            callback(summary, latest);
+        }
+    }
+}
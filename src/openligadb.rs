@@ -0,0 +1,263 @@
+//! Export of the match in the shape of the OpenLigaDB `getmatchdata` API, for widgets built for it.
+//!
+//! The site does not tell which team plays at home in the result, my team is always `team1`.
+
+use chrono::{DateTime, Local, Utc};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+use crate::{calendar, permissions, GameResult, GameTime};
+
+/// Result type of the final (or current) score.
+const FINAL_RESULT: u64 = 2;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Match {
+    #[serde(rename = "matchID")]
+    match_id: u32,
+    match_date_time: Option<String>,
+    #[serde(rename = "matchDateTimeUTC")]
+    match_date_time_utc: Option<DateTime<Utc>>,
+    team1: Team,
+    team2: Team,
+    last_update_date_time: String,
+    match_is_finished: bool,
+    match_results: Vec<MatchResult>,
+    goals: Vec<Goal>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Team {
+    team_id: u32,
+    team_name: String,
+    short_name: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MatchResult {
+    #[serde(rename = "resultID")]
+    result_id: u64,
+    result_name: String,
+    points_team1: u64,
+    points_team2: u64,
+    #[serde(rename = "resultOrderID")]
+    result_order_id: u64,
+    #[serde(rename = "resultTypeID")]
+    result_type_id: u64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Goal {
+    #[serde(rename = "goalID")]
+    goal_id: u64,
+    score_team1: u64,
+    score_team2: u64,
+    match_minute: Option<u64>,
+    goal_getter_name: String,
+    is_overtime: bool,
+    comment: Option<String>,
+}
+
+/// Writes every result as an OpenLigaDB match into a file.
+pub struct Exporter {
+    output: PathBuf,
+    kickoff: Option<DateTime<Local>>,
+}
+
+impl Exporter {
+    pub fn new(output: &Path) -> Self {
+        Self {
+            output: output.to_path_buf(),
+            kickoff: None,
+        }
+    }
+
+    pub fn write(&mut self, result: &GameResult) -> anyhow::Result<()> {
+        self.kickoff = calendar::kickoff(result, self.kickoff);
+        serde_json::to_writer_pretty(
+            permissions::create(&self.output)?,
+            &export(result, self.kickoff),
+        )?;
+        Ok(())
+    }
+}
+
+pub fn export(result: &GameResult, kickoff: Option<DateTime<Local>>) -> Match {
+    let started = !matches!(result.game_time, GameTime::WillBePlayed(_));
+    let match_results = if started {
+        vec![MatchResult {
+            result_id: 1,
+            result_name: "Endergebnis".to_string(),
+            points_team1: result.my_team_score,
+            points_team2: result.opponent_team_score,
+            result_order_id: 1,
+            result_type_id: FINAL_RESULT,
+        }]
+    } else {
+        Vec::new()
+    };
+
+    let (mut score_team1, mut score_team2) = (0, 0);
+    let goals = result
+        .goals
+        .iter()
+        .enumerate()
+        .map(|(index, goal)| {
+            if goal.my_team {
+                score_team1 += 1;
+            } else {
+                score_team2 += 1;
+            }
+            let match_minute = parse_minute(&goal.minute);
+            Goal {
+                goal_id: index as u64 + 1,
+                score_team1,
+                score_team2,
+                match_minute,
+                goal_getter_name: goal.scorer.clone(),
+                is_overtime: match_minute
+                    .is_some_and(|minute| minute > result.periods.regulation()),
+                comment: (!goal.assists.is_empty()).then(|| goal.assists.join(", ")),
+            }
+        })
+        .collect();
+
+    Match {
+        match_id: id(&format!(
+            "{}-{}-{}",
+            result.my_team,
+            result.opponent_team,
+            kickoff
+                .map(|kickoff| kickoff.date_naive().to_string())
+                .unwrap_or_default()
+        )),
+        match_date_time: kickoff.map(|kickoff| {
+            kickoff
+                .naive_local()
+                .format("%Y-%m-%dT%H:%M:%S")
+                .to_string()
+        }),
+        match_date_time_utc: kickoff.map(|kickoff| kickoff.with_timezone(&Utc)),
+        team1: team(&result.my_team),
+        team2: team(&result.opponent_team),
+        last_update_date_time: result
+            .generated
+            .naive_local()
+            .format("%Y-%m-%dT%H:%M:%S")
+            .to_string(),
+        match_is_finished: matches!(result.game_time, GameTime::Played),
+        match_results,
+        goals,
+    }
+}
+
+fn team(name: &str) -> Team {
+    Team {
+        team_id: id(name),
+        team_name: name.to_string(),
+        short_name: name.to_string(),
+    }
+}
+
+/// Stable identifier derived from a name (32-bit FNV-1a), the site's ids are not scraped.
+fn id(name: &str) -> u32 {
+    name.bytes().fold(0x811c9dc5, |hash, byte| {
+        (hash ^ u32::from(byte)).wrapping_mul(0x01000193)
+    })
+}
+
+/// Minute of an incident like `34'` or `60+3'`.
+fn parse_minute(minute: &str) -> Option<u64> {
+    minute
+        .trim_end_matches('\'')
+        .split('+')
+        .map(|part| part.trim().parse::<u64>().ok())
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::details;
+    use crate::Periods;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_export() {
+        let goal = |minute: &str, my_team| details::Goal {
+            minute: minute.to_string(),
+            my_team,
+            scorer: "Chlapík".to_string(),
+            assists: Vec::new(),
+            player_of_interest: false,
+        };
+        let generated = Local.with_ymd_and_hms(2024, 9, 3, 19, 30, 0).unwrap();
+        let result = GameResult {
+            my_team: "Sparta Praha".to_string(),
+            my_team_score: 2,
+            opponent_team: "Kometa Brno".to_string(),
+            opponent_team_score: 1,
+            period_scores: vec![(1, 0), (1, 1)],
+            goals: vec![goal("5'", true), goal("27'", false), goal("38'", true)],
+            penalties: Vec::new(),
+            stats: Vec::new(),
+            broadcast: Vec::new(),
+            opponent_form: None,
+            series_score: None,
+            series_best_of: None,
+            timeline: Vec::new(),
+            estimated_time_remaining: None,
+            estimated_end_at: None,
+            started_at: None,
+            elapsed_minutes: None,
+            last_goal_at: None,
+            minutes_since_last_goal: None,
+            outcome: None,
+            goal_difference: None,
+            is_leading: None,
+            clock: None,
+            quality: None,
+            stale_since: None,
+            served_by: None,
+            game_time: GameTime::Playing(38),
+            periods: Periods::HOCKEY,
+            generated,
+        };
+
+        let exported = export(&result, Some(generated - chrono::Duration::hours(1)));
+        assert_eq!(
+            exported.match_date_time.as_deref(),
+            Some("2024-09-03T18:30:00")
+        );
+        assert!(!exported.match_is_finished);
+        assert_eq!(exported.team1.team_name, "Sparta Praha");
+        assert_eq!(exported.team1.team_id, id("Sparta Praha"));
+        assert_eq!(
+            (
+                exported.match_results[0].points_team1,
+                exported.match_results[0].points_team2
+            ),
+            (2, 1)
+        );
+        let scores = exported
+            .goals
+            .iter()
+            .map(|goal| (goal.score_team1, goal.score_team2, goal.match_minute))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            scores,
+            [(1, 0, Some(5)), (1, 1, Some(27)), (2, 1, Some(38))]
+        );
+    }
+
+    #[test]
+    fn test_parse_minute() {
+        assert_eq!(parse_minute("34'"), Some(34));
+        assert_eq!(parse_minute("60+3'"), Some(63));
+        assert_eq!(parse_minute("SN"), None);
+    }
+}
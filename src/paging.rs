@@ -0,0 +1,58 @@
+//! "Show more matches" of the results and fixtures listings, which show only the first page of
+//! rows at first.
+
+use fantoccini::{Client, Locator};
+use std::time::Duration;
+use tokio::time::sleep;
+use tracing::debug;
+
+const SHOW_MORE_SELECTOR: &str = ".event__more";
+const ROW_SELECTOR: &str = ".event__match";
+/// Bound of the pages loaded into one listing
+pub(crate) const MAX_PAGES: u32 = 20;
+/// How long the next page of rows may take to appear
+const LOAD_TIMEOUT: Duration = Duration::from_secs(5);
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Load the next page of rows; `false` when the listing is complete.
+pub(crate) async fn show_more(client: &mut Client) -> anyhow::Result<bool> {
+    let Ok(more) = client.find(Locator::Css(SHOW_MORE_SELECTOR)).await else {
+        return Ok(false);
+    };
+    let before = rows(client).await?;
+    more.click().await?;
+
+    let mut waited = Duration::ZERO;
+    while waited < LOAD_TIMEOUT {
+        sleep(POLL_INTERVAL).await;
+        waited += POLL_INTERVAL;
+        let after = rows(client).await?;
+        if after > before {
+            debug!("showing {after} rows");
+            return Ok(true);
+        }
+    }
+    // the control stays on the page but nothing comes any more
+    Ok(false)
+}
+
+/// Load pages until the rows matching the selector are at least `wanted`, the listing is
+/// complete or `MAX_PAGES` are loaded.
+pub(crate) async fn show_at_least(
+    client: &mut Client,
+    selector: &str,
+    wanted: usize,
+) -> anyhow::Result<()> {
+    for _ in 1..MAX_PAGES {
+        if client.find_all(Locator::Css(selector)).await?.len() >= wanted
+            || !show_more(client).await?
+        {
+            break;
+        }
+    }
+    Ok(())
+}
+
+async fn rows(client: &mut Client) -> anyhow::Result<usize> {
+    Ok(client.find_all(Locator::Css(ROW_SELECTOR)).await?.len())
+}
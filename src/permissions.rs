@@ -0,0 +1,121 @@
+//! Mode and owner of the written output files and created directories, so that e.g. a web
+//! server running as another user can read them.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::OnceLock;
+use tracing::warn;
+
+struct Permissions {
+    mode: Option<u32>,
+    dir_mode: Option<u32>,
+    owner: Option<(Option<u32>, Option<u32>)>,
+}
+
+static PERMISSIONS: OnceLock<Permissions> = OnceLock::new();
+
+/// Use the modes and the `USER[:GROUP]` or `:GROUP` owner for all output written from now on.
+pub fn init(mode: Option<u32>, dir_mode: Option<u32>, owner: Option<&str>) -> anyhow::Result<()> {
+    let owner = owner.map(resolve_owner).transpose()?;
+    let _ = PERMISSIONS.set(Permissions {
+        mode,
+        dir_mode,
+        owner,
+    });
+    Ok(())
+}
+
+#[cfg(unix)]
+fn resolve_owner(owner: &str) -> anyhow::Result<(Option<u32>, Option<u32>)> {
+    use anyhow::Context;
+    use nix::unistd::{Group, User};
+
+    let (user, group) = match owner.split_once(':') {
+        Some((user, group)) => (user, Some(group)),
+        None => (owner, None),
+    };
+    let uid = match user {
+        "" => None,
+        name => Some(
+            User::from_name(name)?
+                .with_context(|| format!("unknown user {name}"))?
+                .uid
+                .as_raw(),
+        ),
+    };
+    let gid = match group.filter(|group| !group.is_empty()) {
+        Some(name) => Some(
+            Group::from_name(name)?
+                .with_context(|| format!("unknown group {name}"))?
+                .gid
+                .as_raw(),
+        ),
+        None => None,
+    };
+    Ok((uid, gid))
+}
+
+#[cfg(not(unix))]
+fn resolve_owner(_owner: &str) -> anyhow::Result<(Option<u32>, Option<u32>)> {
+    anyhow::bail!("--output-owner is supported on Unix only")
+}
+
+/// Create or truncate an output file.
+pub fn create(path: &Path) -> io::Result<File> {
+    let file = File::create(path)?;
+    apply(path, false);
+    Ok(file)
+}
+
+/// Open an output file for appending, creating it if needed.
+pub fn append(path: &Path) -> io::Result<File> {
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    apply(path, false);
+    Ok(file)
+}
+
+pub fn write(path: &Path, contents: impl AsRef<[u8]>) -> io::Result<()> {
+    create(path)?.write_all(contents.as_ref())
+}
+
+/// Create the directory with all its missing parents, each of them with the directory mode.
+pub fn create_dir_all(path: &Path) -> io::Result<()> {
+    let missing: Vec<_> = path
+        .ancestors()
+        .take_while(|ancestor| !ancestor.as_os_str().is_empty() && !ancestor.exists())
+        .collect();
+    fs::create_dir_all(path)?;
+    for directory in missing {
+        apply(directory, true);
+    }
+    Ok(())
+}
+
+/// A file that cannot be handed over is still written, the problem is only reported.
+#[cfg(unix)]
+fn apply(path: &Path, directory: bool) {
+    use std::os::unix::fs::PermissionsExt;
+
+    let Some(permissions) = PERMISSIONS.get() else {
+        return;
+    };
+    let mode = if directory {
+        permissions.dir_mode
+    } else {
+        permissions.mode
+    };
+    if let Some(mode) = mode {
+        if let Err(error) = fs::set_permissions(path, fs::Permissions::from_mode(mode)) {
+            warn!("cannot set mode {mode:o} of {}: {error}", path.display());
+        }
+    }
+    if let Some((uid, gid)) = permissions.owner {
+        if let Err(error) = std::os::unix::fs::chown(path, uid, gid) {
+            warn!("cannot change the owner of {}: {error}", path.display());
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn apply(_path: &Path, _directory: bool) {}
@@ -0,0 +1,639 @@
+use anyhow::Context;
+use chrono::{DateTime, Local};
+use serde::Serialize;
+use std::fs;
+use std::io::Write;
+use std::mem;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tracing::{info, warn};
+
+use crate::archive::Archiver;
+use crate::context::MatchContext;
+#[cfg(feature = "redis")]
+use crate::dedup::Dedup;
+#[cfg(feature = "smtp")]
+use crate::email::EmailNotifier;
+use crate::events;
+#[cfg(feature = "chat")]
+use crate::gotify::Gotify;
+use crate::hook::EventHook;
+#[cfg(feature = "chat")]
+use crate::matrix::MatrixRoom;
+#[cfg(feature = "mqtt")]
+use crate::mqtt::MqttSink;
+#[cfg(feature = "chat")]
+use crate::ntfy::Ntfy;
+use crate::observer::Observer;
+use crate::openligadb;
+use crate::plugin::{ExecSink, Plugin};
+use crate::quality::Source;
+#[cfg(any(feature = "chat", feature = "smtp"))]
+use crate::secrets;
+#[cfg(feature = "server")]
+use crate::server::Slot;
+#[cfg(feature = "chat")]
+use crate::slack::Slack;
+use crate::standby::Standby;
+#[cfg(feature = "chat")]
+use crate::telegram::TelegramBot;
+use crate::transition;
+use crate::webhook::Webhook;
+use crate::{
+    details, estimate, permissions, state, summary, GameResult, GameTime, OutputFormat, SinkArgs,
+};
+
+/// Everything done with a freshly obtained result: output file, archive, summaries, ...
+pub struct Pipeline {
+    output: PathBuf,
+    output_format: OutputFormat,
+    archiver: Option<Archiver>,
+    summary_output: Option<PathBuf>,
+    summary_template: String,
+    player_output: Option<PathBuf>,
+    openligadb: Option<openligadb::Exporter>,
+    #[cfg(feature = "mqtt")]
+    mqtt: Option<MqttSink>,
+    state: Option<PathBuf>,
+    #[cfg(feature = "redis")]
+    dedup: Option<Dedup>,
+    previous_match: Option<GameResult>,
+    timeline: Vec<(u64, u64, u64)>,
+    observers: Vec<Box<dyn Observer + Send>>,
+    #[cfg(feature = "server")]
+    slot: Option<Slot>,
+    standby: Option<Standby>,
+    drop_glitches: bool,
+    /// Whether the previous sample was an impossible successor too
+    glitched: bool,
+    /// Whether the previous result was loaded from the state saved before a restart
+    resumed: bool,
+}
+
+impl Pipeline {
+    pub fn new(output: &Path, sinks: &SinkArgs) -> anyhow::Result<Self> {
+        let summary_template = match &sinks.summary_template {
+            Some(path) => fs::read_to_string(path)
+                .with_context(|| format!("cannot read summary template {}", path.display()))?,
+            None => summary::DEFAULT_TEMPLATE.to_string(),
+        };
+
+        let mut observers: Vec<Box<dyn Observer + Send>> = Vec::new();
+        for command in &sinks.sink_exec {
+            observers.push(Box::new(Plugin(ExecSink::new(command)?)));
+        }
+        for command in &sinks.on_event {
+            observers.push(Box::new(EventHook::new(command)));
+        }
+        #[cfg(feature = "chat")]
+        if let (Some(token), Some(chat_id)) = (&sinks.telegram_token, &sinks.telegram_chat) {
+            observers.push(Box::new(TelegramBot::new(
+                &secrets::resolve(token).context("cannot read the Telegram bot token")?,
+                chat_id,
+            )?));
+        }
+        #[cfg(feature = "chat")]
+        if let Some(webhook) = &sinks.slack_webhook {
+            observers.push(Box::new(Slack::new(
+                &secrets::resolve(webhook).context("cannot read the Slack webhook URL")?,
+                &sinks.slack_template,
+            )?));
+        }
+        #[cfg(feature = "smtp")]
+        if let (Some(smtp), Some(from)) = (&sinks.smtp, &sinks.email_from) {
+            observers.push(Box::new(EmailNotifier::new(
+                &secrets::resolve(smtp).context("cannot read the SMTP server URL")?,
+                from,
+                &sinks.email_to,
+                &summary_template,
+            )?));
+        }
+        #[cfg(feature = "chat")]
+        if let (Some(homeserver), Some(token), Some(room)) = (
+            &sinks.matrix_homeserver,
+            &sinks.matrix_token,
+            &sinks.matrix_room,
+        ) {
+            observers.push(Box::new(MatrixRoom::new(
+                homeserver,
+                &secrets::resolve(token).context("cannot read the Matrix access token")?,
+                room,
+            )?));
+        }
+        #[cfg(feature = "chat")]
+        if let Some(url) = &sinks.ntfy {
+            let token = sinks
+                .ntfy_token
+                .as_deref()
+                .map(secrets::resolve)
+                .transpose()
+                .context("cannot read the ntfy access token")?;
+            observers.push(Box::new(Ntfy::new(url, token.as_deref())?));
+        }
+        #[cfg(feature = "chat")]
+        if let (Some(server), Some(token)) = (&sinks.gotify, &sinks.gotify_token) {
+            observers.push(Box::new(Gotify::new(
+                server,
+                &secrets::resolve(token).context("cannot read the Gotify token")?,
+            )?));
+        }
+        for url in &sinks.webhook {
+            observers.push(Box::new(Webhook::new(
+                url,
+                sinks.webhook_retries,
+                Duration::from_secs(sinks.webhook_timeout),
+            )?));
+        }
+        #[cfg(feature = "desktop")]
+        if sinks.notify_desktop {
+            observers.push(Box::new(crate::desktop::DesktopNotifier));
+        }
+        #[cfg(feature = "wasm")]
+        for path in &sinks.sink_wasm {
+            observers.push(Box::new(Plugin(crate::wasm::WasmSink::new(path)?)));
+        }
+
+        #[cfg(feature = "mqtt")]
+        let mqtt = match &sinks.mqtt_broker {
+            // the output file is unique per team, so is the client id derived from it
+            Some(broker) => Some(MqttSink::new(
+                broker,
+                &sinks.mqtt_topic,
+                &state::output_key(output),
+            )?),
+            None => None,
+        };
+
+        let state = sinks
+            .state_dir
+            .as_deref()
+            .map(|state_dir| state::path(state_dir, output));
+        let previous_match = state.as_deref().and_then(state::load);
+        if let Some(previous) = &previous_match {
+            info!(
+                "resuming from the saved state {} {}:{} {}",
+                previous.my_team,
+                previous.my_team_score,
+                previous.opponent_team_score,
+                previous.opponent_team
+            );
+        }
+
+        Ok(Self {
+            output: output.to_path_buf(),
+            output_format: sinks.output_format,
+            archiver: sinks.archive_dir.clone().map(Archiver::new),
+            summary_output: sinks.summary_output.clone(),
+            summary_template,
+            player_output: sinks.player_output.clone(),
+            openligadb: sinks
+                .openligadb_output
+                .as_deref()
+                .map(openligadb::Exporter::new),
+            #[cfg(feature = "mqtt")]
+            mqtt,
+            state,
+            #[cfg(feature = "redis")]
+            dedup: sinks.dedup_redis.as_ref().map(Dedup::new).transpose()?,
+            timeline: previous_match
+                .as_ref()
+                .map(|previous| previous.timeline.clone())
+                .unwrap_or_default(),
+            resumed: previous_match.is_some(),
+            previous_match,
+            observers,
+            #[cfg(feature = "server")]
+            slot: None,
+            standby: None,
+            drop_glitches: sinks.drop_glitches,
+            glitched: false,
+        })
+    }
+
+    /// Register callbacks invoked for every change, event and scraping error.
+    pub fn observe(&mut self, observer: impl Observer + Send + 'static) {
+        self.observers.push(Box::new(observer));
+    }
+
+    /// Keep the published result also where the HTTP server reads it.
+    #[cfg(feature = "server")]
+    pub fn serve(&mut self, slot: Slot) {
+        self.slot = Some(slot);
+    }
+
+    /// Announce nothing until the standby instance takes over.
+    pub fn stand_by(&mut self, standby: Standby) {
+        self.standby = Some(standby);
+    }
+
+    /// Whether this is a standby instance still waiting for the primary to fail.
+    fn standing_by(&self) -> bool {
+        self.standby
+            .as_ref()
+            .is_some_and(|standby| !standby.is_active())
+    }
+
+    /// Let the observers know that no result could be obtained.
+    pub fn fail(&mut self, error: &anyhow::Error) {
+        if self.standing_by() {
+            return;
+        }
+        for observer in &mut self.observers {
+            observer.on_error(error);
+        }
+    }
+
+    pub async fn publish(&mut self, mut latest_match: GameResult) -> anyhow::Result<()> {
+        if self.drop_glitch(&latest_match) {
+            return Ok(());
+        }
+        self.enrich(&mut latest_match);
+        self.write_output(&latest_match, &latest_match)?;
+        self.feed_sinks(latest_match).await
+    }
+
+    /// Write the whole context to the output file; the other sinks get the current match only.
+    pub async fn publish_context(&mut self, mut context: MatchContext) -> anyhow::Result<()> {
+        if self.drop_glitch(&context.current_or_next_match) {
+            return Ok(());
+        }
+        self.enrich(&mut context.current_or_next_match);
+        self.write_output(&context, &context.current_or_next_match)?;
+        self.feed_sinks(context.current_or_next_match).await
+    }
+
+    /// Serve the last good result again, marked as not refreshed since the given time.
+    pub fn publish_stale(&mut self, since: DateTime<Local>) -> anyhow::Result<()> {
+        let Some(previous) = &self.previous_match else {
+            return Ok(());
+        };
+        let mut stale = previous.clone();
+        stale.stale_since = Some(since);
+        self.write_output(&stale, &stale)?;
+        if self.standing_by() {
+            return Ok(());
+        }
+        #[cfg(feature = "server")]
+        if let Some(slot) = &self.slot {
+            *slot.lock().unwrap() = Some(stale.clone());
+        }
+        for observer in &mut self.observers {
+            observer.on_change(Some(previous), &stale);
+        }
+        Ok(())
+    }
+
+    /// Rewrite the output file, or append a line to it, with the document of the latest match.
+    fn write_output(
+        &self,
+        document: &impl Serialize,
+        latest_match: &GameResult,
+    ) -> anyhow::Result<()> {
+        match self.output_format {
+            OutputFormat::Json => {
+                serde_json::to_writer_pretty(permissions::create(&self.output)?, document)?
+            }
+            OutputFormat::Yaml => {
+                serde_yaml::to_writer(permissions::create(&self.output)?, document)?
+            }
+            // named fields keep the map readable without knowing the field order
+            OutputFormat::MessagePack => {
+                rmp_serde::encode::write_named(&mut permissions::create(&self.output)?, document)?
+            }
+            OutputFormat::JsonlChanges
+                if self
+                    .previous_match
+                    .as_ref()
+                    .is_some_and(|previous| !events::changed(previous, latest_match)) => {}
+            OutputFormat::Jsonl | OutputFormat::JsonlChanges => {
+                let mut file = permissions::append(&self.output)?;
+                serde_json::to_writer(&mut file, document)?;
+                writeln!(file)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Let the observers know about a problem needing attention of the operator.
+    pub fn alert(&mut self, message: &str) {
+        if self.standing_by() {
+            return;
+        }
+        for observer in &mut self.observers {
+            observer.on_alert(message);
+        }
+    }
+
+    /// Whether the result is an impossible successor of the previous one and should not be
+    /// published; the same glitch seen twice in a row is real, e.g. a disallowed goal.
+    fn drop_glitch(&mut self, latest_match: &GameResult) -> bool {
+        let Some(glitch) = self
+            .previous_match
+            .as_ref()
+            .and_then(|previous| transition::check(previous, latest_match))
+        else {
+            self.glitched = false;
+            return false;
+        };
+        let repeated = mem::replace(&mut self.glitched, true);
+        warn!(
+            "impossible transition of {} against {}: {glitch}",
+            latest_match.my_team, latest_match.opponent_team
+        );
+        if self.drop_glitches && !repeated {
+            return true;
+        }
+        self.glitched = false;
+        false
+    }
+
+    /// Add what is derived from the history of the match rather than scraped.
+    fn enrich(&mut self, latest_match: &mut GameResult) {
+        self.carry_over_details(latest_match);
+        self.record_timeline(latest_match);
+        self.record_times(latest_match);
+        latest_match.estimated_time_remaining =
+            estimate::time_remaining(&latest_match.game_time, latest_match.periods);
+        latest_match.estimated_end_at = estimate::end_at(
+            &latest_match.game_time,
+            latest_match.periods,
+            latest_match.generated,
+        );
+        latest_match.derive_perspective();
+    }
+
+    /// Remember every score change of the running match and attach them to the result.
+    fn record_timeline(&mut self, latest_match: &mut GameResult) {
+        if matches!(latest_match.game_time, GameTime::WillBePlayed(_))
+            || self
+                .previous_match
+                .as_ref()
+                .is_some_and(|previous| previous.opponent_team != latest_match.opponent_team)
+        {
+            self.timeline.clear();
+        }
+        if let Some(minute) = latest_match.game_time.minute() {
+            let score = (latest_match.my_team_score, latest_match.opponent_team_score);
+            if self
+                .timeline
+                .last()
+                .is_none_or(|&(_, my, opponent)| (my, opponent) != score)
+            {
+                self.timeline.push((minute, score.0, score.1));
+            }
+        }
+        latest_match.timeline = self.timeline.clone();
+    }
+
+    /// Details and opponent form the scrape could not read taken from the previous sample of
+    /// the match.
+    fn carry_over_details(&self, latest_match: &mut GameResult) {
+        let Some(previous) = self
+            .previous_match
+            .as_ref()
+            .filter(|previous| previous.opponent_team == latest_match.opponent_team)
+        else {
+            return;
+        };
+        let Some(quality) = &mut latest_match.quality else {
+            return;
+        };
+        if quality.fields.get("details") == Some(&Source::Default) {
+            latest_match.goals = previous.goals.clone();
+            latest_match.penalties = previous.penalties.clone();
+            latest_match.stats = previous.stats.clone();
+            latest_match.broadcast = previous.broadcast.clone();
+            quality.record("details", Source::Fallback);
+        }
+        if quality.fields.get("opponent_form") == Some(&Source::Default) {
+            latest_match.opponent_form = previous.opponent_form.clone();
+            quality.record("opponent_form", Source::Fallback);
+        }
+    }
+
+    /// Wall-clock start and last goal carried over from the previous sample of the match.
+    fn record_times(&self, latest_match: &mut GameResult) {
+        let previous = self.previous_match.as_ref().filter(|previous| {
+            previous.opponent_team == latest_match.opponent_team
+                && (previous.game_time.is_running()
+                    || matches!(
+                        (&previous.game_time, &latest_match.game_time),
+                        (GameTime::Played, GameTime::Played)
+                    ))
+        });
+        latest_match.started_at = previous
+            .and_then(|previous| previous.started_at)
+            .or_else(|| {
+                estimate::started_at(
+                    &latest_match.game_time,
+                    latest_match.periods,
+                    latest_match.generated,
+                )
+            });
+        latest_match.last_goal_at = match previous {
+            Some(previous)
+                if (previous.my_team_score, previous.opponent_team_score)
+                    != (latest_match.my_team_score, latest_match.opponent_team_score) =>
+            {
+                Some(latest_match.generated)
+            }
+            Some(previous) => previous.last_goal_at,
+            None => None,
+        };
+
+        if latest_match.game_time.is_running() {
+            let minutes_since = |since: DateTime<Local>| {
+                (latest_match.generated - since).num_minutes().max(0) as u64
+            };
+            let goalless = latest_match.my_team_score + latest_match.opponent_team_score == 0;
+            latest_match.elapsed_minutes = latest_match.started_at.map(minutes_since);
+            latest_match.minutes_since_last_goal = latest_match
+                .last_goal_at
+                .or(latest_match.started_at.filter(|_| goalless))
+                .map(minutes_since);
+        } else if let Some(previous) = previous {
+            latest_match.elapsed_minutes = previous.elapsed_minutes;
+            latest_match.minutes_since_last_goal = previous.minutes_since_last_goal;
+        }
+    }
+
+    async fn feed_sinks(&mut self, latest_match: GameResult) -> anyhow::Result<()> {
+        if self.standing_by() {
+            // follow the match only, so that the takeover does not announce it again
+            self.previous_match = Some(latest_match);
+            return Ok(());
+        }
+        if mem::take(&mut self.resumed) {
+            self.report_downtime(&latest_match);
+        }
+        if let Some(archiver) = &mut self.archiver {
+            match archiver.record(&latest_match) {
+                Ok(Some(path)) => info!("match archived to {}", path.display()),
+                Ok(None) => {}
+                Err(error) => warn!("could not archive match: {error:#}"),
+            }
+        }
+        #[cfg(feature = "mqtt")]
+        if let Some(mqtt) = &self.mqtt {
+            if let Err(error) = mqtt.publish(&latest_match) {
+                warn!("could not publish to MQTT: {error:#}");
+            }
+        }
+        if let Some(openligadb) = &mut self.openligadb {
+            if let Err(error) = openligadb.write(&latest_match) {
+                warn!("could not write OpenLigaDB match: {error:#}");
+            }
+        }
+        if let Some(previous) = self
+            .previous_match
+            .as_ref()
+            .filter(|previous| previous.opponent_team == latest_match.opponent_team)
+        {
+            if let Some(player_output) = &self.player_output {
+                if let Err(error) = append_player_goals(player_output, previous, &latest_match) {
+                    warn!("could not write player goals: {error}");
+                }
+            }
+            for statistic in details::changed_statistics(&previous.stats, &latest_match.stats) {
+                info!(
+                    "statistic changed: {} {}:{}",
+                    statistic.name, statistic.my_team, statistic.opponent_team
+                );
+            }
+        }
+        self.notify(&latest_match).await;
+        #[cfg(feature = "server")]
+        if let Some(slot) = &self.slot {
+            *slot.lock().unwrap() = Some(latest_match.clone());
+        }
+        if let Some(summary_output) = &self.summary_output {
+            if self
+                .previous_match
+                .as_ref()
+                .is_some_and(|previous| previous.game_time.is_running())
+                && matches!(latest_match.game_time, GameTime::Played)
+            {
+                let summary = summary::render(&self.summary_template, &latest_match);
+                info!("match finished:\n{summary}");
+                if let Err(error) = permissions::write(summary_output, summary) {
+                    warn!("could not write match summary: {error}");
+                }
+            }
+        }
+        if let Some(state) = &self.state {
+            if let Err(error) = state::save(state, &latest_match) {
+                warn!("could not save the state: {error:#}");
+            }
+        }
+        self.previous_match = Some(latest_match);
+        Ok(())
+    }
+
+    /// Tell that the score changed while the crawler was down; the goals themselves are
+    /// announced as usual.
+    fn report_downtime(&mut self, latest_match: &GameResult) {
+        let Some(previous) = self
+            .previous_match
+            .as_ref()
+            .filter(|previous| previous.same_match(latest_match))
+        else {
+            return;
+        };
+        if (previous.my_team_score, previous.opponent_team_score)
+            == (latest_match.my_team_score, latest_match.opponent_team_score)
+        {
+            return;
+        }
+        let message = format!(
+            "{} {}:{} {} changed to {}:{} while the crawler was down since {}",
+            previous.my_team,
+            previous.my_team_score,
+            previous.opponent_team_score,
+            previous.opponent_team,
+            latest_match.my_team_score,
+            latest_match.opponent_team_score,
+            previous.generated.format("%H:%M")
+        );
+        warn!("{message}");
+        self.alert(&message);
+    }
+
+    async fn notify(&mut self, latest_match: &GameResult) {
+        let previous = self
+            .previous_match
+            .as_ref()
+            .filter(|previous| previous.opponent_team == latest_match.opponent_team);
+        if previous.is_some_and(|previous| !events::changed(previous, latest_match)) {
+            return;
+        }
+        let events = previous
+            .map(|previous| events::between(previous, latest_match))
+            .unwrap_or_default();
+        let (claimed, changed) = self.claim(&events, latest_match).await;
+        for observer in &mut self.observers {
+            let announces = observer.announces();
+            if changed || !announces {
+                observer.on_change(self.previous_match.as_ref(), latest_match);
+            }
+            for event in if announces { &claimed } else { &events } {
+                observer.on_event(event, latest_match);
+            }
+        }
+        if claimed
+            .iter()
+            .any(|event| matches!(event.kind, events::TimelineKind::Finished))
+        {
+            let summary = summary::render(&self.summary_template, latest_match);
+            for observer in &mut self.observers {
+                observer.on_summary(&summary, latest_match);
+            }
+        }
+    }
+
+    /// The events, and whether the change, this instance is the first one to announce;
+    /// another instance publishing the same state or event does it alone.
+    #[cfg(feature = "redis")]
+    async fn claim(
+        &mut self,
+        events: &[events::TimelineEvent],
+        latest_match: &GameResult,
+    ) -> (Vec<events::TimelineEvent>, bool) {
+        let Some(dedup) = &mut self.dedup else {
+            return (events.to_vec(), true);
+        };
+        let mut claimed = Vec::new();
+        for event in events {
+            if dedup.claim(event, latest_match).await {
+                claimed.push(event.clone());
+            }
+        }
+        (claimed, dedup.claim_change(latest_match).await)
+    }
+
+    #[cfg(not(feature = "redis"))]
+    async fn claim(
+        &mut self,
+        events: &[events::TimelineEvent],
+        _latest_match: &GameResult,
+    ) -> (Vec<events::TimelineEvent>, bool) {
+        (events.to_vec(), true)
+    }
+}
+
+fn append_player_goals(
+    path: &Path,
+    previous: &GameResult,
+    latest_match: &GameResult,
+) -> anyhow::Result<()> {
+    let mut file = permissions::append(path)?;
+    for goal in latest_match
+        .goals
+        .iter()
+        .filter(|goal| goal.player_of_interest && !previous.goals.contains(goal))
+    {
+        info!("goal of a watched player: {goal:?}");
+        serde_json::to_writer(&mut file, goal)?;
+        writeln!(file)?;
+    }
+    Ok(())
+}
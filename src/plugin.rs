@@ -0,0 +1,153 @@
+//! Sinks implemented by external processes reading one JSON message per line on their stdin.
+
+use anyhow::Context;
+use serde::Serialize;
+use std::io::Write;
+use std::process::{Child, ChildStdin, Command, Stdio};
+use tracing::warn;
+
+use crate::events::TimelineEvent;
+use crate::observer::Observer;
+use crate::GameResult;
+
+/// Message of the plugin protocol, serialized as one JSON line.
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Message<'a> {
+    Result { result: &'a GameResult },
+    Event { event: &'a TimelineEvent },
+    Error { message: String },
+    Alert { message: &'a str },
+}
+
+/// Plugin receiving the protocol messages.
+pub trait MessageSink {
+    fn send(&mut self, message: &Message);
+}
+
+/// Observer of the pipeline forwarding everything to the plugin.
+pub struct Plugin<T>(pub T);
+
+impl<T: MessageSink> Observer for Plugin<T> {
+    fn on_change(&mut self, _previous: Option<&GameResult>, latest: &GameResult) {
+        self.0.send(&Message::Result { result: latest });
+    }
+
+    fn on_error(&mut self, error: &anyhow::Error) {
+        self.0.send(&Message::Error {
+            message: format!("{error:#}"),
+        });
+    }
+
+    fn on_alert(&mut self, message: &str) {
+        self.0.send(&Message::Alert { message });
+    }
+
+    fn on_event(&mut self, event: &TimelineEvent, _latest: &GameResult) {
+        self.0.send(&Message::Event { event });
+    }
+}
+
+/// Long-lived child process started with `sh -c`, restarted when it exits.
+pub struct ExecSink {
+    command: String,
+    child: Option<(Child, ChildStdin)>,
+}
+
+impl ExecSink {
+    pub fn new(command: &str) -> anyhow::Result<Self> {
+        let mut sink = Self {
+            command: command.to_string(),
+            child: None,
+        };
+        sink.child = Some(sink.spawn()?);
+        Ok(sink)
+    }
+
+    fn spawn(&self) -> anyhow::Result<(Child, ChildStdin)> {
+        let mut child = Command::new("sh")
+            .arg("-c")
+            .arg(&self.command)
+            .stdin(Stdio::piped())
+            .spawn()
+            .with_context(|| format!("cannot start sink {}", self.command))?;
+        let stdin = child.stdin.take().context("sink has no stdin")?;
+        Ok((child, stdin))
+    }
+}
+
+impl MessageSink for ExecSink {
+    fn send(&mut self, message: &Message) {
+        let mut line = match serde_json::to_vec(message) {
+            Ok(line) => line,
+            Err(error) => {
+                warn!(
+                    "could not serialize message for sink {}: {error}",
+                    self.command
+                );
+                return;
+            }
+        };
+        line.push(b'\n');
+
+        // one restart per message is enough for a sink that exited in the meantime
+        for _ in 0..2 {
+            if self.child.is_none() {
+                match self.spawn() {
+                    Ok(child) => self.child = Some(child),
+                    Err(error) => {
+                        warn!("{error:#}");
+                        return;
+                    }
+                }
+            }
+            let Some((child, stdin)) = &mut self.child else {
+                return;
+            };
+            match stdin.write_all(&line).and_then(|_| stdin.flush()) {
+                Ok(()) => return,
+                Err(error) => {
+                    warn!("sink {} failed: {error}", self.command);
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    self.child = None;
+                }
+            }
+        }
+    }
+}
+
+impl Drop for ExecSink {
+    fn drop(&mut self) {
+        // closing stdin tells the sink to finish
+        if let Some((mut child, stdin)) = self.child.take() {
+            drop(stdin);
+            let _ = child.wait();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_exec_sink() {
+        let path = std::env::temp_dir().join(format!("livesport-sink-{}", std::process::id()));
+        let mut sink = Plugin(ExecSink::new(&format!("cat > {}", path.display())).unwrap());
+        sink.on_alert("first");
+        sink.on_error(&anyhow::anyhow!("second"));
+        drop(sink);
+
+        let lines: Vec<serde_json::Value> = fs::read_to_string(&path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines[0]["type"], "alert");
+        assert_eq!(lines[0]["message"], "first");
+        assert_eq!(lines[1]["type"], "error");
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,152 @@
+//! When to scrape next, decoupled from the scraping so that embedders can bring their own
+//! timing.
+
+use chrono::{DateTime, Local};
+use std::time::Duration;
+
+use crate::{calendar, schedule, GameResult, GameTime};
+
+/// Policy deciding the delay between the scrapes of a team.
+pub trait PollPolicy: Send {
+    /// Time to wait after the scrape at `now` produced `latest`, `None` when it failed.
+    fn next_delay(&mut self, latest: Option<&GameResult>, now: DateTime<Local>) -> Duration;
+}
+
+/// The same delay all the time.
+pub struct Fixed(pub Duration);
+
+impl PollPolicy for Fixed {
+    fn next_delay(&mut self, _latest: Option<&GameResult>, _now: DateTime<Local>) -> Duration {
+        self.0
+    }
+}
+
+/// Frequent scrapes while the match is played (or the scrape failed), rare ones otherwise,
+/// the latter shortened to be on time for a known kickoff.
+pub struct Adaptive {
+    pub live: Duration,
+    pub idle: Duration,
+}
+
+impl PollPolicy for Adaptive {
+    fn next_delay(&mut self, latest: Option<&GameResult>, _now: DateTime<Local>) -> Duration {
+        let Some(latest) = latest else {
+            return self.live;
+        };
+        match latest.game_time {
+            GameTime::Playing(_) | GameTime::BreakAfter(_) => self.live,
+            GameTime::WillBePlayed(Some((hours, minutes))) => self
+                .idle
+                .min(Duration::from_secs((hours * 60 + minutes) * 60))
+                .max(self.live),
+            GameTime::WillBePlayed(None) | GameTime::Played => self.idle,
+        }
+    }
+}
+
+/// Scrapes at the times of cron expressions (see [`schedule::Expression`]), every
+/// `fallback` when none of them matches.
+pub struct Cron {
+    pub expressions: Vec<schedule::Expression>,
+    pub fallback: Duration,
+}
+
+impl PollPolicy for Cron {
+    fn next_delay(&mut self, _latest: Option<&GameResult>, now: DateTime<Local>) -> Duration {
+        schedule::delay(&self.expressions, &now).unwrap_or(self.fallback)
+    }
+}
+
+/// Another policy around the kickoffs, nothing between them apart from a periodic check for
+/// new fixtures.
+pub struct Calendar<P> {
+    inner: P,
+    kickoff: Option<DateTime<Local>>,
+}
+
+impl<P: PollPolicy> Calendar<P> {
+    pub fn new(inner: P) -> Self {
+        Self {
+            inner,
+            kickoff: None,
+        }
+    }
+}
+
+impl<P: PollPolicy> PollPolicy for Calendar<P> {
+    fn next_delay(&mut self, latest: Option<&GameResult>, now: DateTime<Local>) -> Duration {
+        if let Some(latest) = latest {
+            self.kickoff = calendar::kickoff(latest, self.kickoff);
+        }
+        let delay = self.inner.next_delay(latest, now);
+        calendar::idle_for(&[self.kickoff], now)
+            .filter(|idle| *idle > delay)
+            .unwrap_or(delay)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{quality, MatchRow, Periods};
+
+    fn sample(game_time: GameTime) -> GameResult {
+        MatchRow {
+            home_team: "Sparta Praha".to_string(),
+            away_team: "Kometa Brno".to_string(),
+            home_score: 0,
+            away_score: 0,
+            period_scores: Vec::new(),
+            game_time,
+            clock: None,
+            periods: Periods::HOCKEY,
+            quality: quality::Quality::default(),
+        }
+        .into_result(true)
+    }
+
+    #[test]
+    fn test_adaptive() {
+        let mut adaptive = Adaptive {
+            live: Duration::from_secs(10),
+            idle: Duration::from_secs(3600),
+        };
+        let now = Local::now();
+        let delay =
+            |adaptive: &mut Adaptive, game_time| adaptive.next_delay(Some(&sample(game_time)), now);
+        assert_eq!(
+            delay(&mut adaptive, GameTime::Playing(12)),
+            Duration::from_secs(10)
+        );
+        assert_eq!(
+            delay(&mut adaptive, GameTime::WillBePlayed(Some((0, 20)))),
+            Duration::from_secs(20 * 60)
+        );
+        assert_eq!(
+            delay(&mut adaptive, GameTime::WillBePlayed(Some((5, 0)))),
+            Duration::from_secs(3600)
+        );
+        assert_eq!(
+            delay(&mut adaptive, GameTime::WillBePlayed(Some((0, 0)))),
+            Duration::from_secs(10)
+        );
+        assert_eq!(adaptive.next_delay(None, now), Duration::from_secs(10));
+    }
+
+    #[test]
+    fn test_calendar() {
+        let now = Local::now();
+        let mut calendar = Calendar::new(Fixed(Duration::from_secs(30)));
+        // a kickoff within the activation window keeps the inner policy
+        let soon = sample(GameTime::WillBePlayed(Some((0, 10))));
+        assert_eq!(
+            calendar.next_delay(Some(&soon), now),
+            Duration::from_secs(30)
+        );
+
+        let mut calendar = Calendar::new(Fixed(Duration::from_secs(30)));
+        let later = sample(GameTime::WillBePlayed(Some((2, 0))));
+        let delay = calendar.next_delay(Some(&later), now);
+        assert!(delay > Duration::from_secs(85 * 60) && delay <= Duration::from_secs(91 * 60));
+    }
+}
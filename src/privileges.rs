@@ -0,0 +1,81 @@
+//! Running unprivileged: the ports are bound at the start, everything else runs as the given
+//! user and group.
+
+use tracing::info;
+
+/// Octal file mode or mode creation mask like `027` or `0o640`.
+pub fn parse_mode(mode: &str) -> Result<u32, String> {
+    u32::from_str_radix(mode.trim_start_matches("0o"), 8)
+        .ok()
+        .filter(|mode| *mode <= 0o7777)
+        .ok_or_else(|| format!("{mode} is not an octal file mode"))
+}
+
+/// Mask of the permissions of all files and directories created from now on.
+#[cfg(unix)]
+pub fn set_umask(mask: u32) {
+    use nix::sys::stat::{umask, Mode};
+    umask(Mode::from_bits_truncate(mask as nix::libc::mode_t));
+}
+
+#[cfg(not(unix))]
+pub fn set_umask(_mask: u32) {}
+
+/// Switch the whole process to the user and the group (the primary group of the user by
+/// default) for good; needs to be started as root.
+#[cfg(unix)]
+pub fn drop_to(user: Option<&str>, group: Option<&str>) -> anyhow::Result<()> {
+    use anyhow::Context;
+    use nix::unistd::{setgid, setgroups, setuid, Group, User};
+
+    let user = user
+        .map(|name| User::from_name(name)?.with_context(|| format!("unknown user {name}")))
+        .transpose()?;
+    let gid = match group {
+        Some(name) => Some(
+            Group::from_name(name)?
+                .with_context(|| format!("unknown group {name}"))?
+                .gid,
+        ),
+        None => user.as_ref().map(|user| user.gid),
+    };
+
+    // the group first, an unprivileged user could not change it anymore
+    if let Some(gid) = gid {
+        setgroups(&[gid]).context("cannot drop the supplementary groups")?;
+        setgid(gid).with_context(|| format!("cannot switch to group {gid}"))?;
+    }
+    if let Some(user) = &user {
+        setuid(user.uid).with_context(|| format!("cannot switch to user {}", user.name))?;
+    }
+    if user.is_some() || gid.is_some() {
+        info!(
+            "running as uid {} gid {}",
+            nix::unistd::getuid(),
+            nix::unistd::getgid()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+pub fn drop_to(user: Option<&str>, group: Option<&str>) -> anyhow::Result<()> {
+    anyhow::ensure!(
+        user.is_none() && group.is_none(),
+        "--user and --group are supported on Unix only"
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_mode() {
+        assert_eq!(parse_mode("027"), Ok(0o027));
+        assert_eq!(parse_mode("0o640"), Ok(0o640));
+        assert!(parse_mode("0o8").is_err());
+        assert!(parse_mode("17777").is_err());
+    }
+}
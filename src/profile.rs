@@ -0,0 +1,146 @@
+//! CPU and memory used by the crawler and by the browser (chromedriver with the Chrome
+//! processes it started), to right-size small machines like a Raspberry Pi.
+
+use std::collections::HashSet;
+use std::fmt;
+use std::time::{Duration, Instant};
+use sysinfo::{Pid, ProcessesToUpdate, System};
+use tracing::info;
+
+/// CPU usage in percent of one core and resident memory in bytes.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Usage {
+    pub cpu: f32,
+    pub rss: u64,
+}
+
+impl Usage {
+    fn max(self, other: Usage) -> Usage {
+        Usage {
+            cpu: self.cpu.max(other.cpu),
+            rss: self.rss.max(other.rss),
+        }
+    }
+}
+
+impl fmt::Display for Usage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:.1} % CPU, {} MB RSS", self.cpu, self.rss / 1_000_000)
+    }
+}
+
+pub struct Profiler {
+    system: System,
+    pid: Pid,
+    interval: Duration,
+    last_log: Option<Instant>,
+    peak_crawler: Usage,
+    peak_browser: Usage,
+}
+
+impl Profiler {
+    /// Log the usage at most once per interval.
+    pub fn new(interval: Duration) -> anyhow::Result<Self> {
+        Ok(Self {
+            system: System::new(),
+            pid: sysinfo::get_current_pid().map_err(anyhow::Error::msg)?,
+            interval,
+            last_log: None,
+            peak_crawler: Usage::default(),
+            peak_browser: Usage::default(),
+        })
+    }
+
+    /// Measure the crawler and the process trees of the running chromedrivers.
+    pub fn sample(&mut self, drivers: &[u32]) -> (Usage, Usage) {
+        // the browser of the last sample may be gone, do not count it again
+        self.system.refresh_processes(ProcessesToUpdate::All, true);
+        let processes = self.system.processes();
+        let usage = |pids: &[Pid]| {
+            pids.iter().filter_map(|pid| processes.get(pid)).fold(
+                Usage::default(),
+                |usage, process| Usage {
+                    cpu: usage.cpu + process.cpu_usage(),
+                    rss: usage.rss + process.memory(),
+                },
+            )
+        };
+        let crawler = usage(&[self.pid]);
+        let browser = drivers
+            .iter()
+            .map(|driver| {
+                let tree = descendants(
+                    processes
+                        .iter()
+                        .map(|(pid, process)| (pid.as_u32(), process.parent().map(Pid::as_u32))),
+                    *driver,
+                );
+                usage(&tree.into_iter().map(Pid::from_u32).collect::<Vec<_>>())
+            })
+            .fold(Usage::default(), |total, usage| Usage {
+                cpu: total.cpu + usage.cpu,
+                rss: total.rss + usage.rss,
+            });
+        self.peak_crawler = self.peak_crawler.max(crawler);
+        self.peak_browser = self.peak_browser.max(browser);
+
+        if self
+            .last_log
+            .is_none_or(|last_log| last_log.elapsed() >= self.interval)
+        {
+            info!("crawler uses {crawler}, browser uses {browser}");
+            self.last_log = Some(Instant::now());
+        }
+        (crawler, browser)
+    }
+
+    /// Peak usage since the start, for the report at the exit.
+    pub fn report(&self) -> String {
+        format!(
+            "peak usage of the crawler {}, of the browser {}",
+            self.peak_crawler, self.peak_browser
+        )
+    }
+}
+
+/// The root process with all its children, grandchildren, ... given (pid, parent) pairs.
+fn descendants(processes: impl Iterator<Item = (u32, Option<u32>)>, root: u32) -> Vec<u32> {
+    let processes: Vec<_> = processes.collect();
+    let mut tree = HashSet::from([root]);
+    loop {
+        let size = tree.len();
+        for (pid, parent) in &processes {
+            if parent.is_some_and(|parent| tree.contains(&parent)) {
+                tree.insert(*pid);
+            }
+        }
+        if tree.len() == size {
+            break;
+        }
+    }
+    let mut tree: Vec<_> = tree.into_iter().collect();
+    tree.sort();
+    tree
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_descendants() {
+        let processes = [
+            (1, None),
+            (100, Some(1)),
+            (120, Some(300)),
+            (300, Some(100)),
+            (301, Some(100)),
+            (400, Some(1)),
+        ];
+        assert_eq!(
+            descendants(processes.into_iter(), 100),
+            [100, 120, 300, 301]
+        );
+        assert_eq!(descendants(processes.into_iter(), 400), [400]);
+    }
+}
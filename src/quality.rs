@@ -0,0 +1,48 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Bumped whenever the selectors or the parsing of the match row change.
+pub const PARSER_VERSION: u32 = 1;
+
+/// Where the value of a field comes from.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Source {
+    /// Read from the primary selector and parsed.
+    Primary,
+    /// Derived from a fallback, e.g. a break assumed from a missing clock.
+    Fallback,
+    /// Nothing usable on the page, a default value is used.
+    Default,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Quality {
+    pub parser_version: u32,
+    pub fields: BTreeMap<String, Source>,
+}
+
+impl Default for Quality {
+    fn default() -> Self {
+        Self {
+            parser_version: PARSER_VERSION,
+            fields: BTreeMap::new(),
+        }
+    }
+}
+
+impl Quality {
+    pub fn record(&mut self, field: &str, source: Source) {
+        self.fields.insert(field.to_string(), source);
+    }
+
+    /// Primary if the value parsed, default otherwise.
+    pub fn record_parsed<T>(&mut self, field: &str, value: Option<T>) {
+        let source = if value.is_some() {
+            Source::Primary
+        } else {
+            Source::Default
+        };
+        self.record(field, source);
+    }
+}
@@ -0,0 +1,74 @@
+use anyhow::Context;
+use chrono::{DateTime, Local};
+use serde::Deserialize;
+use std::fs::{self, File};
+use std::path::Path;
+use tokio_util::sync::CancellationToken;
+use tracing::info;
+
+use crate::pipeline::Pipeline;
+use crate::GameResult;
+
+#[derive(Deserialize)]
+struct Archive {
+    samples: Vec<GameResult>,
+}
+
+/// Samples of a recorded match: an archive written with --archive-dir or a directory of output snapshots.
+pub fn load(path: &Path) -> anyhow::Result<Vec<GameResult>> {
+    if !path.is_dir() {
+        let archive: Archive = serde_json::from_reader(File::open(path)?)
+            .with_context(|| format!("{} is not a match archive", path.display()))?;
+        return Ok(archive.samples);
+    }
+
+    let mut files: Vec<_> = fs::read_dir(path)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<_, _>>()?;
+    files.retain(|file| {
+        file.extension()
+            .is_some_and(|extension| extension == "json")
+    });
+    files.sort();
+
+    files
+        .iter()
+        .map(|file| -> anyhow::Result<GameResult> {
+            serde_json::from_reader(File::open(file)?)
+                .with_context(|| format!("{} is not a result snapshot", file.display()))
+        })
+        .collect()
+}
+
+/// Publish the samples with their original spacing divided by the speed factor.
+pub async fn replay(
+    samples: Vec<GameResult>,
+    pipeline: &mut Pipeline,
+    speed: f64,
+    shutdown: &CancellationToken,
+) -> anyhow::Result<()> {
+    anyhow::ensure!(speed > 0.0, "replay speed must be positive");
+
+    let mut previous_generated: Option<DateTime<Local>> = None;
+    for sample in samples {
+        if let Some(previous_generated) = previous_generated {
+            let delay = (sample.generated - previous_generated)
+                .to_std()
+                .unwrap_or_default()
+                .div_f64(speed);
+            tokio::select! {
+                _ = shutdown.cancelled() => {
+                    info!("replay interrupted");
+                    return Ok(());
+                },
+                _ = tokio::time::sleep(delay) => {}
+            }
+        }
+        previous_generated = Some(sample.generated);
+
+        info!("replayed match = {sample:?}");
+        pipeline.publish(sample).await?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,81 @@
+//! Error reporting to Sentry; without the `sentry` feature only the DSN option is rejected.
+
+#[cfg(not(feature = "sentry"))]
+pub use self::disabled::*;
+#[cfg(feature = "sentry")]
+pub use self::enabled::*;
+
+#[cfg(feature = "sentry")]
+mod enabled {
+    use anyhow::Context;
+    use sentry::{ClientInitGuard, ClientOptions, Level};
+    use std::process::ExitStatus;
+    use url::Url;
+
+    /// Initialize the Sentry client; all the reporting functions are no-op without it.
+    pub fn init(dsn: Option<&str>) -> anyhow::Result<Option<ClientInitGuard>> {
+        let Some(dsn) = dsn else {
+            return Ok(None);
+        };
+
+        let guard = sentry::init(ClientOptions {
+            dsn: Some(dsn.parse().context("invalid Sentry DSN")?),
+            release: sentry::release_name!(),
+            ..Default::default()
+        });
+        Ok(Some(guard))
+    }
+
+    pub fn report_scrape_error(error: &anyhow::Error, url: &Url, iteration: u64) {
+        sentry::with_scope(
+            |scope| {
+                scope.set_tag("url", url);
+                scope.set_extra("iteration", iteration.into());
+            },
+            || sentry::integrations::anyhow::capture_anyhow(error),
+        );
+    }
+
+    pub fn report_driver_exit(status: ExitStatus) {
+        sentry::capture_message(&format!("chromedriver exited: {status}"), Level::Error);
+    }
+
+    pub fn report_layout_change(url: &Url, change: f64) {
+        sentry::with_scope(
+            |scope| {
+                scope.set_tag("url", url);
+                scope.set_extra("change", change.into());
+            },
+            || sentry::capture_message("layout of the match row changed", Level::Warning),
+        );
+    }
+
+    pub fn report_alert(message: &str, url: &Url) {
+        sentry::with_scope(
+            |scope| scope.set_tag("url", url),
+            || sentry::capture_message(message, Level::Warning),
+        );
+    }
+}
+
+#[cfg(not(feature = "sentry"))]
+mod disabled {
+    use std::process::ExitStatus;
+    use url::Url;
+
+    pub fn init(dsn: Option<&str>) -> anyhow::Result<Option<()>> {
+        anyhow::ensure!(
+            dsn.is_none(),
+            "Sentry DSN given but built without the sentry feature"
+        );
+        Ok(None)
+    }
+
+    pub fn report_scrape_error(_error: &anyhow::Error, _url: &Url, _iteration: u64) {}
+
+    pub fn report_driver_exit(_status: ExitStatus) {}
+
+    pub fn report_layout_change(_url: &Url, _change: f64) {}
+
+    pub fn report_alert(_message: &str, _url: &Url) {}
+}
@@ -0,0 +1,121 @@
+//! Finished matches of the season read from the results page of a team, to backfill the history
+//! that started mid-season.
+
+use chrono::{DateTime, Datelike, Local, NaiveDate, NaiveDateTime, TimeZone};
+use chrono_tz::Tz;
+use fantoccini::{Client, Locator};
+use std::time::Duration;
+use tokio::time::sleep;
+use tracing::warn;
+use url::Url;
+
+use crate::{budget, paging, parse_datetime, GameResult, MatchRow, RawRow};
+
+const FINISHED_SELECTOR: &str =
+    ".event__match:not(.event__match--scheduled):not(.event__match--live)";
+
+/// All finished matches of the team on its results page (e.g. `.../tym/.../vysledky/`), the
+/// oldest first, optionally only those played since the date.
+pub async fn get_results(
+    client: &mut Client,
+    url: &Url,
+    team_name: &str,
+    since: Option<NaiveDate>,
+    timezone: Option<Tz>,
+) -> anyhow::Result<Vec<GameResult>> {
+    budget::goto(client, url.as_str()).await?;
+    sleep(Duration::from_millis(500)).await;
+
+    for _ in 1..paging::MAX_PAGES {
+        if let Some(since) = since {
+            if oldest_kickoff(client)
+                .await
+                .is_some_and(|oldest| oldest.date() < since)
+            {
+                break;
+            }
+        }
+        if !paging::show_more(client).await? {
+            break;
+        }
+    }
+
+    let mut results = Vec::new();
+    for row in client.find_all(Locator::Css(FINISHED_SELECTOR)).await? {
+        let raw = RawRow::read(&row).await?;
+        let kickoff = raw.event_time.as_deref().map(kickoff_of).transpose()?;
+        if let (Some(since), Some(kickoff)) = (since, kickoff) {
+            if kickoff.date() < since {
+                continue;
+            }
+        }
+        let match_row = match MatchRow::parse(&raw, timezone) {
+            Ok(match_row) => match_row,
+            Err(error) => {
+                warn!("skipping a result that cannot be read: {error}");
+                continue;
+            }
+        };
+        if !match_row.home_team.starts_with(team_name)
+            && !match_row.away_team.starts_with(team_name)
+        {
+            continue;
+        }
+        let is_home = match_row.is_home(team_name);
+        let mut result = match_row.into_result(is_home);
+        if let Some(kickoff) = kickoff.and_then(|kickoff| local_time(kickoff, timezone)) {
+            result.generated = kickoff;
+            result.started_at = Some(kickoff);
+        }
+        result.derive_perspective();
+        results.push(result);
+    }
+    // the page lists the latest match first
+    results.reverse();
+    Ok(results)
+}
+
+async fn oldest_kickoff(client: &mut Client) -> Option<NaiveDateTime> {
+    let rows = client
+        .find_all(Locator::Css(FINISHED_SELECTOR))
+        .await
+        .ok()?;
+    let raw = RawRow::read(rows.last()?).await.ok()?;
+    kickoff_of(raw.event_time.as_deref()?).ok()
+}
+
+/// Kickoff of a finished match, the rows of this year show no year.
+fn kickoff_of(event_time: &str) -> anyhow::Result<NaiveDateTime> {
+    let kickoff = parse_datetime(event_time)?;
+    if kickoff > Local::now().naive_local() {
+        if let Some(last_year) = kickoff.with_year(kickoff.year() - 1) {
+            return Ok(last_year);
+        }
+    }
+    Ok(kickoff)
+}
+
+fn local_time(kickoff: NaiveDateTime, timezone: Option<Tz>) -> Option<DateTime<Local>> {
+    match timezone {
+        Some(timezone) => timezone
+            .from_local_datetime(&kickoff)
+            .earliest()
+            .map(|kickoff| kickoff.with_timezone(&Local)),
+        None => Local.from_local_datetime(&kickoff).earliest(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kickoff_of() {
+        let kickoff = kickoff_of("02.03.2024 17:30").unwrap();
+        assert_eq!(kickoff.to_string(), "2024-03-02 17:30:00");
+
+        let tomorrow = Local::now().naive_local() + chrono::Duration::days(1);
+        let kickoff = kickoff_of(&tomorrow.format("%d.%m. %H:%M").to_string()).unwrap();
+        assert_eq!(kickoff.year(), tomorrow.year() - 1);
+    }
+}
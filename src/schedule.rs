@@ -0,0 +1,64 @@
+use chrono::{DateTime, TimeZone};
+use serde::Deserialize;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Cron expression (`min hour day month weekday`, optionally with leading seconds) of scrape times.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(try_from = "String")]
+pub struct Expression(cron::Schedule);
+
+impl FromStr for Expression {
+    type Err = cron::error::Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim();
+        let value = if value.split_whitespace().count() == 5 {
+            format!("0 {value}")
+        } else {
+            value.to_string()
+        };
+        cron::Schedule::from_str(&value).map(Self)
+    }
+}
+
+impl TryFrom<String> for Expression {
+    type Error = cron::error::Error;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+/// Time until the earliest upcoming scrape of any of the expressions.
+pub fn delay<Z: TimeZone>(expressions: &[Expression], now: &DateTime<Z>) -> Option<Duration> {
+    expressions
+        .iter()
+        .filter_map(|expression| expression.0.after(now).next())
+        .min()
+        .map(|next| (next - now.clone()).to_std().unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    #[test]
+    fn test_delay() {
+        let expressions: Vec<Expression> = ["* 17-21 * * Fri,Sat,Sun", "0 * * * *"]
+            .iter()
+            .map(|value| value.parse().unwrap())
+            .collect();
+
+        // Saturday evening: every minute.
+        let now = Utc.with_ymd_and_hms(2024, 10, 5, 18, 30, 10).unwrap();
+        assert_eq!(delay(&expressions, &now), Some(Duration::from_secs(50)));
+
+        // Monday evening: hourly.
+        let now = Utc.with_ymd_and_hms(2024, 10, 7, 18, 30, 0).unwrap();
+        assert_eq!(delay(&expressions, &now), Some(Duration::from_secs(1800)));
+
+        assert!("* * *".parse::<Expression>().is_err());
+    }
+}
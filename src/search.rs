@@ -0,0 +1,81 @@
+use anyhow::Context;
+use fantoccini::{Client, Locator};
+use std::time::Duration;
+use tokio::time::sleep;
+use url::Url;
+
+use crate::budget;
+
+/// Team found by the site search.
+pub struct Candidate {
+    pub name: String,
+    pub url: Url,
+}
+
+/// Type the team name into the site search and collect the offered team pages.
+pub async fn find_team(
+    client: &mut Client,
+    site: &Url,
+    name: &str,
+) -> anyhow::Result<Vec<Candidate>> {
+    budget::goto(client, site.as_str()).await?;
+    sleep(Duration::from_millis(500)).await;
+
+    client
+        .find(Locator::Css("#search-window"))
+        .await
+        .context("could not find search button")?
+        .click()
+        .await?;
+    client
+        .find(Locator::Css(".searchInput__input"))
+        .await
+        .context("could not find search input")?
+        .send_keys(name)
+        .await?;
+    sleep(Duration::from_millis(1000)).await;
+
+    let mut candidates = Vec::new();
+    for result in client.find_all(Locator::Css("a.searchResult")).await? {
+        let Some(href) = result.attr("href").await? else {
+            continue;
+        };
+        let url = site.join(&href)?;
+        // search also offers players and competitions
+        if !is_team_url(&url) {
+            continue;
+        }
+        let name = result
+            .find(Locator::Css(".searchResult__participantName"))
+            .await?
+            .text()
+            .await?;
+        candidates.push(Candidate { name, url });
+    }
+
+    Ok(candidates)
+}
+
+/// Check the team page still belongs to the team of the given name.
+pub async fn verify_team(client: &mut Client, url: &Url, name: &str) -> anyhow::Result<String> {
+    budget::goto(client, url.as_str()).await?;
+    sleep(Duration::from_millis(500)).await;
+
+    let heading = client
+        .find(Locator::Css(".heading__name"))
+        .await
+        .context("could not find team heading")?
+        .text()
+        .await?;
+    anyhow::ensure!(
+        heading.starts_with(name),
+        "{url} belongs to {heading:?}, not to {name:?}"
+    );
+    Ok(heading)
+}
+
+fn is_team_url(url: &Url) -> bool {
+    url.path_segments()
+        .and_then(|mut segments| segments.next())
+        .is_some_and(|segment| segment == "tym" || segment == "team")
+}
@@ -0,0 +1,107 @@
+//! Competition pages of a past season (e.g. `.../extraliga-2024-2025/pavouk/`) moved to the
+//! new one, found through the links of the team page.
+
+use fantoccini::{Client, Locator};
+use std::time::Duration;
+use tokio::time::sleep;
+use url::Url;
+
+use crate::budget;
+
+/// The same page of the competition in the season the team page links to, `None` when the
+/// team page does not link a newer season.
+pub async fn find_new_season(
+    client: &mut Client,
+    team_url: &Url,
+    stale: &Url,
+) -> anyhow::Result<Option<Url>> {
+    budget::goto(client, team_url.as_str()).await?;
+    sleep(Duration::from_millis(500)).await;
+
+    for link in client.find_all(Locator::Css("a[href]")).await? {
+        let Some(href) = link.attr("href").await? else {
+            continue;
+        };
+        if let Some(url) = team_url
+            .join(&href)
+            .ok()
+            .and_then(|link| season_url(stale, &link))
+        {
+            return Ok(Some(url));
+        }
+    }
+    Ok(None)
+}
+
+/// The stale page moved to the season of the link, if the link leads to another season of
+/// the same competition.
+fn season_url(stale: &Url, link: &Url) -> Option<Url> {
+    if stale.host_str() != link.host_str() {
+        return None;
+    }
+    let stale_segments: Vec<_> = stale.path_segments()?.collect();
+    let link_segments: Vec<_> = link.path_segments()?.collect();
+    let index = stale_segments
+        .iter()
+        .position(|segment| competition(segment) != *segment)?;
+    let season = link_segments.get(index)?;
+    if stale_segments[..index] != link_segments[..index]
+        || competition(season) != competition(stale_segments[index])
+        || *season == stale_segments[index]
+    {
+        return None;
+    }
+
+    let mut url = stale.clone();
+    url.path_segments_mut()
+        .ok()?
+        .clear()
+        .extend(&stale_segments[..index])
+        .push(season)
+        .extend(&stale_segments[index + 1..]);
+    Some(url)
+}
+
+/// Competition without the season suffix, e.g. `extraliga` of `extraliga-2024-2025`.
+fn competition(segment: &str) -> &str {
+    let mut competition = segment;
+    for _ in 0..2 {
+        match competition.rsplit_once('-') {
+            Some((rest, year)) if year.len() == 4 && year.bytes().all(|b| b.is_ascii_digit()) => {
+                competition = rest
+            }
+            _ => break,
+        }
+    }
+    competition
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_season_url() {
+        let stale =
+            Url::parse("https://www.livesport.cz/hokej/cesko/extraliga-2024-2025/pavouk/").unwrap();
+        let url = |path: &str| Url::parse(&format!("https://www.livesport.cz{path}")).unwrap();
+
+        assert_eq!(
+            season_url(&stale, &url("/hokej/cesko/extraliga/")),
+            Some(url("/hokej/cesko/extraliga/pavouk/"))
+        );
+        assert_eq!(
+            season_url(&stale, &url("/hokej/cesko/extraliga-2025-2026/vysledky/")),
+            Some(url("/hokej/cesko/extraliga-2025-2026/pavouk/"))
+        );
+        assert_eq!(
+            season_url(&stale, &url("/hokej/cesko/extraliga-2024-2025/")),
+            None
+        );
+        assert_eq!(season_url(&stale, &url("/hokej/cesko/1-liga/")), None);
+        assert_eq!(season_url(&stale, &url("/fotbal/cesko/extraliga/")), None);
+
+        let current = url("/hokej/cesko/extraliga/pavouk/");
+        assert_eq!(season_url(&current, &url("/hokej/cesko/extraliga/")), None);
+    }
+}
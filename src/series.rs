@@ -0,0 +1,108 @@
+use fantoccini::{Client, Locator};
+use url::Url;
+
+use crate::{bracket, GameResult, GameTime, MatchRow, ScrapeOptions};
+
+/// Wins in the running series: the consecutive latest finished matches against the opponent.
+pub fn count_wins<'a>(
+    results: impl IntoIterator<Item = &'a GameResult>,
+    opponent: &str,
+) -> (u64, u64) {
+    results
+        .into_iter()
+        .filter(|result| matches!(result.game_time, GameTime::Played))
+        .take_while(|result| result.opponent_team == opponent)
+        .fold((0, 0), |(wins, losses), result| {
+            if result.my_team_score > result.opponent_team_score {
+                (wins + 1, losses)
+            } else {
+                (wins, losses + 1)
+            }
+        })
+}
+
+/// Count the series score from the match rows of the currently loaded team page.
+pub async fn get_series_score(
+    client: &mut Client,
+    team_name: &str,
+    opponent: &str,
+    options: &ScrapeOptions,
+) -> anyhow::Result<(u64, u64)> {
+    let mut results = Vec::new();
+    for element in client.find_all(Locator::Css(".event__match")).await? {
+        let row = MatchRow::read(&element, options.timezone).await?;
+        let is_home = row.is_home(team_name);
+        results.push(row.into_result(is_home));
+    }
+    Ok(count_wins(&results, opponent))
+}
+
+/// Look the series of the team up in the playoff bracket.
+pub async fn get_bracket_score(
+    client: &mut Client,
+    bracket_url: &Url,
+    team_name: &str,
+) -> anyhow::Result<(u64, u64)> {
+    bracket::get_bracket(client, bracket_url)
+        .await?
+        .iter()
+        .rev()
+        .flat_map(|round| &round.series)
+        .find_map(|series| series.score_for(team_name))
+        .ok_or_else(|| anyhow::anyhow!("{team_name} is not in the bracket {bracket_url}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Periods;
+    use chrono::Local;
+
+    fn result(opponent_team: &str, score: (u64, u64), game_time: GameTime) -> GameResult {
+        GameResult {
+            my_team: "Sparta Praha".to_string(),
+            my_team_score: score.0,
+            opponent_team: opponent_team.to_string(),
+            opponent_team_score: score.1,
+            period_scores: Vec::new(),
+            goals: Vec::new(),
+            penalties: Vec::new(),
+            stats: Vec::new(),
+            broadcast: Vec::new(),
+            opponent_form: None,
+            series_score: None,
+            series_best_of: None,
+            timeline: Vec::new(),
+            estimated_time_remaining: None,
+            estimated_end_at: None,
+            started_at: None,
+            elapsed_minutes: None,
+            last_goal_at: None,
+            minutes_since_last_goal: None,
+            outcome: None,
+            goal_difference: None,
+            is_leading: None,
+            clock: None,
+            quality: None,
+            stale_since: None,
+            served_by: None,
+            game_time,
+            periods: Periods::HOCKEY,
+            generated: Local::now(),
+        }
+    }
+
+    #[test]
+    fn test_count_wins() {
+        let results = [
+            result("Kometa Brno", (0, 0), GameTime::WillBePlayed(None)),
+            result("Kometa Brno", (3, 2), GameTime::Played),
+            result("Kometa Brno", (1, 4), GameTime::Played),
+            result("Kometa Brno", (2, 1), GameTime::Played),
+            result("HC Olomouc", (5, 0), GameTime::Played),
+            result("Kometa Brno", (0, 1), GameTime::Played),
+        ];
+        assert_eq!(count_wins(&results, "Kometa Brno"), (2, 1));
+        assert_eq!(count_wins(&results, "HC Olomouc"), (0, 0));
+    }
+}
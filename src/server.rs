@@ -0,0 +1,872 @@
+//! Built-in HTTP server answering `GET /score` and `GET /score/<team>` with the latest result,
+//! pushing every changed result to WebSocket clients of `/ws` and `/ws/<team>` and streaming
+//! `score`, `goal` and `status-change` Server-Sent Events on `/events` and `/events/<team>`;
+//! `/heartbeat` tells a standby instance when the latest result was scraped.
+//!
+//! With a control token, `POST /teams` with the JSON of a team (the keys of a `[[teams]]`
+//! entry of the config file) starts tracking it and `DELETE /teams/<team>` (the key used by
+//! `--only`) stops it; both need the `Authorization: Bearer <token>` header. So do `GET /teams`
+//! listing the teams with their latest results and `POST /teams/<team>/pause`, `/resume` and
+//! `/refresh`; `/admin` is a page calling them.
+//!
+//! Once a team has a read token, issued by `POST /teams/<team>/token`, the results are no longer
+//! public: the token of a team opens its `/score/<team>`, `/ws/<team>` and `/events/<team>`
+//! only, the control token all of them. Browsers not able to send the header pass the token
+//! as `?token=`.
+
+use chrono::{DateTime, Local};
+use futures_util::{SinkExt, StreamExt};
+use percent_encoding::percent_decode_str;
+use std::borrow::Cow;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::sync::{mpsc, oneshot, Notify};
+use tokio_tungstenite::tungstenite::handshake::derive_accept_key;
+use tokio_tungstenite::tungstenite::protocol::Role;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::WebSocketStream;
+use tracing::debug;
+
+use crate::events::{TimelineEvent, TimelineKind};
+use crate::observer::Observer;
+use crate::teams::Team;
+use crate::GameResult;
+
+/// Changed results waiting for slow WebSocket clients before they are dropped.
+const PUSH_BACKLOG: usize = 64;
+/// Largest request line and headers accepted.
+const MAX_HEAD_BYTES: usize = 8 * 1024;
+/// Time a client gets to send the request line and headers, and then the body.
+const HEAD_TIMEOUT: Duration = Duration::from_secs(10);
+/// Largest body of a control request.
+const MAX_BODY_BYTES: usize = 64 * 1024;
+/// Control commands waiting for the crawl loop.
+const COMMAND_BACKLOG: usize = 16;
+/// Page of `/admin` managing the tracked teams over the control API.
+const ADMIN_PAGE: &str = include_str!("admin.html");
+
+/// Latest result of one team, written by its pipeline.
+pub type Slot = Arc<Mutex<Option<GameResult>>>;
+
+/// Changed result or match event of a team, serialized to JSON.
+#[derive(Clone)]
+struct Update {
+    team_name: String,
+    /// `score` for results, `goal` or `status-change` for events
+    event: &'static str,
+    data: String,
+}
+
+/// Change of the tracked teams asked for over the control API.
+pub enum Command {
+    /// Track the team and add it to the config file
+    AddTeam(Box<Team>),
+    /// Stop tracking the team of the key and remove it from the config file
+    RemoveTeam(String),
+    /// List the tracked teams with their latest results
+    Teams,
+    /// Stop scraping the team until resumed, while still serving its last result
+    Pause(String),
+    Resume(String),
+    /// Scrape the team right away
+    Refresh(String),
+    /// Issue a new read token of the team, revoking its previous one
+    IssueToken(String),
+}
+
+/// How the crawl loop handled a command.
+pub enum Outcome {
+    /// Applied, with the JSON answer
+    Done(serde_json::Value),
+    NotFound,
+    /// Not applicable to the tracked teams, e.g. a team tracked already
+    Rejected(String),
+    Failed(String),
+}
+
+/// Command with the channel its outcome goes back on.
+pub type Request = (Command, oneshot::Sender<Outcome>);
+
+#[derive(Clone)]
+struct Control {
+    token: String,
+    requests: mpsc::Sender<Request>,
+    /// Woken for every sent command, the crawl loop applies it right away
+    wake: Arc<Notify>,
+}
+
+/// Latest results of all tracked teams, in the order they were given.
+#[derive(Clone)]
+pub struct Scoreboard {
+    teams: Arc<Mutex<Vec<(String, Slot)>>>,
+    /// Read tokens by team name
+    tokens: Arc<Mutex<Vec<(String, String)>>>,
+    updates: broadcast::Sender<Update>,
+    control: Option<Control>,
+}
+
+impl Default for Scoreboard {
+    fn default() -> Self {
+        Self {
+            teams: Arc::default(),
+            tokens: Arc::default(),
+            updates: broadcast::channel(PUSH_BACKLOG).0,
+            control: None,
+        }
+    }
+}
+
+impl Scoreboard {
+    /// Register a team and return where its results are to be stored.
+    pub fn add(&self, team_name: &str) -> Slot {
+        let slot = Slot::default();
+        self.teams
+            .lock()
+            .unwrap()
+            .push((team_name.to_string(), slot.clone()));
+        slot
+    }
+
+    /// Stop serving the results of the team.
+    pub fn remove(&self, team_name: &str) {
+        self.teams
+            .lock()
+            .unwrap()
+            .retain(|(name, _)| name != team_name);
+        self.tokens
+            .lock()
+            .unwrap()
+            .retain(|(name, _)| name != team_name);
+    }
+
+    /// Give the token read access to the results of the team, instead of its previous token.
+    pub fn grant(&self, team_name: &str, token: String) {
+        let mut tokens = self.tokens.lock().unwrap();
+        tokens.retain(|(name, _)| name != team_name);
+        tokens.push((team_name.to_string(), token));
+    }
+
+    /// Whether the token may read the results of the team, of all teams without one.
+    fn authorized(&self, token: Option<&str>, team_name: Option<&str>) -> bool {
+        let tokens = self.tokens.lock().unwrap();
+        if tokens.is_empty() {
+            return true;
+        }
+        let Some(token) = token else {
+            return false;
+        };
+        self.control
+            .as_ref()
+            .is_some_and(|control| control.token == token)
+            || team_name.is_some_and(|team_name| {
+                tokens
+                    .iter()
+                    .any(|(name, granted)| name.eq_ignore_ascii_case(team_name) && granted == token)
+            })
+    }
+
+    /// Accept the control API with the bearer token; the commands come out of the receiver,
+    /// each followed by a `wake` notification.
+    pub fn control(&mut self, token: String, wake: Arc<Notify>) -> mpsc::Receiver<Request> {
+        let (requests, receiver) = mpsc::channel(COMMAND_BACKLOG);
+        self.control = Some(Control {
+            token,
+            requests,
+            wake,
+        });
+        receiver
+    }
+
+    /// Observer pushing the changed results of the team to the WebSocket clients.
+    pub fn pusher(&self, team_name: &str) -> Pusher {
+        Pusher {
+            team_name: team_name.to_string(),
+            updates: self.updates.clone(),
+        }
+    }
+
+    /// When the most recent of the latest results was scraped; a stale result does not count.
+    fn last_scraped(&self) -> Option<DateTime<Local>> {
+        self.teams
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|(_, slot)| {
+                slot.lock()
+                    .unwrap()
+                    .as_ref()
+                    .filter(|result| result.stale_since.is_none())
+                    .map(|result| result.generated)
+            })
+            .max()
+    }
+
+    /// Latest result of the team, or of the first team when none is given.
+    /// Latest result of the team, if scraped already.
+    pub fn result(&self, team_name: &str) -> Option<GameResult> {
+        self.get(Some(team_name))?.lock().unwrap().clone()
+    }
+
+    fn get(&self, team_name: Option<&str>) -> Option<Slot> {
+        let teams = self.teams.lock().unwrap();
+        match team_name {
+            Some(team_name) => teams
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(team_name)),
+            None => teams.first(),
+        }
+        .map(|(_, slot)| slot.clone())
+    }
+}
+
+pub struct Pusher {
+    team_name: String,
+    updates: broadcast::Sender<Update>,
+}
+
+impl Pusher {
+    fn send(&self, event: &'static str, data: serde_json::Result<String>) {
+        match data {
+            // sending fails only when no client is connected
+            Ok(data) => {
+                let _ = self.updates.send(Update {
+                    team_name: self.team_name.clone(),
+                    event,
+                    data,
+                });
+            }
+            Err(error) => debug!("could not serialize the {event} event: {error}"),
+        }
+    }
+}
+
+impl Observer for Pusher {
+    fn on_change(&mut self, _previous: Option<&GameResult>, latest: &GameResult) {
+        self.send("score", serde_json::to_string(latest));
+    }
+
+    fn on_event(&mut self, event: &TimelineEvent, _latest: &GameResult) {
+        let name = match event.kind {
+            TimelineKind::GoalFor | TimelineKind::GoalAgainst => "goal",
+            TimelineKind::PlayerGoal => "player-goal",
+            TimelineKind::Started
+            | TimelineKind::Break
+            | TimelineKind::Resumed
+            | TimelineKind::Finished => "status-change",
+            TimelineKind::StatisticChanged => return,
+        };
+        self.send(
+            name,
+            serde_json::to_value(event).and_then(|mut data| {
+                data["team"] = self.team_name.clone().into();
+                serde_json::to_string(&data)
+            }),
+        );
+    }
+}
+
+/// Serve the scoreboard on the bound listener until the runtime ends.
+pub fn serve(listener: TcpListener, scoreboard: Scoreboard) -> std::io::Result<SocketAddr> {
+    let address = listener.local_addr()?;
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            let scoreboard = scoreboard.clone();
+            tokio::spawn(async move {
+                if let Err(error) = handle(stream, &scoreboard).await {
+                    debug!("could not answer a score request: {error}");
+                }
+            });
+        }
+    });
+    Ok(address)
+}
+
+/// Request line and headers, `None` when they exceed [`MAX_HEAD_BYTES`].
+async fn read_head(stream: &mut TcpStream) -> std::io::Result<Option<Vec<u8>>> {
+    let mut request = Vec::new();
+    let mut buffer = [0; 1024];
+    // the body of a POST may arrive with the head
+    while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+        let read = stream.read(&mut buffer).await?;
+        if read == 0 {
+            break;
+        }
+        request.extend_from_slice(&buffer[..read]);
+        if request.len() > MAX_HEAD_BYTES {
+            return Ok(None);
+        }
+    }
+    Ok(Some(request))
+}
+
+async fn handle(mut stream: TcpStream, scoreboard: &Scoreboard) -> std::io::Result<()> {
+    let Ok(request) = tokio::time::timeout(HEAD_TIMEOUT, read_head(&mut stream)).await else {
+        debug!("client did not send the request in time");
+        return Ok(());
+    };
+    let Some(mut request) = request? else {
+        return stream
+            .write_all(b"HTTP/1.1 431 Request Header Fields Too Large\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+            .await;
+    };
+
+    let head_length = request
+        .windows(4)
+        .position(|window| window == b"\r\n\r\n")
+        .map_or(request.len(), |end| end + 4);
+    let body = request.split_off(head_length);
+    let request = String::from_utf8_lossy(&request);
+    let mut request_line = request
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .split_whitespace();
+    let method = request_line.next().unwrap_or_default();
+    let target = request_line.next().unwrap_or("/");
+    let path = target.split(['?', '#']).next().unwrap_or_default();
+
+    if let Some(team_name) = route(path, "/score")
+        .or_else(|| route(path, "/ws"))
+        .or_else(|| route(path, "/events"))
+    {
+        if !scoreboard.authorized(token(&request, target).as_deref(), team_name.as_deref()) {
+            return respond(
+                &mut stream,
+                "401 Unauthorized",
+                "missing or wrong token".to_string(),
+            )
+            .await;
+        }
+    }
+
+    if let (Some(team_name), Some(key)) =
+        (route(path, "/ws"), header(&request, "sec-websocket-key"))
+    {
+        if method == "GET"
+            && (team_name.is_none() || scoreboard.get(team_name.as_deref()).is_some())
+        {
+            stream
+                .write_all(
+                    format!(
+                        "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: {}\r\n\r\n",
+                        derive_accept_key(key.as_bytes())
+                    )
+                    .as_bytes(),
+                )
+                .await?;
+            let socket = WebSocketStream::from_raw_socket(stream, Role::Server, None).await;
+            return push(socket, scoreboard, team_name.as_deref()).await;
+        }
+    }
+
+    if let Some(team_name) = route(path, "/events").filter(|_| method == "GET") {
+        if team_name.is_none() || scoreboard.get(team_name.as_deref()).is_some() {
+            return stream_events(stream, scoreboard, team_name.as_deref()).await;
+        }
+    }
+
+    if path.trim_end_matches('/') == "/admin" && method == "GET" && scoreboard.control.is_some() {
+        return stream
+            .write_all(
+                format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{ADMIN_PAGE}",
+                    ADMIN_PAGE.len()
+                )
+                .as_bytes(),
+            )
+            .await;
+    }
+
+    let (status, body) = if let Some(team_key) = route(path, "/teams") {
+        let Some(control) = &scoreboard.control else {
+            return respond(&mut stream, "404 Not Found", "not found".to_string()).await;
+        };
+        if header(&request, "authorization") != Some(&format!("Bearer {}", control.token)) {
+            return respond(
+                &mut stream,
+                "401 Unauthorized",
+                "missing or wrong token".to_string(),
+            )
+            .await;
+        }
+        let body = tokio::time::timeout(HEAD_TIMEOUT, read_body(&mut stream, &request, body));
+        match body.await {
+            Ok(Ok(Some(body))) => command(control, method, team_key, &body).await,
+            Ok(Ok(None)) => ("413 Content Too Large", "request too large".to_string()),
+            Ok(Err(error)) => return Err(error),
+            Err(_) => ("408 Request Timeout", "body not sent in time".to_string()),
+        }
+    } else if method != "GET" {
+        (
+            "405 Method Not Allowed",
+            "only GET is supported".to_string(),
+        )
+    } else if path == "/heartbeat" {
+        match scoreboard.last_scraped() {
+            Some(generated) => ("200 OK", serde_json::to_string(&generated)?),
+            None => ("503 Service Unavailable", "no result yet".to_string()),
+        }
+    } else {
+        match route(path, "/score").map(|team_name| scoreboard.get(team_name.as_deref())) {
+            None | Some(None) => ("404 Not Found", "not found".to_string()),
+            Some(Some(slot)) => match slot.lock().unwrap().as_ref() {
+                Some(result) => ("200 OK", serde_json::to_string(result)?),
+                None => ("503 Service Unavailable", "no result yet".to_string()),
+            },
+        }
+    };
+    respond(&mut stream, status, body).await
+}
+
+/// Answer with the JSON of a successful request, or with the text of the error.
+async fn respond(stream: &mut TcpStream, status: &str, body: String) -> std::io::Result<()> {
+    let content_type = if status.starts_with('2') {
+        "application/json"
+    } else {
+        "text/plain; charset=utf-8"
+    };
+    let authenticate = if status.starts_with("401") {
+        "WWW-Authenticate: Bearer\r\n"
+    } else {
+        ""
+    };
+
+    stream
+        .write_all(
+            format!(
+                "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\n{authenticate}Connection: close\r\n\r\n",
+                body.len()
+            )
+            .as_bytes(),
+        )
+        .await?;
+    stream.write_all(body.as_bytes()).await
+}
+
+/// Body of the request following what was read with the head, `None` when it exceeds
+/// [`MAX_BODY_BYTES`].
+async fn read_body(
+    stream: &mut TcpStream,
+    head: &str,
+    mut body: Vec<u8>,
+) -> std::io::Result<Option<Vec<u8>>> {
+    let length = header(head, "content-length")
+        .and_then(|length| length.parse().ok())
+        .unwrap_or(0);
+    if length > MAX_BODY_BYTES {
+        return Ok(None);
+    }
+    let mut buffer = [0; 1024];
+    while body.len() < length {
+        let read = stream.read(&mut buffer).await?;
+        if read == 0 {
+            break;
+        }
+        body.extend_from_slice(&buffer[..read]);
+    }
+    body.truncate(length);
+    Ok(Some(body))
+}
+
+/// Pass the control request to the crawl loop and answer with its outcome.
+async fn command(
+    control: &Control,
+    method: &str,
+    team_key: Option<Cow<'_, str>>,
+    body: &[u8],
+) -> (&'static str, String) {
+    // the key of a team of a club has a slash too, the action is the last segment
+    let action = team_key
+        .as_deref()
+        .and_then(|team_key| team_key.rsplit_once('/'))
+        .filter(|(_, action)| ["pause", "resume", "refresh", "token"].contains(action));
+    let command = match (method, &team_key, action) {
+        ("GET", None, _) => Command::Teams,
+        ("POST", None, _) => match serde_json::from_slice(body) {
+            Ok(team) => Command::AddTeam(Box::new(team)),
+            Err(error) => return ("400 Bad Request", format!("invalid team: {error}")),
+        },
+        ("POST", Some(_), Some((team_key, "pause"))) => Command::Pause(team_key.to_string()),
+        ("POST", Some(_), Some((team_key, "resume"))) => Command::Resume(team_key.to_string()),
+        ("POST", Some(_), Some((team_key, "refresh"))) => Command::Refresh(team_key.to_string()),
+        ("POST", Some(_), Some((team_key, _))) => Command::IssueToken(team_key.to_string()),
+        ("DELETE", Some(team_key), None) => Command::RemoveTeam(team_key.to_string()),
+        _ => {
+            return (
+                "405 Method Not Allowed",
+                "GET or POST /teams, POST /teams/<team>/<action> or DELETE /teams/<team> expected"
+                    .to_string(),
+            )
+        }
+    };
+    let (reply, outcome) = oneshot::channel();
+    if control.requests.send((command, reply)).await.is_err() {
+        return (
+            "503 Service Unavailable",
+            "the crawler is stopping".to_string(),
+        );
+    }
+    control.wake.notify_one();
+    match outcome.await {
+        Ok(Outcome::Done(answer)) => ("200 OK", answer.to_string()),
+        Ok(Outcome::NotFound) => ("404 Not Found", "no such team".to_string()),
+        Ok(Outcome::Rejected(reason)) => ("409 Conflict", reason),
+        Ok(Outcome::Failed(reason)) => ("500 Internal Server Error", reason),
+        Err(_) => (
+            "503 Service Unavailable",
+            "the crawler is stopping".to_string(),
+        ),
+    }
+}
+
+/// Team of a `prefix` or `prefix/<team>` path, `None` when the path is something else.
+fn route<'a>(path: &'a str, prefix: &str) -> Option<Option<Cow<'a, str>>> {
+    match path.trim_end_matches('/').strip_prefix(prefix)? {
+        "" => Some(None),
+        rest => rest
+            .strip_prefix('/')
+            .map(|team_name| Some(percent_decode_str(team_name).decode_utf8_lossy())),
+    }
+}
+
+/// Bearer token of the request, or its `token` query parameter.
+fn token<'a>(request: &'a str, target: &'a str) -> Option<Cow<'a, str>> {
+    header(request, "authorization")
+        .and_then(|authorization| authorization.strip_prefix("Bearer "))
+        .map(Cow::Borrowed)
+        .or_else(|| {
+            let query = target.split('#').next()?.split_once('?')?.1;
+            query
+                .split('&')
+                .find_map(|pair| pair.strip_prefix("token="))
+                .map(|token| percent_decode_str(token).decode_utf8_lossy())
+        })
+}
+
+fn header<'a>(request: &'a str, name: &str) -> Option<&'a str> {
+    request.lines().skip(1).find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        key.trim().eq_ignore_ascii_case(name).then(|| value.trim())
+    })
+}
+
+fn wanted(name: &str, team_name: Option<&str>) -> bool {
+    team_name.is_none_or(|team_name| name.eq_ignore_ascii_case(team_name))
+}
+
+/// Latest results of the team, of all teams without one, serialized to JSON.
+fn latest(scoreboard: &Scoreboard, team_name: Option<&str>) -> serde_json::Result<Vec<String>> {
+    scoreboard
+        .teams
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|(name, _)| wanted(name, team_name))
+        .filter_map(|(_, slot)| slot.lock().unwrap().as_ref().map(serde_json::to_string))
+        .collect()
+}
+
+/// Send the latest and then every changed result of the team, of all teams without one.
+async fn push(
+    mut socket: WebSocketStream<TcpStream>,
+    scoreboard: &Scoreboard,
+    team_name: Option<&str>,
+) -> std::io::Result<()> {
+    let mut updates = scoreboard.updates.subscribe();
+    for json in latest(scoreboard, team_name)? {
+        socket
+            .send(Message::Text(json))
+            .await
+            .map_err(std::io::Error::other)?;
+    }
+
+    loop {
+        tokio::select! {
+            update = updates.recv() => match update {
+                Ok(update) if update.event == "score" && wanted(&update.team_name, team_name) => {
+                    socket.send(Message::Text(update.data)).await.map_err(std::io::Error::other)?;
+                }
+                Ok(_) => {}
+                Err(RecvError::Lagged(skipped)) => debug!("WebSocket client missed {skipped} results"),
+                Err(RecvError::Closed) => break,
+            },
+            message = socket.next() => match message {
+                Some(Ok(Message::Close(_)) | Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+    Ok(())
+}
+
+/// Stream the latest results and then every update of the team as Server-Sent Events.
+async fn stream_events(
+    mut stream: TcpStream,
+    scoreboard: &Scoreboard,
+    team_name: Option<&str>,
+) -> std::io::Result<()> {
+    let mut updates = scoreboard.updates.subscribe();
+    stream
+        .write_all(b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\n\r\n")
+        .await?;
+    for data in latest(scoreboard, team_name)? {
+        stream
+            .write_all(format!("event: score\ndata: {data}\n\n").as_bytes())
+            .await?;
+    }
+
+    loop {
+        match updates.recv().await {
+            Ok(update) if wanted(&update.team_name, team_name) => {
+                stream
+                    .write_all(
+                        format!("event: {}\ndata: {}\n\n", update.event, update.data).as_bytes(),
+                    )
+                    .await?;
+            }
+            Ok(_) => {}
+            Err(RecvError::Lagged(skipped)) => {
+                debug!("event stream client missed {skipped} updates")
+            }
+            Err(RecvError::Closed) => break,
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::{sample, Simulation};
+    use crate::GameTime;
+
+    async fn get(address: SocketAddr, path: &str) -> String {
+        let mut stream = TcpStream::connect(address).await.unwrap();
+        stream
+            .write_all(format!("GET {path} HTTP/1.1\r\nHost: localhost\r\n\r\n").as_bytes())
+            .await
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    #[tokio::test]
+    async fn test_serve_scores() {
+        let scoreboard = Scoreboard::default();
+        let sparta = scoreboard.add("Sparta Praha");
+        scoreboard.add("Kometa Brno");
+        let address = serve(TcpListener::bind("127.0.0.1:0").await.unwrap(), scoreboard).unwrap();
+
+        assert!(get(address, "/score")
+            .await
+            .starts_with("HTTP/1.1 503 Service Unavailable"));
+
+        *sparta.lock().unwrap() = Some(sample(GameTime::Playing(12), 1, 0));
+        let response = get(address, "/score").await;
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.contains(r#""my_team":"Sparta Praha""#));
+        assert_eq!(get(address, "/score/sparta%20praha").await, response);
+
+        assert!(get(address, "/score/Kometa%20Brno")
+            .await
+            .starts_with("HTTP/1.1 503 Service Unavailable"));
+        assert!(get(address, "/score/Slavia")
+            .await
+            .starts_with("HTTP/1.1 404 Not Found"));
+        assert!(get(address, "/heartbeat")
+            .await
+            .starts_with("HTTP/1.1 200 OK"));
+    }
+
+    #[tokio::test]
+    async fn test_add_team() {
+        let mut scoreboard = Scoreboard::default();
+        let mut commands = scoreboard.control("secret".to_string(), Arc::new(Notify::new()));
+        let address = serve(TcpListener::bind("127.0.0.1:0").await.unwrap(), scoreboard).unwrap();
+        let body = r#"{"url":"https://www.livesport.cz/tym/sparta-praha/zcG9U7N6/","name":"Sparta Praha","output":"sparta.json"}"#;
+        let post = |authorization: &'static str| async move {
+            let mut stream = TcpStream::connect(address).await.unwrap();
+            stream
+                .write_all(
+                    format!(
+                        "POST /teams HTTP/1.1\r\nHost: localhost\r\n{authorization}Content-Length: {}\r\n\r\n{body}",
+                        body.len()
+                    )
+                    .as_bytes(),
+                )
+                .await
+                .unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).await.unwrap();
+            response
+        };
+
+        assert!(post("").await.starts_with("HTTP/1.1 401 Unauthorized"));
+        let response = tokio::spawn(post("Authorization: Bearer secret\r\n"));
+        let (command, reply) = commands.recv().await.unwrap();
+        assert!(matches!(command, Command::AddTeam(team) if team.name == "Sparta Praha"));
+        assert!(reply
+            .send(Outcome::Done(serde_json::json!({"team": "Sparta Praha"})))
+            .is_ok());
+        assert!(response.await.unwrap().starts_with("HTTP/1.1 200 OK"));
+    }
+
+    #[tokio::test]
+    async fn test_admin() {
+        assert!(get(
+            serve(
+                TcpListener::bind("127.0.0.1:0").await.unwrap(),
+                Scoreboard::default()
+            )
+            .unwrap(),
+            "/admin"
+        )
+        .await
+        .starts_with("HTTP/1.1 404 Not Found"));
+
+        let mut scoreboard = Scoreboard::default();
+        let mut commands = scoreboard.control("secret".to_string(), Arc::new(Notify::new()));
+        let address = serve(TcpListener::bind("127.0.0.1:0").await.unwrap(), scoreboard).unwrap();
+        let page = get(address, "/admin").await;
+        assert!(page.contains("Content-Type: text/html"));
+        assert!(page.contains("/teams/"));
+
+        // the key of a team of a club has a slash too
+        let response = tokio::spawn(async move {
+            let mut stream = TcpStream::connect(address).await.unwrap();
+            stream
+                .write_all(b"POST /teams/hockey/sparta/pause HTTP/1.1\r\nAuthorization: Bearer secret\r\n\r\n")
+                .await
+                .unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).await.unwrap();
+            response
+        });
+        let (command, reply) = commands.recv().await.unwrap();
+        assert!(matches!(command, Command::Pause(key) if key == "hockey/sparta"));
+        assert!(reply.send(Outcome::NotFound).is_ok());
+        assert!(response
+            .await
+            .unwrap()
+            .starts_with("HTTP/1.1 404 Not Found"));
+    }
+
+    #[tokio::test]
+    async fn test_team_tokens() {
+        let mut scoreboard = Scoreboard::default();
+        scoreboard.control("secret".to_string(), Arc::new(Notify::new()));
+        let sparta = scoreboard.add("Sparta Praha");
+        scoreboard.add("Kometa Brno");
+        let address = serve(
+            TcpListener::bind("127.0.0.1:0").await.unwrap(),
+            scoreboard.clone(),
+        )
+        .unwrap();
+        *sparta.lock().unwrap() = Some(sample(GameTime::Playing(12), 1, 0));
+
+        // public until a team has a token
+        assert!(get(address, "/score/Sparta%20Praha")
+            .await
+            .starts_with("HTTP/1.1 200 OK"));
+        scoreboard.grant("Sparta Praha", "coach".to_string());
+        assert!(get(address, "/score/Sparta%20Praha")
+            .await
+            .starts_with("HTTP/1.1 401 Unauthorized"));
+        assert!(get(address, "/score/Sparta%20Praha?token=coach")
+            .await
+            .starts_with("HTTP/1.1 200 OK"));
+        assert!(get(address, "/score/Kometa%20Brno?token=coach")
+            .await
+            .starts_with("HTTP/1.1 401 Unauthorized"));
+        assert!(get(address, "/score?token=coach")
+            .await
+            .starts_with("HTTP/1.1 401 Unauthorized"));
+        assert!(get(address, "/score?token=secret")
+            .await
+            .starts_with("HTTP/1.1 200 OK"));
+        assert!(get(address, "/heartbeat")
+            .await
+            .starts_with("HTTP/1.1 200 OK"));
+    }
+
+    #[tokio::test]
+    async fn test_oversized_request() {
+        let address = serve(
+            TcpListener::bind("127.0.0.1:0").await.unwrap(),
+            Scoreboard::default(),
+        )
+        .unwrap();
+        let mut stream = TcpStream::connect(address).await.unwrap();
+        let header = format!("X-Padding: {}\r\n", "a".repeat(MAX_HEAD_BYTES));
+        stream
+            .write_all(format!("GET /score HTTP/1.1\r\n{header}\r\n").as_bytes())
+            .await
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 431 Request Header Fields Too Large"));
+    }
+
+    #[tokio::test]
+    async fn test_push_scores() {
+        let scoreboard = Scoreboard::default();
+        scoreboard.add("Sparta Praha");
+        scoreboard.add("Kometa Brno");
+        let mut sparta = scoreboard.pusher("Sparta Praha");
+        let mut kometa = scoreboard.pusher("Kometa Brno");
+        let address = serve(TcpListener::bind("127.0.0.1:0").await.unwrap(), scoreboard).unwrap();
+
+        let stream = TcpStream::connect(address).await.unwrap();
+        let (mut socket, _) =
+            tokio_tungstenite::client_async(format!("ws://{address}/ws/Sparta%20Praha"), stream)
+                .await
+                .unwrap();
+        // the client is subscribed once the handshake is answered
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+        kometa.on_change(None, &Simulation::new("Kometa Brno").next_result());
+        sparta.on_change(None, &sample(GameTime::Playing(12), 1, 0));
+        let Some(Ok(Message::Text(json))) = socket.next().await else {
+            panic!("expected a pushed result");
+        };
+        assert!(json.contains(r#""my_team":"Sparta Praha""#));
+    }
+
+    #[tokio::test]
+    async fn test_stream_events() {
+        let scoreboard = Scoreboard::default();
+        scoreboard.add("Sparta Praha");
+        let mut sparta = scoreboard.pusher("Sparta Praha");
+        let address = serve(TcpListener::bind("127.0.0.1:0").await.unwrap(), scoreboard).unwrap();
+
+        let mut stream = TcpStream::connect(address).await.unwrap();
+        stream
+            .write_all(b"GET /events HTTP/1.1\r\nHost: localhost\r\n\r\n")
+            .await
+            .unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+        let previous = sample(GameTime::Playing(12), 1, 0);
+        let latest = sample(GameTime::Playing(13), 2, 0);
+        sparta.on_change(Some(&previous), &latest);
+        for event in crate::events::between(&previous, &latest) {
+            sparta.on_event(&event, &latest);
+        }
+
+        let mut response = String::new();
+        let mut buffer = [0; 1024];
+        while !response.contains("event: goal") {
+            let read = stream.read(&mut buffer).await.unwrap();
+            assert!(read > 0, "the stream ended early: {response}");
+            response.push_str(&String::from_utf8_lossy(&buffer[..read]));
+        }
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.contains("event: score\ndata: {"));
+        assert!(response.contains(r#""team":"Sparta Praha""#));
+    }
+}
@@ -0,0 +1,162 @@
+use chrono::Local;
+use rand::Rng;
+
+use crate::{GameResult, GameTime, Periods, PERIODS, PERIOD_MINUTES};
+
+const MINUTES_PER_STEP: u64 = 2;
+const SCHEDULED_STEPS: u64 = 3;
+const BREAK_STEPS: u64 = 2;
+const FINISHED_STEPS: u64 = 5;
+const GOAL_PROBABILITY: f64 = 0.1;
+
+enum Phase {
+    Scheduled(u64),
+    Playing,
+    Break(u64),
+    Finished(u64),
+}
+
+/// Synthetic match progression: scheduled, three periods with random goals, finished and again.
+pub struct Simulation {
+    my_team: String,
+    opponent_team: String,
+    phase: Phase,
+    minute: u64,
+    period_scores: Vec<(u64, u64)>,
+}
+
+impl Simulation {
+    pub fn new(my_team: &str) -> Self {
+        Self {
+            my_team: my_team.to_string(),
+            opponent_team: "Simulated Opponent".to_string(),
+            phase: Phase::Scheduled(SCHEDULED_STEPS),
+            minute: 0,
+            period_scores: Vec::new(),
+        }
+    }
+
+    pub fn next_result(&mut self) -> GameResult {
+        let mut rng = rand::thread_rng();
+
+        let game_time = match self.phase {
+            Phase::Scheduled(0) => {
+                self.phase = Phase::Playing;
+                self.minute = 1;
+                self.period_scores = vec![(0, 0)];
+                GameTime::Playing(self.minute)
+            }
+            Phase::Scheduled(steps) => {
+                self.phase = Phase::Scheduled(steps - 1);
+                GameTime::WillBePlayed(Some((0, steps * MINUTES_PER_STEP)))
+            }
+            Phase::Playing => {
+                self.minute += MINUTES_PER_STEP;
+                let period = self.period_scores.last_mut().unwrap();
+                if rng.gen_bool(GOAL_PROBABILITY) {
+                    period.0 += 1;
+                }
+                if rng.gen_bool(GOAL_PROBABILITY) {
+                    period.1 += 1;
+                }
+
+                if self.minute >= PERIOD_MINUTES * PERIODS {
+                    self.phase = Phase::Finished(FINISHED_STEPS);
+                    GameTime::Played
+                } else if self.minute >= PERIOD_MINUTES * self.period_scores.len() as u64 {
+                    self.minute = PERIOD_MINUTES * self.period_scores.len() as u64;
+                    self.phase = Phase::Break(BREAK_STEPS);
+                    GameTime::BreakAfter(self.minute)
+                } else {
+                    GameTime::Playing(self.minute)
+                }
+            }
+            Phase::Break(0) => {
+                self.phase = Phase::Playing;
+                self.minute += 1;
+                self.period_scores.push((0, 0));
+                GameTime::Playing(self.minute)
+            }
+            Phase::Break(steps) => {
+                self.phase = Phase::Break(steps - 1);
+                GameTime::BreakAfter(self.minute)
+            }
+            Phase::Finished(0) => {
+                *self = Self::new(&self.my_team);
+                return self.next_result();
+            }
+            Phase::Finished(steps) => {
+                self.phase = Phase::Finished(steps - 1);
+                GameTime::Played
+            }
+        };
+
+        GameResult {
+            my_team: self.my_team.clone(),
+            my_team_score: self.period_scores.iter().map(|period| period.0).sum(),
+            opponent_team: self.opponent_team.clone(),
+            opponent_team_score: self.period_scores.iter().map(|period| period.1).sum(),
+            period_scores: self.period_scores.clone(),
+            goals: Vec::new(),
+            penalties: Vec::new(),
+            stats: Vec::new(),
+            broadcast: Vec::new(),
+            opponent_form: None,
+            series_score: None,
+            series_best_of: None,
+            timeline: Vec::new(),
+            estimated_time_remaining: None,
+            estimated_end_at: None,
+            started_at: None,
+            elapsed_minutes: None,
+            last_goal_at: None,
+            minutes_since_last_goal: None,
+            outcome: None,
+            goal_difference: None,
+            is_leading: None,
+            clock: None,
+            quality: None,
+            stale_since: None,
+            served_by: None,
+            game_time,
+            periods: Periods::HOCKEY,
+            generated: Local::now(),
+        }
+    }
+}
+
+/// Sparta Praha against Kometa Brno at the time and score, the result the tests start from.
+#[cfg(test)]
+pub fn sample(game_time: GameTime, my_team_score: u64, opponent_team_score: u64) -> GameResult {
+    GameResult {
+        opponent_team: "Kometa Brno".to_string(),
+        my_team_score,
+        opponent_team_score,
+        game_time,
+        ..Simulation::new("Sparta Praha").next_result()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_simulation_progress() {
+        let mut simulation = Simulation::new("Sparta Praha");
+        let results: Vec<_> = (0..50).map(|_| simulation.next_result()).collect();
+
+        assert!(matches!(results[0].game_time, GameTime::WillBePlayed(_)));
+        assert!(results
+            .iter()
+            .any(|result| matches!(result.game_time, GameTime::BreakAfter(20))));
+        let finished = results
+            .iter()
+            .position(|result| matches!(result.game_time, GameTime::Played))
+            .unwrap();
+        assert_eq!(results[finished].period_scores.len(), 3);
+        assert!(results[..finished]
+            .windows(2)
+            .all(|pair| pair[0].my_team_score <= pair[1].my_team_score));
+    }
+}
@@ -0,0 +1,185 @@
+//! Slack incoming webhook announcing the match events with configurable templates.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::events::{TimelineEvent, TimelineKind};
+use crate::observer::Observer;
+use crate::template;
+use crate::webhook::Poster;
+use crate::GameResult;
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+const RETRIES: u32 = 1;
+
+pub const PLACEHOLDERS: &[&str] = &[
+    "my_team",
+    "opponent_team",
+    "my_team_score",
+    "opponent_team_score",
+    "minute",
+    "scorer",
+];
+
+/// Templates of the events announced unless configured otherwise.
+const DEFAULT_TEMPLATES: &[(&str, &str)] = &[
+    (
+        "started",
+        ":arrow_forward: {my_team} – {opponent_team} has started",
+    ),
+    (
+        "goal_for",
+        ":tada: *{my_team}* scores! {my_team} {my_team_score}:{opponent_team_score} {opponent_team} {minute}",
+    ),
+    (
+        "goal_against",
+        ":disappointed: {opponent_team} scores. {my_team} {my_team_score}:{opponent_team_score} {opponent_team} {minute}",
+    ),
+    (
+        "player_goal",
+        ":star: {scorer} scored! {my_team} {my_team_score}:{opponent_team_score} {opponent_team} {minute}",
+    ),
+    (
+        "finished",
+        ":checkered_flag: Final result: {my_team} {my_team_score}:{opponent_team_score} {opponent_team}",
+    ),
+];
+
+#[derive(Serialize)]
+struct Message<'a> {
+    text: &'a str,
+}
+
+pub struct Slack {
+    /// Template of each announced event kind
+    templates: HashMap<String, String>,
+    poster: Poster,
+}
+
+impl Slack {
+    /// The custom templates replace the default ones, an empty one silences the event.
+    pub fn new(webhook: &str, custom: &[(String, String)]) -> anyhow::Result<Self> {
+        let mut templates: HashMap<_, _> = DEFAULT_TEMPLATES
+            .iter()
+            .map(|(kind, template)| (kind.to_string(), template.to_string()))
+            .collect();
+        templates.extend(custom.iter().cloned());
+        Ok(Self {
+            templates,
+            // the webhook URL is the secret itself
+            poster: Poster::new("Slack webhook", webhook, RETRIES, TIMEOUT)?,
+        })
+    }
+
+    fn message(&self, event: &TimelineEvent, latest: &GameResult) -> Option<String> {
+        let template = self
+            .templates
+            .get(event.kind.name())
+            .filter(|template| !template.is_empty())?;
+        let text = template::render(
+            template,
+            &[
+                ("my_team", latest.my_team.clone()),
+                ("opponent_team", latest.opponent_team.clone()),
+                ("my_team_score", event.my_team_score.to_string()),
+                ("opponent_team_score", event.opponent_team_score.to_string()),
+                (
+                    "minute",
+                    event
+                        .minute
+                        .map(|minute| format!("{minute}'"))
+                        .unwrap_or_default(),
+                ),
+                (
+                    "scorer",
+                    event
+                        .goal
+                        .as_ref()
+                        .map(|goal| goal.scorer.clone())
+                        .unwrap_or_default(),
+                ),
+            ],
+        );
+        Some(text.trim_end().to_string())
+    }
+}
+
+/// `EVENT=TEMPLATE` of the command line, EVENT being e.g. `goal_for` or `break`.
+pub fn parse_template(value: &str) -> Result<(String, String), String> {
+    let (kind, template) = value
+        .split_once('=')
+        .ok_or_else(|| format!("{value} is not EVENT=TEMPLATE"))?;
+    if !TimelineKind::ALL.iter().any(|known| known.name() == kind) {
+        return Err(format!(
+            "unknown event {kind}, expected one of {}",
+            TimelineKind::ALL
+                .iter()
+                .map(TimelineKind::name)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+    Ok((kind.to_string(), template.to_string()))
+}
+
+impl Observer for Slack {
+    fn announces(&self) -> bool {
+        true
+    }
+
+    fn on_event(&mut self, event: &TimelineEvent, latest: &GameResult) {
+        if let Some(text) = self.message(event, latest) {
+            self.poster.post(&Message { text: &text });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events;
+    use crate::simulation::sample;
+    use crate::GameTime;
+
+    #[tokio::test]
+    async fn test_message() {
+        let slack = Slack::new(
+            "http://127.0.0.1:9/",
+            &[
+                parse_template(
+                    "goal_for=GOAL {my_team|upper} {my_team_score}:{opponent_team_score}",
+                )
+                .unwrap(),
+                parse_template("finished=").unwrap(),
+            ],
+        )
+        .unwrap();
+        let previous = sample(GameTime::WillBePlayed(None), 0, 0);
+        let latest = GameResult {
+            my_team_score: 1,
+            game_time: GameTime::Playing(3),
+            ..previous.clone()
+        };
+        let messages: Vec<_> = events::between(&previous, &latest)
+            .iter()
+            .filter_map(|event| slack.message(event, &latest))
+            .collect();
+        assert_eq!(
+            messages,
+            [
+                ":arrow_forward: Sparta Praha – Kometa Brno has started",
+                "GOAL SPARTA PRAHA 1:0"
+            ]
+        );
+
+        let finished = GameResult {
+            game_time: GameTime::Played,
+            ..latest.clone()
+        };
+        assert!(events::between(&latest, &finished)
+            .iter()
+            .all(|event| slack.message(event, &finished).is_none()));
+        assert!(parse_template("penalty=x").is_err());
+    }
+}
@@ -0,0 +1,60 @@
+use async_trait::async_trait;
+use fantoccini::Client;
+use std::sync::{Arc, Mutex};
+use url::Url;
+
+use crate::layout::LayoutDetector;
+use crate::{details, scrape_match_page, scrape_team_page, GameResult, ScrapeOptions};
+
+/// Backend providing the latest match of a team page.
+#[async_trait]
+pub trait ScoreSource: Send + Sync {
+    async fn get_score(
+        &self,
+        url: &Url,
+        team_name: &str,
+        options: &ScrapeOptions,
+    ) -> anyhow::Result<GameResult>;
+}
+
+/// Page rendered by a browser controlled over WebDriver.
+pub struct WebDriverSource {
+    client: Client,
+    layout: Arc<Mutex<LayoutDetector>>,
+}
+
+impl WebDriverSource {
+    pub fn new(client: Client) -> Self {
+        Self::with_layout(client, Arc::new(Mutex::new(LayoutDetector::new(None))))
+    }
+
+    /// Share the layout detector so that it outlives the browser session.
+    pub fn with_layout(client: Client, layout: Arc<Mutex<LayoutDetector>>) -> Self {
+        Self { client, layout }
+    }
+
+    pub fn client(&mut self) -> &mut Client {
+        &mut self.client
+    }
+
+    pub fn into_client(self) -> Client {
+        self.client
+    }
+}
+
+#[async_trait]
+impl ScoreSource for WebDriverSource {
+    async fn get_score(
+        &self,
+        url: &Url,
+        team_name: &str,
+        options: &ScrapeOptions,
+    ) -> anyhow::Result<GameResult> {
+        // the client is a handle of the session, a clone drives the same browser
+        let mut client = self.client.clone();
+        if details::is_match_url(url) {
+            return scrape_match_page(&mut client, url, team_name, options).await;
+        }
+        scrape_team_page(&mut client, url, team_name, options, &self.layout).await
+    }
+}
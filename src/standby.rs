@@ -0,0 +1,241 @@
+//! Redundant deployments: the primary instance beats a heartbeat, a standby instance scrapes
+//! along without announcing anything until the heartbeat goes stale and it takes over.
+
+use anyhow::Context;
+use chrono::{DateTime, FixedOffset, Local};
+#[cfg(feature = "mqtt")]
+use rumqttc::{AsyncClient, Event, Packet, QoS};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+#[cfg(feature = "mqtt")]
+use std::sync::Mutex;
+use std::time::Duration;
+use tracing::{debug, warn};
+use url::Url;
+
+#[cfg(feature = "mqtt")]
+use crate::mqtt;
+
+#[cfg(feature = "mqtt")]
+const DEFAULT_TOPIC: &str = "livesport/heartbeat";
+
+/// Heartbeat written by the primary, the time of its last good scrape.
+pub enum Heartbeat {
+    File(PathBuf),
+    #[cfg(feature = "mqtt")]
+    Mqtt {
+        client: AsyncClient,
+        topic: String,
+    },
+}
+
+impl Heartbeat {
+    /// Beat to a `file:///path` or `mqtt://host[:port]/topic`; over HTTP, the primary
+    /// serves its heartbeat on `/heartbeat` of `--serve`.
+    pub fn new(url: &Url) -> anyhow::Result<Self> {
+        match url.scheme() {
+            "file" => Ok(Self::File(file_path(url)?)),
+            #[cfg(feature = "mqtt")]
+            "mqtt" => Ok(Self::Mqtt {
+                client: mqtt::connect(url, &format!("heartbeat-{}", std::process::id()))?,
+                topic: topic(url),
+            }),
+            #[cfg(not(feature = "mqtt"))]
+            "mqtt" => anyhow::bail!("heartbeat {url} given but built without the mqtt feature"),
+            scheme => anyhow::bail!("unsupported heartbeat {url}, {scheme}:// cannot be written"),
+        }
+    }
+
+    pub fn beat(&self) {
+        let now = Local::now().to_rfc3339();
+        let written = match self {
+            Self::File(path) => fs::write(path, &now).context("cannot write the heartbeat file"),
+            // retained, so that a standby started later learns about the primary right away
+            #[cfg(feature = "mqtt")]
+            Self::Mqtt { client, topic } => client
+                .try_publish(topic, QoS::AtLeastOnce, true, now)
+                .context("cannot publish the heartbeat"),
+        };
+        if let Err(error) = written {
+            warn!("{error:#}");
+        }
+    }
+}
+
+/// Whether a standby instance took over, shared by the pipelines of all teams.
+#[derive(Clone, Default)]
+pub struct Standby {
+    promoted: Arc<AtomicBool>,
+}
+
+impl Standby {
+    /// Watch the heartbeat of the primary at a `file://`, `http(s)://` or `mqtt://` URL and
+    /// take over for good once it is older than `timeout`.
+    pub fn watch(url: &Url, timeout: Duration) -> anyhow::Result<Self> {
+        let probe = Probe::new(url)?;
+        let standby = Self::default();
+        let promoted = standby.promoted.clone();
+        let url = url.clone();
+        let check_interval = (timeout / 4).max(Duration::from_secs(1));
+        tokio::spawn(async move {
+            // a primary never heard of gets the timeout to show up
+            let mut last_beat = Local::now().fixed_offset();
+            loop {
+                match probe.last_beat().await {
+                    Ok(beat) => last_beat = beat,
+                    Err(error) => debug!("cannot read the heartbeat {url}: {error:#}"),
+                }
+                let silence = (Local::now().fixed_offset() - last_beat)
+                    .to_std()
+                    .unwrap_or_default();
+                if silence > timeout {
+                    warn!("the primary did not beat since {last_beat}, taking over");
+                    promoted.store(true, Ordering::Relaxed);
+                    break;
+                }
+                tokio::time::sleep(check_interval).await;
+            }
+        });
+        Ok(standby)
+    }
+
+    /// Whether the results are to be announced.
+    pub fn is_active(&self) -> bool {
+        self.promoted.load(Ordering::Relaxed)
+    }
+}
+
+/// Where the standby reads the heartbeat of the primary.
+enum Probe {
+    File(PathBuf),
+    Http(reqwest::Client, Url),
+    #[cfg(feature = "mqtt")]
+    Mqtt(Arc<Mutex<Option<DateTime<FixedOffset>>>>),
+}
+
+impl Probe {
+    fn new(url: &Url) -> anyhow::Result<Self> {
+        match url.scheme() {
+            "file" => Ok(Self::File(file_path(url)?)),
+            "http" | "https" => Ok(Self::Http(
+                reqwest::Client::builder()
+                    .timeout(Duration::from_secs(5))
+                    .build()?,
+                url.clone(),
+            )),
+            #[cfg(feature = "mqtt")]
+            "mqtt" => Ok(Self::Mqtt(subscribe(url)?)),
+            #[cfg(not(feature = "mqtt"))]
+            "mqtt" => anyhow::bail!("heartbeat {url} given but built without the mqtt feature"),
+            scheme => anyhow::bail!("unsupported heartbeat {url}, {scheme}:// cannot be read"),
+        }
+    }
+
+    async fn last_beat(&self) -> anyhow::Result<DateTime<FixedOffset>> {
+        let text = match self {
+            Self::File(path) => fs::read_to_string(path)?,
+            Self::Http(client, url) => {
+                client
+                    .get(url.as_str())
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .text()
+                    .await?
+            }
+            #[cfg(feature = "mqtt")]
+            Self::Mqtt(last_beat) => {
+                return (*last_beat.lock().unwrap()).context("no heartbeat received yet")
+            }
+        };
+        parse_beat(&text)
+    }
+}
+
+/// Keep the last heartbeat published to the topic of the broker URL.
+#[cfg(feature = "mqtt")]
+fn subscribe(url: &Url) -> anyhow::Result<Arc<Mutex<Option<DateTime<FixedOffset>>>>> {
+    let options = mqtt::options(url, &format!("standby-{}", std::process::id()))?;
+    let (client, mut eventloop) = AsyncClient::new(options, 16);
+    let topic = topic(url);
+    let last_beat = Arc::new(Mutex::new(None));
+    let received = last_beat.clone();
+    tokio::spawn(async move {
+        loop {
+            match eventloop.poll().await {
+                // the subscription does not survive a reconnection
+                Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                    if let Err(error) = client.try_subscribe(&topic, QoS::AtLeastOnce) {
+                        warn!("cannot subscribe to the heartbeat: {error}");
+                    }
+                }
+                Ok(Event::Incoming(Packet::Publish(publish))) => {
+                    match parse_beat(&String::from_utf8_lossy(&publish.payload)) {
+                        Ok(beat) => *received.lock().unwrap() = Some(beat),
+                        Err(error) => warn!("ignoring heartbeat: {error:#}"),
+                    }
+                }
+                Ok(_) => {}
+                Err(error) => {
+                    warn!("MQTT connection failed: {error}");
+                    tokio::time::sleep(mqtt::RECONNECT_DELAY).await;
+                }
+            }
+        }
+    });
+    Ok(last_beat)
+}
+
+fn file_path(url: &Url) -> anyhow::Result<PathBuf> {
+    url.to_file_path()
+        .map_err(|_| anyhow::anyhow!("invalid heartbeat file {url}"))
+}
+
+#[cfg(feature = "mqtt")]
+fn topic(url: &Url) -> String {
+    match url.path().trim_matches('/') {
+        "" => DEFAULT_TOPIC.to_string(),
+        topic => topic.to_string(),
+    }
+}
+
+/// RFC 3339 time of the beat, also as a JSON string.
+fn parse_beat(text: &str) -> anyhow::Result<DateTime<FixedOffset>> {
+    let text = text.trim().trim_matches('"');
+    DateTime::parse_from_rfc3339(text).with_context(|| format!("invalid heartbeat {text:?}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_beat() {
+        let beat = parse_beat("2024-09-14T17:02:11.5+02:00\n").unwrap();
+        assert_eq!(beat.to_rfc3339(), "2024-09-14T17:02:11.500+02:00");
+        assert_eq!(parse_beat("\"2024-09-14T17:02:11.5+02:00\"").unwrap(), beat);
+        assert!(parse_beat("yesterday").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_take_over() {
+        let path = std::env::temp_dir().join(format!("livesport-heartbeat-{}", std::process::id()));
+        let url = Url::from_file_path(&path).unwrap();
+        Heartbeat::new(&url).unwrap().beat();
+
+        let standby = Standby::watch(&url, Duration::from_secs(1)).unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(!standby.is_active());
+
+        fs::write(
+            &path,
+            (Local::now() - chrono::Duration::minutes(1)).to_rfc3339(),
+        )
+        .unwrap();
+        tokio::time::sleep(Duration::from_millis(1500)).await;
+        assert!(standby.is_active());
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,100 @@
+use fantoccini::{Client, Locator};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tokio::time::sleep;
+use url::Url;
+
+use crate::{budget, element_text, parse_score};
+
+/// Row of a league or group table.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Standing {
+    pub rank: u64,
+    pub team: String,
+    pub played: u64,
+    /// Goals scored and conceded
+    pub goals: (u64, u64),
+    pub points: u64,
+}
+
+/// One table of the standings; tournaments split the teams into groups.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Group {
+    /// Label of the group (e.g. `Group A`), `None` for the only table of a league
+    pub name: Option<String>,
+    pub standings: Vec<Standing>,
+}
+
+/// Read all tables of the standings page (e.g. `.../extraliga/tabulka/` or the groups of
+/// a World Championship).
+pub async fn get_standings(client: &mut Client, url: &Url) -> anyhow::Result<Vec<Group>> {
+    budget::goto(client, url.as_str()).await?;
+    sleep(Duration::from_millis(500)).await;
+
+    let tables = client.find_all(Locator::Css(".ui-table")).await?;
+    let grouped = tables.len() > 1;
+    let mut groups = Vec::new();
+    for (index, table) in tables.iter().enumerate() {
+        // the participant column of a group table is headed by the name of the group
+        let name = if grouped {
+            Some(
+                element_text(table, ".ui-table__headerCell--participant")
+                    .await
+                    .map(|name| name.trim().to_string())
+                    .unwrap_or_else(|_| format!("Group {}", index + 1)),
+            )
+        } else {
+            None
+        };
+
+        let mut standings = Vec::new();
+        for row in table.find_all(Locator::Css(".ui-table__row")).await? {
+            let values = row.find_all(Locator::Css(".table__cell--value")).await?;
+            let played = match values.first() {
+                Some(played) => parse_score(&played.text().await?),
+                None => 0,
+            };
+            standings.push(Standing {
+                // ranks are shown as `1.`
+                rank: parse_score(
+                    element_text(&row, ".tableCellRank")
+                        .await?
+                        .trim()
+                        .trim_end_matches('.'),
+                ),
+                team: element_text(&row, ".tableCellParticipant__name").await?,
+                played,
+                goals: parse_goals(
+                    &element_text(&row, ".table__cell--score")
+                        .await
+                        .unwrap_or_default(),
+                ),
+                points: parse_score(&element_text(&row, ".table__cell--points").await?),
+            });
+        }
+        groups.push(Group { name, standings });
+    }
+
+    anyhow::ensure!(!groups.is_empty(), "{url} contains no standings");
+    Ok(groups)
+}
+
+/// Goals as `45:30`.
+fn parse_goals(value: &str) -> (u64, u64) {
+    match value.split_once(':') {
+        Some((scored, conceded)) => (parse_score(scored), parse_score(conceded)),
+        None => (0, 0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_goals() {
+        assert_eq!(parse_goals("45:30"), (45, 30));
+        assert_eq!(parse_goals(" 3 : 12 "), (3, 12));
+        assert_eq!(parse_goals(""), (0, 0));
+    }
+}
@@ -0,0 +1,93 @@
+//! Last published result of a team kept on disk, so that a restart does not announce again
+//! what was announced before it.
+
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+use crate::GameResult;
+
+/// Name of the team writing the output file: the file stem and a hash of its full path, so
+/// that `a/result.json` and `b/result.json` differ.
+pub fn output_key(output: &Path) -> String {
+    // the output file itself may not exist yet
+    let parent = output
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let full = match (parent.canonicalize(), output.file_name()) {
+        (Ok(parent), Some(name)) => parent.join(name),
+        _ => output.to_path_buf(),
+    };
+    // FNV-1a, stable across Rust versions unlike `DefaultHasher`
+    let hash = full
+        .to_string_lossy()
+        .bytes()
+        .fold(0xcbf29ce484222325_u64, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+        });
+    format!(
+        "{}-{hash:016x}",
+        output.file_stem().unwrap_or_default().to_string_lossy()
+    )
+}
+
+/// State file of the team writing the output file.
+pub fn path(state_dir: &Path, output: &Path) -> PathBuf {
+    state_dir.join(format!("{}.state.json", output_key(output)))
+}
+
+/// The result saved before the restart, if any and readable.
+pub fn load(path: &Path) -> Option<GameResult> {
+    let content = fs::read(path).ok()?;
+    match serde_json::from_slice(&content) {
+        Ok(result) => Some(result),
+        Err(error) => {
+            warn!("ignoring unreadable state {}: {error}", path.display());
+            None
+        }
+    }
+}
+
+/// Replace the state at once, a crash in the middle leaves the previous one.
+pub fn save(path: &Path, result: &GameResult) -> anyhow::Result<()> {
+    let temporary = path.with_extension("tmp");
+    serde_json::to_writer(File::create(&temporary)?, result)?;
+    fs::rename(&temporary, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::sample;
+    use crate::GameTime;
+
+    #[test]
+    fn test_save_and_load() {
+        let dir = std::env::temp_dir().join(format!("livesport-state-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = path(&dir, Path::new("out/sparta.json"));
+        assert!(path.starts_with(&dir));
+        assert!(path
+            .file_name()
+            .unwrap()
+            .to_string_lossy()
+            .starts_with("sparta-"));
+        assert_ne!(
+            output_key(Path::new("a/result.json")),
+            output_key(Path::new("b/result.json"))
+        );
+        assert!(load(&path).is_none());
+
+        let result = sample(GameTime::Playing(12), 1, 0);
+        save(&path, &result).unwrap();
+        let loaded = load(&path).unwrap();
+        assert_eq!(loaded.my_team, "Sparta Praha");
+        assert_eq!(loaded.game_time, result.game_time);
+
+        fs::write(&path, "{").unwrap();
+        assert!(load(&path).is_none());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,70 @@
+use fantoccini::cookies::Cookie;
+use fantoccini::Client;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+use tracing::debug;
+use url::Url;
+
+use crate::budget;
+
+const USER_AGENTS: &[&str] = &[
+    "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/127.0.0.0 Safari/537.36",
+    "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/127.0.0.0 Safari/537.36",
+    "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/127.0.0.0 Safari/537.36",
+    "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/126.0.0.0 Safari/537.36 Edg/126.0.0.0",
+];
+
+const VIEWPORTS: &[(u32, u32)] = &[
+    (1920, 1080),
+    (1536, 864),
+    (1440, 900),
+    (1366, 768),
+    (1280, 720),
+];
+
+/// Chrome arguments making the headless browser look like a regular desktop one.
+pub fn chrome_args() -> Vec<String> {
+    let mut rng = rand::thread_rng();
+    let user_agent = USER_AGENTS.choose(&mut rng).unwrap();
+    let (width, height) = VIEWPORTS.choose(&mut rng).unwrap();
+    vec![
+        format!("--user-agent={user_agent}"),
+        format!("--window-size={width},{height}"),
+        "--disable-blink-features=AutomationControlled".to_string(),
+    ]
+}
+
+/// Prolong the duration by a random amount of up to a half.
+pub fn jitter(duration: Duration) -> Duration {
+    duration.mul_f64(rand::thread_rng().gen_range(1.0..1.5))
+}
+
+/// Restore cookies saved by a previous run; the site is opened first as cookies can be set only for it.
+pub async fn load_cookies(client: &mut Client, path: &Path, url: &Url) -> anyhow::Result<()> {
+    let Ok(content) = fs::read_to_string(path) else {
+        return Ok(());
+    };
+
+    budget::goto(client, url.as_str()).await?;
+    for line in content.lines() {
+        match Cookie::parse(line.to_string()) {
+            Ok(cookie) => client.add_cookie(cookie).await?,
+            Err(error) => debug!("skipping invalid cookie {line:?}: {error}"),
+        }
+    }
+    Ok(())
+}
+
+pub async fn save_cookies(client: &mut Client, path: &Path) -> anyhow::Result<()> {
+    let cookies: Vec<_> = client
+        .get_all_cookies()
+        .await?
+        .iter()
+        .map(|cookie| cookie.to_string())
+        .collect();
+    fs::write(path, cookies.join("\n"))?;
+    Ok(())
+}
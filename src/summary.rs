@@ -0,0 +1,132 @@
+use crate::{template, GameResult, GameTime};
+
+pub const DEFAULT_TEMPLATE: &str =
+    "## {my_team} {my_team_score}:{opponent_team_score} {opponent_team}
+
+Periods: {period_scores}
+Scorers: {scorers}
+
+_{date}_
+";
+
+/// Render a post-match summary by substituting `{field}` placeholders in the template.
+pub fn render(template_text: &str, result: &GameResult) -> String {
+    let period_scores = result
+        .period_scores
+        .iter()
+        .map(|(my, opponent)| format!("{my}:{opponent}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let scorers = result
+        .goals
+        .iter()
+        .filter(|goal| goal.my_team)
+        .map(|goal| format!("{} {}", goal.scorer, goal.minute))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let (status, period) = match result.game_time {
+        GameTime::WillBePlayed(_) => (template::SCHEDULED, None),
+        GameTime::Playing(_) => (template::LIVE, result.period()),
+        GameTime::BreakAfter(_) => (template::BREAK, result.period()),
+        GameTime::Played => (template::FINISHED, Some(result.period_scores.len() as u64)),
+    };
+    let clock = match (&result.clock, result.game_time.minute()) {
+        (Some(clock), _) => format!("{:02}:{:02}", clock.minute, clock.second.unwrap_or(0)),
+        (None, Some(minute)) => format!("{minute:02}:00"),
+        (None, None) => String::new(),
+    };
+
+    template::render(
+        template_text,
+        &[
+            ("my_team", result.my_team.clone()),
+            ("opponent_team", result.opponent_team.clone()),
+            ("my_team_score", result.my_team_score.to_string()),
+            (
+                "opponent_team_score",
+                result.opponent_team_score.to_string(),
+            ),
+            ("period_scores", period_scores),
+            ("scorers", scorers),
+            ("broadcast", result.broadcast.join(", ")),
+            ("date", result.generated.format("%d.%m.%Y").to_string()),
+            ("status", status.to_string()),
+            (
+                "period",
+                period.map(|period| period.to_string()).unwrap_or_default(),
+            ),
+            ("clock", clock),
+        ],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::details::Goal;
+    use crate::GameTime;
+    use crate::Periods;
+    use chrono::{Local, TimeZone};
+
+    fn goal(scorer: &str, minute: &str, my_team: bool) -> Goal {
+        Goal {
+            minute: minute.to_string(),
+            my_team,
+            scorer: scorer.to_string(),
+            assists: Vec::new(),
+            player_of_interest: false,
+        }
+    }
+
+    #[test]
+    fn test_render() {
+        let result = GameResult {
+            my_team: "Sparta Praha".to_string(),
+            my_team_score: 3,
+            opponent_team: "Kometa Brno".to_string(),
+            opponent_team_score: 1,
+            period_scores: vec![(1, 0), (2, 1), (0, 0)],
+            goals: vec![
+                goal("Novák J.", "12'", true),
+                goal("Zohorna R.", "25'", false),
+                goal("Dvořák P.", "58'", true),
+            ],
+            penalties: Vec::new(),
+            stats: Vec::new(),
+            broadcast: Vec::new(),
+            opponent_form: None,
+            series_score: None,
+            series_best_of: None,
+            timeline: Vec::new(),
+            estimated_time_remaining: None,
+            estimated_end_at: None,
+            started_at: None,
+            elapsed_minutes: None,
+            last_goal_at: None,
+            minutes_since_last_goal: None,
+            outcome: None,
+            goal_difference: None,
+            is_leading: None,
+            clock: None,
+            quality: None,
+            stale_since: None,
+            served_by: None,
+            game_time: GameTime::Played,
+            periods: Periods::HOCKEY,
+            generated: Local.with_ymd_and_hms(2024, 9, 7, 20, 30, 0).unwrap(),
+        };
+        assert_eq!(
+            render(DEFAULT_TEMPLATE, &result),
+            "## Sparta Praha 3:1 Kometa Brno\n\nPeriods: 1:0, 2:1, 0:0\nScorers: Novák J. 12', Dvořák P. 58'\n\n_07.09.2024_\n"
+        );
+        assert_eq!(
+            render(
+                "{status|status} {period|ordinal} {opponent_team|truncate:5}",
+                &result
+            ),
+            "FT 3rd Kome…"
+        );
+    }
+}
@@ -0,0 +1,229 @@
+use anyhow::Context;
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::str::FromStr;
+use url::Url;
+
+use crate::ScrapeOptions;
+
+/// One tracked team: its page, its name and the file its results are written to.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Team {
+    /// Short name used by --only, the team name when missing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub url: Url,
+    pub name: String,
+    pub output: PathBuf,
+    #[serde(default, skip_serializing_if = "TeamOptions::is_default")]
+    pub options: TeamOptions,
+    /// Browser session (counted from 0, see --browsers) scraping the team, assigned round
+    /// robin when missing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub browser: Option<usize>,
+    /// Read token of --serve giving access to the results of this team only; also as @FILE or
+    /// cmd:COMMAND
+    #[cfg(feature = "server")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+    /// Club the team was listed under in the config
+    #[serde(skip)]
+    pub club: Option<String>,
+}
+
+/// Scraping options overriding the global ones for a club or a team.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct TeamOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    details: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    context: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    opponent_form: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    best_of: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    timezone: Option<Tz>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    players: Option<Vec<String>>,
+}
+
+impl TeamOptions {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Fill what is not set from the defaults of the club.
+    fn or(self, defaults: &TeamOptions) -> TeamOptions {
+        TeamOptions {
+            details: self.details.or(defaults.details),
+            context: self.context.or(defaults.context),
+            opponent_form: self.opponent_form.or(defaults.opponent_form),
+            best_of: self.best_of.or(defaults.best_of),
+            timezone: self.timezone.or(defaults.timezone),
+            players: self.players.or_else(|| defaults.players.clone()),
+        }
+    }
+
+    /// The global options with the overrides applied.
+    pub fn apply(&self, options: &ScrapeOptions) -> ScrapeOptions {
+        ScrapeOptions {
+            details: self.details.unwrap_or(options.details),
+            context: self.context.unwrap_or(options.context),
+            opponent_form: self.opponent_form.unwrap_or(options.opponent_form),
+            best_of: self.best_of.or(options.best_of),
+            timezone: self.timezone.or(options.timezone),
+            players: self.players.clone().unwrap_or(options.players.clone()),
+            ..options.clone()
+        }
+    }
+}
+
+/// Teams of one club sharing their default options.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Club {
+    pub name: String,
+    #[serde(default)]
+    pub options: TeamOptions,
+    pub teams: Vec<Team>,
+}
+
+impl Club {
+    pub fn into_teams(self) -> impl Iterator<Item = Team> {
+        let Club {
+            name,
+            options,
+            teams,
+        } = self;
+        teams.into_iter().map(move |team| Team {
+            options: team.options.or(&options),
+            club: Some(name.clone()),
+            ..team
+        })
+    }
+}
+
+impl Team {
+    /// `club/id` for teams of a club, `id` otherwise.
+    pub fn key(&self) -> String {
+        let id = self.id.as_deref().unwrap_or(&self.name);
+        match &self.club {
+            Some(club) => format!("{club}/{id}"),
+            None => id.to_string(),
+        }
+    }
+
+    fn selected_by(&self, selector: &str) -> bool {
+        self.key() == selector || self.club.as_deref() == Some(selector)
+    }
+}
+
+/// Teams chosen by `club` or `club/team` selectors, all of them without any.
+pub fn select(teams: Vec<Team>, only: &[String]) -> anyhow::Result<Vec<Team>> {
+    if only.is_empty() {
+        return Ok(teams);
+    }
+    for selector in only {
+        anyhow::ensure!(
+            teams.iter().any(|team| team.selected_by(selector)),
+            "--only {selector} matches no team"
+        );
+    }
+    Ok(teams
+        .into_iter()
+        .filter(|team| only.iter().any(|selector| team.selected_by(selector)))
+        .collect())
+}
+
+impl FromStr for Team {
+    type Err = anyhow::Error;
+
+    /// Parse `URL,NAME,OUTPUT`; the name may contain commas.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (url, rest) = value.split_once(',').context("expected URL,NAME,OUTPUT")?;
+        let (name, output) = rest.rsplit_once(',').context("expected URL,NAME,OUTPUT")?;
+        anyhow::ensure!(
+            !name.trim().is_empty() && !output.trim().is_empty(),
+            "expected URL,NAME,OUTPUT"
+        );
+        Ok(Self {
+            id: None,
+            url: url.trim().parse().context("invalid team URL")?,
+            name: name.trim().to_string(),
+            output: PathBuf::from(output.trim()),
+            options: TeamOptions::default(),
+            browser: None,
+            #[cfg(feature = "server")]
+            token: None,
+            club: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_team() {
+        let team: Team =
+            "https://www.livesport.cz/tym/sparta-praha/fT1kUaTu/,Sparta, Praha,sparta.json"
+                .parse()
+                .unwrap();
+        assert_eq!(team.name, "Sparta, Praha");
+        assert_eq!(team.output, PathBuf::from("sparta.json"));
+
+        assert!("https://www.livesport.cz/,Sparta".parse::<Team>().is_err());
+        assert!("not a url,Sparta,sparta.json".parse::<Team>().is_err());
+    }
+
+    #[test]
+    fn test_clubs() {
+        let club: Club = toml::from_str(
+            r#"
+            name = "sparta"
+            options = { details = true, best_of = 7 }
+
+            [[teams]]
+            id = "men"
+            url = "https://www.livesport.cz/tym/sparta-praha/fT1kUaTu/"
+            name = "Sparta Praha"
+            output = "men.json"
+            options = { details = false }
+
+            [[teams]]
+            url = "https://www.livesport.cz/tym/sparta-praha-u20/x4VzLkpm/"
+            name = "Sparta U20"
+            output = "u20.json"
+            "#,
+        )
+        .unwrap();
+        let teams = club.into_teams().collect::<Vec<_>>();
+        assert_eq!(teams[0].key(), "sparta/men");
+        assert_eq!(teams[1].key(), "sparta/Sparta U20");
+
+        let global = ScrapeOptions {
+            context: true,
+            ..ScrapeOptions::default()
+        };
+        let men = teams[0].options.apply(&global);
+        assert!(!men.details && men.context);
+        assert_eq!(men.best_of, Some(7));
+        assert!(teams[1].options.apply(&global).details);
+
+        assert_eq!(select(teams.clone(), &[]).unwrap().len(), 2);
+        assert_eq!(
+            select(teams.clone(), &["sparta".to_string()])
+                .unwrap()
+                .len(),
+            2
+        );
+        let men = select(teams.clone(), &["sparta/men".to_string()]).unwrap();
+        assert_eq!(men[0].output, PathBuf::from("men.json"));
+        assert!(select(teams, &["slavia".to_string()]).is_err());
+    }
+}
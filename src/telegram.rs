@@ -0,0 +1,113 @@
+//! Telegram bot announcing goals, the kick-off and the final result to a chat.
+
+use serde::Serialize;
+use std::time::Duration;
+
+use crate::events::{TimelineEvent, TimelineKind};
+use crate::observer::Observer;
+use crate::webhook::Poster;
+use crate::GameResult;
+
+const API: &str = "https://api.telegram.org";
+const TIMEOUT: Duration = Duration::from_secs(10);
+/// One retry rides out a hiccup, a goal announced much later is of no use
+const RETRIES: u32 = 1;
+
+#[derive(Serialize)]
+struct SendMessage<'a> {
+    chat_id: &'a str,
+    text: &'a str,
+}
+
+pub struct TelegramBot {
+    chat_id: String,
+    poster: Poster,
+}
+
+impl TelegramBot {
+    pub fn new(token: &str, chat_id: &str) -> anyhow::Result<Self> {
+        Ok(Self {
+            chat_id: chat_id.to_string(),
+            // the token is part of the URL, keep it out of the logs
+            poster: Poster::new(
+                "Telegram bot",
+                &format!("{API}/bot{token}/sendMessage"),
+                RETRIES,
+                TIMEOUT,
+            )?,
+        })
+    }
+}
+
+/// Text announcing the event, `None` for the events not worth a message.
+fn message(event: &TimelineEvent, latest: &GameResult) -> Option<String> {
+    let score = format!(
+        "{} {}:{} {}",
+        latest.my_team, event.my_team_score, event.opponent_team_score, latest.opponent_team
+    );
+    let minute = event
+        .minute
+        .map(|minute| format!(" ({minute}')"))
+        .unwrap_or_default();
+    match event.kind {
+        TimelineKind::Started => Some(format!(
+            "▶️ {} – {} has started",
+            latest.my_team, latest.opponent_team
+        )),
+        TimelineKind::GoalFor => Some(format!("⚽ {} scores! {score}{minute}", latest.my_team)),
+        TimelineKind::GoalAgainst => Some(format!(
+            "😞 {} scores. {score}{minute}",
+            latest.opponent_team
+        )),
+        TimelineKind::Finished => Some(format!("🏁 Final result: {score}")),
+        TimelineKind::PlayerGoal => event
+            .goal
+            .as_ref()
+            .map(|goal| format!("⭐ {} scored! {score} ({})", goal.scorer, goal.minute)),
+        TimelineKind::Break | TimelineKind::Resumed | TimelineKind::StatisticChanged => None,
+    }
+}
+
+impl Observer for TelegramBot {
+    fn announces(&self) -> bool {
+        true
+    }
+
+    fn on_event(&mut self, event: &TimelineEvent, latest: &GameResult) {
+        if let Some(text) = message(event, latest) {
+            self.poster.post(&SendMessage {
+                chat_id: &self.chat_id,
+                text: &text,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events;
+    use crate::simulation::sample;
+    use crate::GameTime;
+
+    #[test]
+    fn test_message() {
+        let previous = sample(GameTime::Playing(54), 1, 1);
+        let latest = GameResult {
+            my_team_score: 2,
+            game_time: GameTime::Played,
+            ..previous.clone()
+        };
+        let messages: Vec<_> = events::between(&previous, &latest)
+            .iter()
+            .filter_map(|event| message(event, &latest))
+            .collect();
+        assert_eq!(
+            messages,
+            [
+                "⚽ Sparta Praha scores! Sparta Praha 2:1 Kometa Brno",
+                "🏁 Final result: Sparta Praha 2:1 Kometa Brno"
+            ]
+        );
+    }
+}
@@ -0,0 +1,179 @@
+//! `{placeholder|filter|filter:argument}` templates of summaries and notifications.
+//!
+//! Filters: `upper`, `lower`, `truncate:N` (cut to N characters with an ellipsis), `pad:N`
+//! (zero-pad a number to N digits), `ordinal[:LANG]` (1st, 2nd, ... or 1., 2., ...) and
+//! `status[:LANG]` (short label like FT or HT of the `status` placeholder).
+
+use anyhow::Context;
+
+pub const FILTERS: &[&str] = &["upper", "lower", "truncate", "pad", "ordinal", "status"];
+
+/// Values of the `status` placeholder.
+pub const SCHEDULED: &str = "scheduled";
+pub const LIVE: &str = "live";
+pub const BREAK: &str = "break";
+pub const FINISHED: &str = "finished";
+
+/// Substitute the placeholders; unknown ones are left as they are.
+pub fn render(template: &str, values: &[(&str, String)]) -> String {
+    let mut output = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        output.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find('}') else {
+            rest = &rest[start..];
+            break;
+        };
+        let expression = &rest[start + 1..start + end];
+        match expand(expression, values) {
+            Some(value) => output.push_str(&value),
+            None => output.push_str(&rest[start..=start + end]),
+        }
+        rest = &rest[start + end + 1..];
+    }
+    output.push_str(rest);
+    output
+}
+
+/// Check the placeholder and the filters of one `{...}` expression.
+pub fn check(expression: &str, placeholders: &[&str]) -> anyhow::Result<()> {
+    let mut parts = expression.split('|');
+    let name = parts.next().unwrap_or_default();
+    anyhow::ensure!(
+        placeholders.contains(&name),
+        "unknown placeholder {{{name}}}"
+    );
+    for filter in parts {
+        let (filter, argument) = split_filter(filter);
+        anyhow::ensure!(FILTERS.contains(&filter), "unknown filter {filter}");
+        if matches!(filter, "truncate" | "pad") {
+            argument
+                .context("missing width")?
+                .parse::<usize>()
+                .with_context(|| format!("invalid width of {filter}"))?;
+        }
+    }
+    Ok(())
+}
+
+fn expand(expression: &str, values: &[(&str, String)]) -> Option<String> {
+    let mut parts = expression.split('|');
+    let name = parts.next()?;
+    let mut value = values
+        .iter()
+        .find(|(placeholder, _)| *placeholder == name)?
+        .1
+        .clone();
+    for filter in parts {
+        value = apply(split_filter(filter), value)?;
+    }
+    Some(value)
+}
+
+fn split_filter(filter: &str) -> (&str, Option<&str>) {
+    match filter.split_once(':') {
+        Some((filter, argument)) => (filter.trim(), Some(argument.trim())),
+        None => (filter.trim(), None),
+    }
+}
+
+fn apply((filter, argument): (&str, Option<&str>), value: String) -> Option<String> {
+    Some(match filter {
+        "upper" => value.to_uppercase(),
+        "lower" => value.to_lowercase(),
+        "truncate" => truncate(&value, argument?.parse().ok()?),
+        "pad" => match value.parse::<u64>() {
+            Ok(number) => format!("{number:0width$}", width = argument?.parse().ok()?),
+            Err(_) => value,
+        },
+        "ordinal" => match value.parse::<u64>() {
+            Ok(number) => ordinal(number, argument.unwrap_or("en")),
+            Err(_) => value,
+        },
+        "status" => status(&value, argument.unwrap_or("en")).to_string(),
+        _ => return None,
+    })
+}
+
+fn truncate(value: &str, width: usize) -> String {
+    if value.chars().count() <= width {
+        value.to_string()
+    } else {
+        let mut truncated = value
+            .chars()
+            .take(width.saturating_sub(1))
+            .collect::<String>()
+            .trim_end()
+            .to_string();
+        truncated.push('…');
+        truncated
+    }
+}
+
+fn ordinal(number: u64, language: &str) -> String {
+    match language {
+        "en" => {
+            let suffix = match (number % 10, number % 100) {
+                (_, 11..=13) => "th",
+                (1, _) => "st",
+                (2, _) => "nd",
+                (3, _) => "rd",
+                _ => "th",
+            };
+            format!("{number}{suffix}")
+        }
+        // Czech, German and most other languages write ordinals with a period
+        _ => format!("{number}."),
+    }
+}
+
+fn status<'a>(value: &'a str, language: &str) -> &'a str {
+    let labels = match language {
+        "cs" => ["", "živě", "přestávka", "konec"],
+        "de" => ["", "live", "Pause", "Ende"],
+        _ => ["", "LIVE", "HT", "FT"],
+    };
+    match value {
+        SCHEDULED => labels[0],
+        LIVE => labels[1],
+        BREAK => labels[2],
+        FINISHED => labels[3],
+        _ => value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let values = [
+            ("team", "Sparta Praha".to_string()),
+            ("period", "2".to_string()),
+            ("minute", "7".to_string()),
+            ("status", BREAK.to_string()),
+        ];
+        assert_eq!(
+            render(
+                "{team|truncate:6|upper} {period|ordinal} {period|ordinal:cs} {minute|pad:2}' {status|status} {status|status:de}",
+                &values
+            ),
+            "SPART… 2nd 2. 07' HT Pause"
+        );
+        assert_eq!(
+            render("{unknown} {team|bogus} {", &values),
+            "{unknown} {team|bogus} {"
+        );
+        assert_eq!(ordinal(12, "en"), "12th");
+        assert_eq!(ordinal(23, "en"), "23rd");
+    }
+
+    #[test]
+    fn test_check() {
+        assert!(check("team|truncate:10|upper", &["team"]).is_ok());
+        assert!(check("team|truncate", &["team"]).is_err());
+        assert!(check("team|shout", &["team"]).is_err());
+        assert!(check("teams", &["team"]).is_err());
+    }
+}
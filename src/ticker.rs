@@ -0,0 +1,134 @@
+//! Compact score line of all tracked teams redrawn in place on the terminal.
+
+use std::io::{self, IsTerminal, Write};
+use std::sync::{Arc, Mutex};
+
+use crate::observer::Observer;
+use crate::{chart, GameResult, GameTime};
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const DIM: &str = "\x1b[2m";
+const RED: &str = "\x1b[31m";
+const GREEN: &str = "\x1b[32m";
+const YELLOW: &str = "\x1b[33m";
+const CYAN: &str = "\x1b[36m";
+
+/// Characters of the score progression chart, each of them covering a few minutes.
+const CHART_WIDTH: usize = 20;
+
+/// Line shared by the teams, each of them owning one segment.
+#[derive(Clone, Default)]
+pub struct Ticker {
+    segments: Arc<Mutex<Vec<String>>>,
+}
+
+impl Ticker {
+    /// Observer updating a new segment of the line.
+    pub fn segment(&self) -> Segment {
+        let mut segments = self.segments.lock().unwrap();
+        segments.push(String::new());
+        Segment {
+            ticker: self.clone(),
+            index: segments.len() - 1,
+            color: io::stdout().is_terminal(),
+        }
+    }
+}
+
+pub struct Segment {
+    ticker: Ticker,
+    index: usize,
+    /// Colors and in-place updates only make sense on a terminal
+    color: bool,
+}
+
+/// A team no longer tracked leaves the line on the next redraw.
+impl Drop for Segment {
+    fn drop(&mut self) {
+        self.ticker.segments.lock().unwrap()[self.index].clear();
+    }
+}
+
+impl Observer for Segment {
+    fn on_change(&mut self, _previous: Option<&GameResult>, latest: &GameResult) {
+        let mut segments = self.ticker.segments.lock().unwrap();
+        segments[self.index] = format_result(latest, self.color);
+        let line = segments
+            .iter()
+            .filter(|segment| !segment.is_empty())
+            .cloned()
+            .collect::<Vec<_>>()
+            .join(" │ ");
+
+        let mut stdout = io::stdout().lock();
+        let written = if self.color {
+            write!(stdout, "\r\x1b[2K{line}")
+        } else {
+            writeln!(stdout, "{line}")
+        };
+        // a closed stdout only means nobody watches the ticker
+        let _ = written.and_then(|_| stdout.flush());
+    }
+}
+
+fn format_result(result: &GameResult, color: bool) -> String {
+    let paint = |code: &str, text: String| {
+        if color {
+            format!("{code}{text}{RESET}")
+        } else {
+            text
+        }
+    };
+
+    let score = format!("{}:{}", result.my_team_score, result.opponent_team_score);
+    let (score, state) = match &result.game_time {
+        GameTime::WillBePlayed(Some((hours, minutes))) => (
+            paint(CYAN, "-:-".to_string()),
+            paint(CYAN, format!("in {hours}h{minutes:02}")),
+        ),
+        GameTime::WillBePlayed(None) => (paint(CYAN, "-:-".to_string()), String::new()),
+        GameTime::Playing(minute) => {
+            let code = match result.my_team_score.cmp(&result.opponent_team_score) {
+                std::cmp::Ordering::Greater => GREEN,
+                std::cmp::Ordering::Less => RED,
+                std::cmp::Ordering::Equal => BOLD,
+            };
+            (paint(code, score), paint(BOLD, format!("{minute}'")))
+        }
+        GameTime::BreakAfter(minute) => (
+            paint(YELLOW, score),
+            paint(YELLOW, format!("break {minute}'")),
+        ),
+        GameTime::Played => (paint(DIM, score), paint(DIM, "FT".to_string())),
+    };
+    let stale = if result.stale_since.is_some() {
+        paint(DIM, " (stale)".to_string())
+    } else {
+        String::new()
+    };
+    let chart = chart::sparkline(result, CHART_WIDTH);
+    format!(
+        "{} {score} {} {state}{stale} {chart}",
+        result.my_team, result.opponent_team
+    )
+    .trim_end()
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::sample;
+
+    #[test]
+    fn test_format_result() {
+        let result = GameResult {
+            timeline: vec![(12, 1, 0)],
+            ..sample(GameTime::Playing(34), 2, 1)
+        };
+        let plain = format_result(&result, false);
+        assert_eq!(plain, "Sparta Praha 2:1 Kometa Brno 34' ▄▄▄▅▅▅▅▅▅▅▅▅");
+        assert!(format_result(&result, true).contains(&format!("{GREEN}2:1{RESET}")));
+    }
+}
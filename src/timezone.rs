@@ -0,0 +1,86 @@
+//! Time zone of the times shown on the site, read from the page instead of assuming the one of
+//! the crawler host.
+
+use chrono_tz::Tz;
+use fantoccini::Client;
+use tracing::debug;
+
+/// Label of the time zone picker of the site.
+pub(crate) const SETTING_SELECTOR: &str = "#tzactual";
+
+/// Time zone the page shows the times in, `None` when it cannot be told.
+pub(crate) async fn detect(client: &mut Client) -> Option<Tz> {
+    // the site shows the times in the zone picked in its settings, otherwise in the one of the
+    // browser, which does not need to run on the crawler host
+    let script = format!(
+        "const setting = document.querySelector('{SETTING_SELECTOR}');
+        return setting ? setting.textContent : Intl.DateTimeFormat().resolvedOptions().timeZone;"
+    );
+    match client.execute(&script, Vec::new()).await {
+        Ok(value) => {
+            let timezone = parse(value.as_str()?);
+            debug!("time zone of the site: {timezone:?} ({value})");
+            timezone
+        }
+        Err(error) => {
+            debug!("cannot detect the time zone of the site: {error}");
+            None
+        }
+    }
+}
+
+/// Time zone of a name (`Europe/Prague`) or of an offset (`GMT+2`, `(UTC-03:00) Brasília`);
+/// the offsets come in whole hours only.
+pub(crate) fn parse(value: &str) -> Option<Tz> {
+    let value = value.trim();
+    if let Some(timezone) = value
+        .split(|c: char| c.is_whitespace() || c == '(' || c == ')')
+        .filter(|token| token.contains('/'))
+        .find_map(|token| token.parse().ok())
+    {
+        return Some(timezone);
+    }
+
+    let start = value.find("GMT").or_else(|| value.find("UTC"))? + 3;
+    let rest = value[start..].trim_start();
+    let (sign, rest) = if let Some(rest) = rest.strip_prefix('+') {
+        ('-', rest)
+    } else if let Some(rest) = rest.strip_prefix(['-', '−']) {
+        ('+', rest)
+    } else {
+        return Some(Tz::UTC);
+    };
+    let offset: String = rest
+        .chars()
+        .take_while(|c| c.is_ascii_digit() || *c == ':')
+        .collect();
+    let (hours, minutes) = offset.split_once(':').unwrap_or((&offset, "0"));
+    let hours: u32 = hours.parse().ok()?;
+    if minutes.parse::<u32>().ok()? != 0 || hours > 14 {
+        return None;
+    }
+    // the signs of the Etc zones are inverted
+    match hours {
+        0 => Some(Tz::UTC),
+        hours => format!("Etc/GMT{sign}{hours}").parse().ok(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(parse("Europe/Prague"), Some(chrono_tz::Europe::Prague));
+        assert_eq!(
+            parse("(GMT+01:00) Europe/Prague"),
+            Some(chrono_tz::Europe::Prague)
+        );
+        assert_eq!(parse("GMT+2"), Some(chrono_tz::Etc::GMTMinus2));
+        assert_eq!(parse(" UTC−03:00 "), Some(chrono_tz::Etc::GMTPlus3));
+        assert_eq!(parse("GMT"), Some(Tz::UTC));
+        assert_eq!(parse("GMT+05:30"), None);
+        assert_eq!(parse("Settings"), None);
+    }
+}
@@ -0,0 +1,115 @@
+//! Transitions of a match, `WillBePlayed → Playing ⇄ BreakAfter → Played`, and the impossible
+//! ones seen when the site briefly serves stale markup.
+
+use std::fmt;
+
+use crate::{GameResult, GameTime};
+
+#[derive(Debug, PartialEq)]
+pub enum Glitch {
+    /// The match went back to a state it has left
+    StateRegressed {
+        from: GameTime,
+        to: GameTime,
+    },
+    MinuteBackwards {
+        from: u64,
+        to: u64,
+    },
+    /// Scores of my team and of the opponent
+    ScoreDecreased {
+        from: (u64, u64),
+        to: (u64, u64),
+    },
+}
+
+impl fmt::Display for Glitch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Glitch::StateRegressed { from, to } => write!(f, "state went from {from:?} to {to:?}"),
+            Glitch::MinuteBackwards { from, to } => {
+                write!(f, "minute went backwards from {from} to {to}")
+            }
+            Glitch::ScoreDecreased { from, to } => write!(
+                f,
+                "score went down from {}:{} to {}:{}",
+                from.0, from.1, to.0, to.1
+            ),
+        }
+    }
+}
+
+/// What makes the latest result an impossible successor of the previous one of the same match.
+pub fn check(previous: &GameResult, latest: &GameResult) -> Option<Glitch> {
+    if previous.opponent_team != latest.opponent_team {
+        return None;
+    }
+    match (&previous.game_time, &latest.game_time) {
+        // a finished match followed by a scheduled one is the next match of a series
+        (GameTime::Played, GameTime::Playing(_) | GameTime::BreakAfter(_))
+        | (GameTime::Playing(_) | GameTime::BreakAfter(_), GameTime::WillBePlayed(_)) => {
+            return Some(Glitch::StateRegressed {
+                from: previous.game_time.clone(),
+                to: latest.game_time.clone(),
+            });
+        }
+        (
+            GameTime::Playing(from) | GameTime::BreakAfter(from),
+            GameTime::Playing(to) | GameTime::BreakAfter(to),
+        ) if to < from => {
+            return Some(Glitch::MinuteBackwards {
+                from: *from,
+                to: *to,
+            });
+        }
+        _ => {}
+    }
+    let from = (previous.my_team_score, previous.opponent_team_score);
+    let to = (latest.my_team_score, latest.opponent_team_score);
+    let new_match = matches!(
+        (&previous.game_time, &latest.game_time),
+        (GameTime::Played, GameTime::WillBePlayed(_))
+    );
+    (!new_match && (to.0 < from.0 || to.1 < from.1)).then_some(Glitch::ScoreDecreased { from, to })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::sample;
+
+    #[test]
+    fn test_check() {
+        let playing = sample(GameTime::Playing(25), 1, 0);
+        assert_eq!(
+            check(&playing, &sample(GameTime::BreakAfter(40), 2, 1)),
+            None
+        );
+        assert_eq!(check(&playing, &sample(GameTime::Played, 1, 0)), None);
+        assert_eq!(
+            check(&playing, &sample(GameTime::Playing(21), 1, 0)),
+            Some(Glitch::MinuteBackwards { from: 25, to: 21 })
+        );
+        assert_eq!(
+            check(&playing, &sample(GameTime::Playing(26), 0, 0)),
+            Some(Glitch::ScoreDecreased {
+                from: (1, 0),
+                to: (0, 0)
+            })
+        );
+        assert_eq!(
+            check(&playing, &sample(GameTime::WillBePlayed(None), 0, 0)),
+            Some(Glitch::StateRegressed {
+                from: GameTime::Playing(25),
+                to: GameTime::WillBePlayed(None)
+            })
+        );
+
+        let played = sample(GameTime::Played, 3, 2);
+        assert_eq!(
+            check(&played, &sample(GameTime::WillBePlayed(None), 0, 0)),
+            None
+        );
+        assert!(check(&played, &sample(GameTime::Playing(60), 3, 2)).is_some());
+    }
+}
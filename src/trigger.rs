@@ -0,0 +1,59 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tokio::time::sleep;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// File whose touching (creation or modification time change) triggers a scrape.
+pub struct TriggerFile {
+    path: PathBuf,
+    last_modified: Option<SystemTime>,
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+impl TriggerFile {
+    pub fn new(path: &Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            last_modified: modified(path),
+        }
+    }
+
+    /// Wait until the file is touched since the last call.
+    pub async fn wait(&mut self) {
+        loop {
+            sleep(POLL_INTERVAL).await;
+            let modified = modified(&self.path);
+            if modified.is_some() && modified != self.last_modified {
+                self.last_modified = modified;
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_wait() {
+        let path = std::env::temp_dir().join(format!("livesport-trigger-{}", std::process::id()));
+        let mut trigger = TriggerFile::new(&path);
+        assert!(tokio::time::timeout(Duration::from_secs(1), trigger.wait())
+            .await
+            .is_err());
+
+        fs::write(&path, "").unwrap();
+        tokio::time::timeout(Duration::from_secs(2), trigger.wait())
+            .await
+            .unwrap();
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,66 @@
+use fantoccini::{Client, Locator};
+use std::time::{Duration, Instant};
+use tokio::time::sleep;
+use url::Url;
+
+use crate::budget;
+
+/// Periodic check that the team still has a scheduled (or running) match.
+pub struct FixtureCheck {
+    interval: Duration,
+    last_check: Option<Instant>,
+}
+
+impl FixtureCheck {
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            last_check: None,
+        }
+    }
+
+    /// Whether the check is due; the first one happens right away.
+    pub fn due(&mut self) -> bool {
+        if self
+            .last_check
+            .is_some_and(|last_check| last_check.elapsed() < self.interval)
+        {
+            return false;
+        }
+        self.last_check = Some(Instant::now());
+        true
+    }
+}
+
+/// Whether the team page lists any scheduled or live match.
+pub async fn has_upcoming(client: &mut Client, url: &Url, stealth: bool) -> anyhow::Result<bool> {
+    budget::goto(client, url.as_str()).await?;
+    sleep(Duration::from_millis(500)).await;
+
+    let upcoming = !client
+        .find_all(Locator::Css(
+            ".event__match--scheduled, .event__match--live",
+        ))
+        .await?
+        .is_empty();
+    if !stealth {
+        client.goto("about:blank").await?;
+    }
+    Ok(upcoming)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_due() {
+        let mut check = FixtureCheck::new(Duration::from_secs(3600));
+        assert!(check.due());
+        assert!(!check.due());
+
+        let mut check = FixtureCheck::new(Duration::ZERO);
+        assert!(check.due());
+        assert!(check.due());
+    }
+}
@@ -0,0 +1,69 @@
+//! Sinks compiled to WebAssembly, run without any imports so they cannot reach the host.
+//!
+//! A plugin module exports its `memory`, `alloc(len: i32) -> i32` returning a buffer
+//! for the message and `on_message(ptr: i32, len: i32)` consuming the JSON message.
+
+use anyhow::Context;
+use std::path::{Path, PathBuf};
+use tracing::warn;
+use wasmtime::{Config, Engine, Instance, Memory, Module, Store, TypedFunc};
+
+use crate::plugin::{Message, MessageSink};
+
+/// Instructions a plugin may execute per message, guards against endless loops.
+const FUEL_PER_MESSAGE: u64 = 100_000_000;
+
+pub struct WasmSink {
+    path: PathBuf,
+    store: Store<()>,
+    memory: Memory,
+    alloc: TypedFunc<u32, u32>,
+    on_message: TypedFunc<(u32, u32), ()>,
+}
+
+impl WasmSink {
+    pub fn new(path: &Path) -> anyhow::Result<Self> {
+        let mut config = Config::new();
+        config.consume_fuel(true);
+        let engine = Engine::new(&config)?;
+        let module = Module::from_file(&engine, path)
+            .with_context(|| format!("cannot load plugin {}", path.display()))?;
+
+        let mut store = Store::new(&engine, ());
+        store.set_fuel(FUEL_PER_MESSAGE)?;
+        let instance = Instance::new(&mut store, &module, &[])
+            .with_context(|| format!("plugin {} must not import anything", path.display()))?;
+        let memory = instance
+            .get_memory(&mut store, "memory")
+            .context("plugin does not export its memory")?;
+        let alloc = instance.get_typed_func(&mut store, "alloc")?;
+        let on_message = instance.get_typed_func(&mut store, "on_message")?;
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            store,
+            memory,
+            alloc,
+            on_message,
+        })
+    }
+
+    fn try_send(&mut self, message: &Message) -> anyhow::Result<()> {
+        let message = serde_json::to_vec(message)?;
+        let len = u32::try_from(message.len())?;
+        self.store.set_fuel(FUEL_PER_MESSAGE)?;
+        let ptr = self.alloc.call(&mut self.store, len)?;
+        self.memory
+            .write(&mut self.store, ptr as usize, &message)
+            .context("plugin returned a buffer outside of its memory")?;
+        self.on_message.call(&mut self.store, (ptr, len))
+    }
+}
+
+impl MessageSink for WasmSink {
+    fn send(&mut self, message: &Message) {
+        if let Err(error) = self.try_send(message) {
+            warn!("plugin {} failed: {error:#}", self.path.display());
+        }
+    }
+}
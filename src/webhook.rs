@@ -0,0 +1,146 @@
+//! Sink POSTing the new and the previous result to a URL whenever the score or the state of
+//! the match changes, and the delivery shared by the chat notifiers.
+
+use reqwest::header::HeaderMap;
+use reqwest::Method;
+use serde::Serialize;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tracing::warn;
+use url::Url;
+
+use crate::events;
+use crate::observer::Observer;
+use crate::GameResult;
+
+/// First delay before a retry, doubled with every next one.
+const RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// JSON bodies POSTed in order from a background task, so that a slow receiver does not hold
+/// the scraping back.
+pub struct Poster {
+    /// Stands for the URL in the logs, the URL may contain a secret
+    name: String,
+    url: String,
+    bodies: mpsc::UnboundedSender<(String, Vec<u8>)>,
+}
+
+impl Poster {
+    pub fn new(name: &str, url: &str, retries: u32, timeout: Duration) -> anyhow::Result<Self> {
+        Self::with_headers(name, url, HeaderMap::new(), retries, timeout)
+    }
+
+    /// Poster sending the headers, e.g. the credentials, with every body.
+    pub fn with_headers(
+        name: &str,
+        url: &str,
+        headers: HeaderMap,
+        retries: u32,
+        timeout: Duration,
+    ) -> anyhow::Result<Self> {
+        Self::with_method(name, url, Method::POST, headers, retries, timeout)
+    }
+
+    /// Poster sending the bodies with another method than POST, e.g. PUT.
+    pub fn with_method(
+        name: &str,
+        url: &str,
+        method: Method,
+        headers: HeaderMap,
+        retries: u32,
+        timeout: Duration,
+    ) -> anyhow::Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .default_headers(headers)
+            .build()?;
+        let (bodies, mut receiver) = mpsc::unbounded_channel::<(String, Vec<u8>)>();
+        let label = name.to_string();
+        tokio::spawn(async move {
+            while let Some((url, body)) = receiver.recv().await {
+                deliver(&client, &method, &url, &label, body, retries).await;
+            }
+        });
+        Ok(Self {
+            name: name.to_string(),
+            url: url.to_string(),
+            bodies,
+        })
+    }
+
+    pub fn post(&self, body: &impl Serialize) {
+        self.send_to(&self.url, body);
+    }
+
+    /// Send the body to another URL than the one of the poster, e.g. one with a unique id.
+    pub fn send_to(&self, url: &str, body: &impl Serialize) {
+        match serde_json::to_vec(body) {
+            Ok(body) => {
+                if self.bodies.send((url.to_string(), body)).is_err() {
+                    warn!("{} is no longer running", self.name);
+                }
+            }
+            Err(error) => warn!("could not serialize the body for {}: {error}", self.name),
+        }
+    }
+}
+
+async fn deliver(
+    client: &reqwest::Client,
+    method: &Method,
+    url: &str,
+    name: &str,
+    body: Vec<u8>,
+    retries: u32,
+) {
+    let mut delay = RETRY_DELAY;
+    for attempt in 0..=retries {
+        let response = client
+            .request(method.clone(), url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body.clone())
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(reqwest::Error::without_url);
+        match response {
+            Ok(_) => return,
+            Err(error) if attempt < retries => {
+                warn!("{name} failed, retrying in {delay:?}: {error}");
+                tokio::time::sleep(delay).await;
+                delay *= 2;
+            }
+            Err(error) => warn!("{name} failed, giving up: {error}"),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Payload<'a> {
+    previous: Option<&'a GameResult>,
+    latest: &'a GameResult,
+}
+
+pub struct Webhook {
+    poster: Poster,
+}
+
+impl Webhook {
+    pub fn new(url: &Url, retries: u32, timeout: Duration) -> anyhow::Result<Self> {
+        Ok(Self {
+            poster: Poster::new(&format!("webhook {url}"), url.as_str(), retries, timeout)?,
+        })
+    }
+}
+
+impl Observer for Webhook {
+    fn announces(&self) -> bool {
+        true
+    }
+
+    fn on_change(&mut self, previous: Option<&GameResult>, latest: &GameResult) {
+        if events::score_or_state_changed(previous, latest) {
+            self.poster.post(&Payload { previous, latest });
+        }
+    }
+}